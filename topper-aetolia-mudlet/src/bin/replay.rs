@@ -0,0 +1,66 @@
+#![allow(warnings)]
+#[macro_use]
+extern crate lazy_static;
+#[macro_use]
+extern crate topper_derive;
+extern crate strum;
+#[macro_use]
+extern crate strum_macros;
+extern crate regex;
+#[macro_use]
+extern crate log;
+extern crate chrono;
+extern crate simplelog;
+use std::env;
+use std::sync::mpsc;
+use std::sync::mpsc::{Receiver, Sender};
+use topper_core_mudlet::topper::recorder::{read_session, replay_session};
+use topper_core_mudlet::topper::send_response;
+
+use crate::topper::battle_stats::BattleStats;
+use crate::topper::AetTopper;
+#[path = "../sect_parser/mod.rs"]
+mod sect_parser;
+#[path = "../topper/mod.rs"]
+mod topper;
+
+// Feeds a recorded session back through a fresh topper, printing each response as it was sent.
+// Point it at a copy of the database, since the replay writes to it as the session did.
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let session_path = args
+        .get(1)
+        .expect("Usage: replay <session.jsonl> [db] [triggers] [behavior_trees] [aff_stacks]");
+
+    let db_dir = args
+        .get(2)
+        .map_or("topper.db".to_string(), |string| string.to_string());
+
+    let triggers_dir = args
+        .get(3)
+        .map_or("triggers".to_string(), |string| string.to_string());
+
+    let behavior_trees_dir = args
+        .get(4)
+        .map_or("behavior_trees".to_string(), |string| string.to_string());
+
+    let stacks_dir = args
+        .get(5)
+        .map_or("aff_stacks".to_string(), |string| string.to_string());
+
+    let lines = read_session(session_path).expect("Could not read session");
+    // Nothing proxies the lines during a replay, but the telnet module still sends them.
+    let (send_lines, _receive_lines): (Sender<String>, Receiver<String>) = mpsc::channel();
+    let mut topper = AetTopper::new(
+        send_lines,
+        db_dir,
+        triggers_dir,
+        behavior_trees_dir,
+        stacks_dir,
+        None,
+    );
+    for response in replay_session::<BattleStats, _>(&mut topper, &lines).iter() {
+        send_response(response);
+    }
+}
@@ -22,7 +22,7 @@ use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
 use std::thread;
 use topper_core_mudlet::topper::telnet::proxy;
-use topper_core_mudlet::topper::Topper;
+use topper_core_mudlet::topper::{SessionRecorder, Topper};
 
 use crate::topper::battle_stats::BattleStats;
 use crate::topper::AetTopper;
@@ -52,6 +52,7 @@ fn main() {
         },
     };
     let log_name = format!("{}/{}.log", log_dir, time);
+    let session_name = format!("{}/{}.jsonl", log_dir, time);
     println!("Logging to: {:?}", Path::new(&log_dir).canonicalize());
 
     let db_dir = args
@@ -88,6 +89,10 @@ fn main() {
             stacks_dir,
             publish_dir,
         );
+        match SessionRecorder::new(session_name) {
+            Ok(recorder) => topper.session_recorder = Some(recorder),
+            Err(err) => println!("Could not record session: {:?}", err),
+        }
         topper.provide_action();
     });
     thread::spawn(|| {
@@ -1,8 +1,11 @@
 use serde::Serialize;
 use std::collections::HashMap;
+use topper_aetolia::bt::PlanExplanation;
 use topper_aetolia::classes::infiltrator::{get_hypno_stack, get_hypno_stack_name};
-use topper_aetolia::classes::{get_attack, Class, LockType};
-use topper_aetolia::curatives::gather_alerts;
+use topper_aetolia::classes::{get_class_stack, get_explained_attack, Class, LockType};
+use topper_aetolia::curatives::{
+    forecast_target_locks, gather_alerts, LockEta, LockForecastConfig,
+};
 use topper_aetolia::db::AetDatabaseModule;
 use topper_aetolia::timeline::*;
 use topper_aetolia::types::*;
@@ -11,6 +14,9 @@ use topper_core_mudlet::topper::{TopperMessage, TopperModule, TopperRequest, Top
 
 use super::db::AetMudletDatabaseModule;
 
+// Lines of the combat feed shown under the limbs.
+const FEED_LENGTH: usize = 16;
+
 #[derive(Serialize)]
 pub struct PlayerStats {
     name: String,
@@ -182,6 +188,8 @@ pub struct BattleStats {
     pub alerts: Vec<String>,
     pub target_stats: Option<PlayerStats>,
     pub plan: String,
+    pub explanation: Option<PlanExplanation>,
+    pub lock_etas: Vec<LockEta>,
     pub class_state: String,
 }
 
@@ -193,14 +201,6 @@ fn format_target_limbs(state: &AgentState) -> String {
     format!("<red>Target Limbs: [{:?}]", state.limb_damage)
 }
 
-fn format_combat_action(combat_action: &CombatAction) -> Vec<String> {
-    let lines = vec![format!(
-        "{} ={}= @ {}",
-        combat_action.caster, combat_action.skill, combat_action.target
-    )];
-    lines
-}
-
 pub fn get_battle_stats(
     timeline: &AetTimeline,
     target: &Option<String>,
@@ -226,21 +226,38 @@ pub fn get_battle_stats(
         .iter()
         .map(|alert| alert.to_string())
         .collect();
-    let mut lines_available = 16;
     lines.push(format_self_limbs(&timeline.state.borrow_me()));
     if let Some(target) = target {
         let target = timeline.state.borrow_agent(target);
         lines.push(format_target_limbs(&target));
     }
-    let plan_str = if let (Some(plan), Some(target)) = (plan, target) {
+    let (plan_str, explanation) = if let (Some(plan), Some(target)) = (plan, target) {
         if !plan.eq("") {
-            get_attack(timeline, &timeline.who_am_i(), target, &plan, Some(db))
+            let (attack, explanation) =
+                get_explained_attack(timeline, &timeline.who_am_i(), target, &plan, Some(db));
+            (attack, Some(explanation))
         } else {
-            "".to_string()
+            ("".to_string(), None)
         }
     } else {
-        "".to_string()
+        ("".to_string(), None)
     };
+    let lock_etas =
+        if let (Some(plan), Some(target)) = (plan.as_ref().filter(|plan| !plan.eq(&"")), target) {
+            get_class_stack(
+                db.get_class(&timeline.who_am_i()),
+                timeline,
+                target,
+                plan,
+                Some(db),
+            )
+            .map(|stack| {
+                forecast_target_locks(timeline, target, &stack, &LockForecastConfig::default())
+            })
+            .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
     let class_state = match my_stats.class.as_ref() {
         "Infiltrator" => format!(
             "{}: {:?}",
@@ -270,45 +287,15 @@ pub fn get_battle_stats(
         ),
         _ => "".to_string(),
     };
-    for timeslice in timeline.slices.iter().rev() {
-        for observation in timeslice.observations.iter().flatten().rev() {
-            if lines_available <= 0 {
-                break;
-            }
-            if let AetObservation::CombatAction(combat_action) = observation {
-                if let Some(who) = target {
-                    if !who.eq_ignore_ascii_case(&combat_action.target)
-                        && !who.eq_ignore_ascii_case(&combat_action.caster)
-                        && !who.eq_ignore_ascii_case(&timeline.who_am_i())
-                    {
-                        continue;
-                    }
-                }
-                let new_lines = format_combat_action(combat_action);
-                for line in new_lines.iter().rev() {
-                    if lines_available > 0 {
-                        lines.push(line.to_string());
-                        lines_available -= 1;
-                    }
-                }
-            }
-            if target.is_none() {
-                if let AetObservation::SimpleCureAction(simple_cure) = observation {
-                    lines.push(format!(
-                        "{} <= {:?}",
-                        simple_cure.caster, simple_cure.cure_type
-                    ));
-                    lines_available -= 1;
-                }
-            }
-        }
-    }
+    lines.extend(get_digest_feed(timeline, target.as_ref(), FEED_LENGTH));
     BattleStats {
         feed: lines,
         my_stats,
         target_stats,
         alerts,
         plan: plan_str,
+        explanation,
+        lock_etas,
         class_state,
     }
 }
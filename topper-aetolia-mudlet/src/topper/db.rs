@@ -14,7 +14,6 @@ use std::thread::JoinHandle;
 use std::time::{Duration, SystemTime};
 use tokio;
 use topper_aetolia::classes::{Class, VenomPlan};
-use topper_aetolia::curatives::first_aid::{parse_priority_set, FirstAidPriorities};
 use topper_aetolia::db::AetDatabaseModule;
 use topper_aetolia::defense::DEFENSE_DATABASE;
 use topper_aetolia::timeline::*;
@@ -171,19 +170,6 @@ impl AetMudletDatabaseModule {
         self.insert_json::<Vec<Hypnosis>>("hypnosis", stack_name, stack);
     }
 
-    fn set_first_aid_priorities(
-        &self,
-        who: &String,
-        priorities_name: &String,
-        priorities: FirstAidPriorities,
-    ) {
-        self.insert_json::<FirstAidPriorities>(
-            "first_aid",
-            &format!("{}_{}", who, priorities_name),
-            priorities,
-        );
-    }
-
     pub fn get_characters(&self) -> Vec<CharacterApiResponse> {
         self.db
             .open_tree("character")
@@ -430,9 +416,6 @@ impl<'s> TopperModule<'s, AetTimeSlice, BattleStats> for AetMudletDatabaseModule
                         _ => {}
                     }
                 }
-                if let Some((name, priorities)) = parse_priority_set(&event.lines) {
-                    self.set_first_aid_priorities(&siblings, &name, priorities);
-                }
                 Ok(TopperResponse::silent())
             }
             _ => Ok(TopperResponse::silent()),
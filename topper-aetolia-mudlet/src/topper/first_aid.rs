@@ -1,36 +1,168 @@
 use super::battle_stats::BattleStats;
 use super::db::AetMudletDatabaseModule;
 use super::BattleModule;
+use regex::Regex;
 use topper_aetolia::db::AetDatabaseModule;
 use topper_aetolia::timeline::AetTimeline;
 use topper_aetolia::types::*;
 use topper_aetolia::{classes::*, curatives::*, timeline::AetTimeSlice};
+use topper_core::timeline::CType;
 use topper_core_mudlet::topper::*;
 
+lazy_static! {
+    static ref USE_SET: Regex = Regex::new(r"^use (\w+)$").unwrap();
+    static ref CLASS_SET: Regex = Regex::new(r"^class (\w+) (\w+)$").unwrap();
+}
+
+// How long to wait for the game to report a swap back before giving up on it.
+const IN_FLIGHT_TIMEOUT: CType = 500;
+
+// The priority set to swap to when targeting a class, per character.
+fn get_class_set_hint(me: &String, class: &Class) -> String {
+    format!("firstaid_{}_{}", me, class.normal().to_str())
+}
+
 #[derive(Debug, Default)]
 pub struct FirstAidModule {
+    // The priorities the game last reported.
     active: FirstAid,
+    active_name: Option<String>,
+    // Sent to the game, but not yet reported back.
     in_flight: FirstAid,
+    in_flight_name: Option<String>,
+    in_flight_since: CType,
+    target_class: Option<Class>,
+}
+
+impl FirstAidModule {
+    fn apply_set(
+        &mut self,
+        me: &String,
+        set_name: &String,
+        now: CType,
+        db: &AetMudletDatabaseModule,
+    ) -> Option<String> {
+        if let Some(priorities) = db.get_first_aid_priorities(me, set_name) {
+            let mut commands = self.active.get_priority_commands(&priorities);
+            self.in_flight =
+                FirstAid::from_priorities(self.active.get_swapped_priorities(&priorities));
+            self.in_flight_name = Some(set_name.clone());
+            self.in_flight_since = now;
+            // Ask for the priorities back, so the swap can be confirmed.
+            commands.push("firstaid priority".to_string());
+            println!("Applying first aid priorities {}", set_name);
+            Some(commands.join(";;"))
+        } else {
+            println!("No first aid priorities named {} for {}", set_name, me);
+            None
+        }
+    }
+
+    fn observe_priorities(
+        &mut self,
+        me: &String,
+        name: String,
+        priorities: FirstAidPriorities,
+        db: &AetMudletDatabaseModule,
+    ) {
+        if name.eq("") {
+            self.active = FirstAid::from_priorities(priorities);
+            if self.in_flight_name.is_some()
+                && self.active.has_priorities(self.in_flight.get_priorities())
+            {
+                self.active_name = self.in_flight_name.take();
+                println!("Confirmed first aid priorities {:?}", self.active_name);
+            } else {
+                if let Some(in_flight_name) = self.in_flight_name.take() {
+                    println!("Failed to apply first aid priorities {}", in_flight_name);
+                }
+                self.active_name = None;
+            }
+        } else {
+            println!("Saved first aid priorities {} for {}", name, me);
+            db.set_first_aid_priorities(me, &name, priorities);
+        }
+    }
+
+    fn swap_for_target(
+        &mut self,
+        me: &String,
+        target: &Option<String>,
+        now: CType,
+        db: &AetMudletDatabaseModule,
+    ) -> Option<String> {
+        if self.in_flight_name.is_some() && now - self.in_flight_since > IN_FLIGHT_TIMEOUT {
+            println!(
+                "Gave up on first aid priorities {:?}",
+                self.in_flight_name.take()
+            );
+        }
+        let class = target.as_ref().and_then(|target| db.get_class(target));
+        if class == self.target_class {
+            return None;
+        }
+        self.target_class = class;
+        let set_name = class.and_then(|class| db.get_hint(&get_class_set_hint(me, &class)))?;
+        if Some(&set_name) == self.active_name.as_ref()
+            || Some(&set_name) == self.in_flight_name.as_ref()
+        {
+            None
+        } else {
+            self.apply_set(me, &set_name, now, db)
+        }
+    }
 }
 
 impl<'s> TopperModule<'s, AetTimeSlice, BattleStats> for FirstAidModule {
-    type Siblings = (&'s mut AetTimeline, &'s AetMudletDatabaseModule);
+    type Siblings = (
+        &'s mut AetTimeline,
+        &'s Option<String>,
+        &'s AetMudletDatabaseModule,
+    );
 
     fn handle_message(
         &mut self,
         message: &TopperMessage<AetTimeSlice>,
-        siblings: Self::Siblings,
+        (timeline, target, db): Self::Siblings,
     ) -> Result<TopperResponse<BattleStats>, String> {
+        let me = timeline.who_am_i();
+        let mut commands = None;
         match message {
+            TopperMessage::TimeSlice(timeslice) => {
+                if let Some((name, priorities)) = parse_priority_set(&timeslice.lines) {
+                    self.observe_priorities(&me, name, priorities, db);
+                }
+                commands = self.swap_for_target(&me, target, timeslice.time, db);
+            }
             TopperMessage::Request(TopperRequest::ModuleMsg(module, command)) => {
                 if module.eq("firstaid") {
                     if command.eq("check") {
                         println!("FirstAidModule: check {:?}", self);
+                    } else if let Some(captures) = USE_SET.captures(command) {
+                        let set_name = captures.get(1).unwrap().as_str().to_string();
+                        commands = self.apply_set(&me, &set_name, timeline.state.time, db);
+                    } else if let Some(captures) = CLASS_SET.captures(command) {
+                        let class_name = captures.get(1).unwrap().as_str();
+                        let set_name = captures.get(2).unwrap().as_str().to_string();
+                        if let Some(class) = Class::from_str(class_name) {
+                            db.insert_hint(&get_class_set_hint(&me, &class), &set_name);
+                            println!("Using {} against {}", set_name, class.to_str());
+                            // Swap on the next time slice if we are already fighting one.
+                            self.target_class = None;
+                        } else {
+                            println!("Unknown class: {}", class_name);
+                        }
+                    } else {
+                        println!("No such command: {}", command);
                     }
                 }
-                Ok(TopperResponse::silent())
             }
-            _ => Ok(TopperResponse::silent()),
+            _ => {}
+        }
+        if let Some(commands) = commands {
+            Ok(TopperResponse::passive("firstaid".to_string(), commands))
+        } else {
+            Ok(TopperResponse::silent())
         }
     }
 }
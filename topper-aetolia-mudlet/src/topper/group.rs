@@ -1,5 +1,6 @@
 use regex::Regex;
 use std::collections::HashMap;
+use topper_aetolia::non_agent::{AetTimelineCallsExt, AetTimelineRoomExt};
 use topper_aetolia::timeline::{
     for_agent, AetObservation, AetTimeSlice, AetTimeline, CombatAction,
 };
@@ -184,7 +185,27 @@ impl<'s> TopperModule<'s, AetTimeSlice, BattleStats> for GroupModule {
         let mut calls = None;
         match message {
             TopperMessage::TimeSlice(timeslice) => {
+                for call in timeline.state.get_calls_since(self.now) {
+                    if call.enemy {
+                        let caller_aggro = self.aggro.entry(call.caller.clone()).or_default();
+                        caller_aggro.last_seen = call.time;
+                    } else {
+                        let target_aggro = self.aggro.entry(call.target.clone()).or_default();
+                        target_aggro.last_seen = call.time;
+                        self.last_call = Some((call.time, call.target.clone()));
+                    }
+                }
                 self.now = timeslice.time;
+                if timeline.state.get_my_room().is_some() {
+                    // GMCP keeps the room occupancy, so trust it over what we last saw.
+                    let in_room = timeline.state.get_players_in_my_room();
+                    for (name, aggro) in self.aggro.iter_mut() {
+                        aggro.in_room = in_room.contains(name);
+                        if aggro.in_room {
+                            aggro.last_seen = self.now;
+                        }
+                    }
+                }
                 if let Some(observations) = &timeslice.observations {
                     for event in observations.iter() {
                         match event {
@@ -283,6 +304,8 @@ impl<'s> TopperModule<'s, AetTimeSlice, BattleStats> for GroupModule {
                         }
                         "check" => {
                             println!("Aggros: {:?}", self.aggro);
+                            println!("Last call: {:?}", self.last_call);
+                            println!("In room: {:?}", timeline.state.get_players_in_my_room());
                         }
                         _ => {
                             if let Some(captures) = PRIORITY.captures(command) {
@@ -5,16 +5,20 @@ use serde_json::from_str;
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, RwLock};
 use topper_aetolia::bt::{clear_behavior_trees, DEBUG_TREES};
-use topper_aetolia::classes::{clear_aff_stacks, get_attack, VenomPlan};
+use topper_aetolia::classes::{
+    clear_aff_stacks, get_explained_attack, load_curing_profiles, VenomPlan,
+};
+use topper_aetolia::curatives::{load_cure_orders, load_hidden_aff_tables, reset_cure_orders};
 use topper_aetolia::defense::DEFENSE_DATABASE;
-use topper_aetolia::non_agent::{AetNonAgent, AetTimelineRoomExt};
+use topper_aetolia::non_agent::{AetNonAgent, AetTimelinePlayersExt, AetTimelineRoomExt};
 use topper_aetolia::timeline::*;
 use topper_aetolia::types::AgentState;
+use topper_core::coverage::LineCoverage;
 use topper_core::observations;
 use topper_core::observations::{ObservationParser, BENCHMARKS};
 use topper_core_mudlet::topper::{
-    TelnetModule, TimelineModule, Topper, TopperCore, TopperHandler, TopperMessage, TopperModule,
-    TopperRequest, TopperResponse,
+    SessionRecorder, TelnetModule, TimelineModule, Topper, TopperCore, TopperHandler,
+    TopperMessage, TopperModule, TopperRequest, TopperResponse,
 };
 pub mod basher;
 pub mod battle_stats;
@@ -23,6 +27,7 @@ pub mod db;
 pub mod first_aid;
 pub mod group;
 pub mod prediction;
+pub mod self_curing;
 pub mod stacks;
 pub mod web_ui;
 use crate::topper::basher::BasherModule;
@@ -33,6 +38,7 @@ use crate::topper::stacks::initialize_load_stack_func;
 use self::battle_stats::BattleStats;
 use self::db::AetMudletDatabaseModule;
 use self::first_aid::FirstAidModule;
+use self::self_curing::SelfCuringModule;
 use self::web_ui::WebModule;
 
 pub type AetTimelineModule = TimelineModule<AetObservation, AetPrompt, AgentState, AetNonAgent>;
@@ -97,12 +103,11 @@ impl<'s> TopperModule<'s, AetTimeSlice, BattleStats> for BattleModule {
             TopperMessage::Request(request) => match request {
                 TopperRequest::Attack(strategy) => {
                     if let Some(target) = target {
-                        Ok(TopperResponse::qeb(get_attack(
-                            &timeline,
-                            me,
-                            &target,
-                            &strategy,
-                            Some(db),
+                        let (attack, explanation) =
+                            get_explained_attack(&timeline, me, &target, &strategy, Some(db));
+                        Ok(TopperResponse::qeb(attack).then(TopperResponse::passive(
+                            "explanation".to_string(),
+                            serde_json::to_string(&explanation).unwrap_or_default(),
                         )))
                     } else {
                         Ok(TopperResponse::error("No target.".into()))
@@ -118,6 +123,11 @@ impl<'s> TopperModule<'s, AetTimeSlice, BattleStats> for BattleModule {
 pub struct AetTopper {
     pub debug_mode: bool,
     triggers_dir: String,
+    // The last target GMCP gave us, so a target set by hand is not overwritten every slice.
+    gmcp_target: Option<String>,
+    // Lines no trigger matched, while coverage is on.
+    line_coverage: Option<LineCoverage>,
+    pub session_recorder: Option<SessionRecorder>,
     pub timeline_module: AetTimelineModule,
     pub core_module: TopperCore,
     pub telnet_module: TelnetModule,
@@ -125,6 +135,7 @@ pub struct AetTopper {
     pub prediction_module: PredictionModule,
     pub group_module: GroupModule,
     pub firstaid_module: FirstAidModule,
+    pub self_curing_module: SelfCuringModule,
     pub basher_module: BasherModule,
     pub battlestats_module: BattleStatsModule,
     pub database_module: Arc<RwLock<AetMudletDatabaseModule>>,
@@ -162,6 +173,9 @@ impl AetTopper {
         AetTopper {
             debug_mode: false,
             triggers_dir: triggers_dir.clone(),
+            gmcp_target: None,
+            line_coverage: None,
+            session_recorder: None,
             timeline_module: AetTimelineModule::new(),
             core_module: TopperCore::new(),
             telnet_module: TelnetModule::new(send_lines),
@@ -170,6 +184,7 @@ impl AetTopper {
             basher_module: BasherModule::new(),
             group_module: GroupModule::new(&database_module),
             firstaid_module: FirstAidModule::default(),
+            self_curing_module: SelfCuringModule::default(),
             battlestats_module: BattleStatsModule::new(),
             database_module: Arc::new(RwLock::new(database_module)),
             web_module: WebModule::new(publish_dir),
@@ -189,6 +204,10 @@ impl TopperHandler<BattleStats> for AetTopper {
         from_str(line).map_err(|error| error.to_string())
     }
 
+    fn get_recorder(&mut self) -> Option<&mut SessionRecorder> {
+        self.session_recorder.as_mut()
+    }
+
     fn handle_request_or_event(
         &mut self,
         topper_msg: &mut TopperMessage<AetTimeSlice>,
@@ -201,16 +220,13 @@ impl TopperHandler<BattleStats> for AetTopper {
                     }
                     Err(err) => println!("Could not assign database: {:?}", err),
                 }
-                let mut new_observations = self.observation_parser.observe(&slice);
+                self.observation_parser
+                    .observe_into(slice, self.line_coverage.as_mut());
                 if self.debug_mode {
-                    println!("{:?}", new_observations);
+                    println!("{:?}", slice.observations);
                     println!("{:?}", slice.gmcp);
                     println!("{:?}", self.timeline_module.timeline.state.get_my_room());
                 }
-                slice
-                    .observations
-                    .get_or_insert(Vec::new())
-                    .append(&mut new_observations);
             }
             TopperMessage::Request(TopperRequest::ModuleMsg(module, command)) => {
                 if "core".eq(module) && "debug".eq(command) {
@@ -232,14 +248,104 @@ impl TopperHandler<BattleStats> for AetTopper {
                             aet_observation_creator,
                         )
                         .map_err(|err| err.to_string())?;
+                } else if "core".eq(module) && "coverage".eq(command) {
+                    if self.line_coverage.take().is_some() {
+                        println!("Coverage off!");
+                    } else {
+                        println!("Coverage on!");
+                        self.line_coverage = Some(LineCoverage::default());
+                    }
+                } else if "core".eq(module) && command.starts_with("coverage report") {
+                    // A path writes the whole report as JSON, rather than printing the top of it.
+                    let path = command.trim_start_matches("coverage report").trim();
+                    if let Some(coverage) = &self.line_coverage {
+                        if path.is_empty() {
+                            println!("{}", coverage.format_report(25));
+                        } else {
+                            match serde_json::to_string_pretty(&coverage.report()) {
+                                Ok(report) => {
+                                    if let Err(err) = std::fs::write(path, report) {
+                                        println!("Failed to write {}: {:?}", path, err);
+                                    }
+                                }
+                                Err(err) => println!("Failed to write coverage report: {:?}", err),
+                            }
+                        }
+                    } else {
+                        println!("Coverage is off.");
+                    }
+                } else if "core".eq(module) && "coverage clear".eq(command) {
+                    if let Some(coverage) = self.line_coverage.as_mut() {
+                        coverage.clear();
+                    }
                 } else if "core".eq(module) && "reload trees".eq(command) {
                     println!("Reloading behavior trees");
                     clear_behavior_trees();
                 } else if "core".eq(module) && "reload stacks".eq(command) {
                     println!("Reloading aff stacks");
                     clear_aff_stacks();
+                } else if "core".eq(module) && command.starts_with("reload cure orders") {
+                    // The HELP CURE ORDER text, captured as JSON like docs/cure_order.json.
+                    let path = command.trim_start_matches("reload cure orders").trim();
+                    let path = if path.is_empty() {
+                        "cure_order.json"
+                    } else {
+                        path
+                    };
+                    println!("Reloading cure orders from {}", path);
+                    match std::fs::read_to_string(path) {
+                        Ok(help_json) => {
+                            if let Err(err) = load_cure_orders(&help_json) {
+                                println!("Failed to load cure orders: {}", err);
+                            }
+                        }
+                        Err(err) => println!("Failed to read {}: {:?}", path, err),
+                    }
+                } else if "core".eq(module) && "reset cure orders".eq(command) {
+                    println!("Resetting cure orders");
+                    reset_cure_orders();
+                } else if "core".eq(module) && command.starts_with("reload hidden affs") {
+                    // Per-class tables for weighing hidden afflictions, keyed by class name.
+                    let path = command.trim_start_matches("reload hidden affs").trim();
+                    let path = if path.is_empty() {
+                        "hidden_affs.json"
+                    } else {
+                        path
+                    };
+                    println!("Reloading hidden affliction tables from {}", path);
+                    match std::fs::read_to_string(path) {
+                        Ok(tables_json) => {
+                            if let Err(err) = load_hidden_aff_tables(&tables_json) {
+                                println!("Failed to load hidden affliction tables: {}", err);
+                            }
+                        }
+                        Err(err) => println!("Failed to read {}: {:?}", path, err),
+                    }
+                } else if "core".eq(module) && command.starts_with("reload curing profiles") {
+                    // Class cures and blocked abilities, keyed by class name.
+                    let path = command.trim_start_matches("reload curing profiles").trim();
+                    let path = if path.is_empty() {
+                        "curing_profiles.json"
+                    } else {
+                        path
+                    };
+                    println!("Reloading curing profiles from {}", path);
+                    match std::fs::read_to_string(path) {
+                        Ok(profiles_json) => {
+                            if let Err(err) = load_curing_profiles(&profiles_json) {
+                                println!("Failed to load curing profiles: {}", err);
+                            }
+                        }
+                        Err(err) => println!("Failed to read {}: {:?}", path, err),
+                    }
                 }
             }
+            TopperMessage::Kill => {
+                flush_learned_balances(
+                    &mut self.timeline_module.timeline.state,
+                    &*self.database_module.read().unwrap(),
+                );
+            }
             _ => {}
         }
         // We don't even do any real writes, but the signature is stuck for now. I'm not going to refactor it.
@@ -248,13 +354,18 @@ impl TopperHandler<BattleStats> for AetTopper {
             .unwrap()
             .handle_message(&topper_msg, (self.timeline_module.timeline.who_am_i()))?;
         let mut database_module = self.database_module.read().unwrap();
-        Ok(self
-            .core_module
-            .handle_message(&topper_msg, ())?
-            .then(
-                self.timeline_module
-                    .handle_message(&topper_msg, (&database_module,))?,
-            )
+        let response = self.core_module.handle_message(&topper_msg, ())?.then(
+            self.timeline_module
+                .handle_message(&topper_msg, (&database_module,))?,
+        );
+        let gmcp_target = self.timeline_module.timeline.state.get_my_target();
+        if gmcp_target != self.gmcp_target {
+            if gmcp_target.is_some() {
+                self.core_module.target = gmcp_target.clone();
+            }
+            self.gmcp_target = gmcp_target;
+        }
+        Ok(response
             .then(self.telnet_module.handle_message(&topper_msg, ())?)
             .then(self.battlestats_module.handle_message(
                 &topper_msg,
@@ -266,7 +377,15 @@ impl TopperHandler<BattleStats> for AetTopper {
             )?)
             .then(self.firstaid_module.handle_message(
                 &topper_msg,
-                (&mut self.timeline_module.timeline, &database_module),
+                (
+                    &mut self.timeline_module.timeline,
+                    &self.core_module.target,
+                    &database_module,
+                ),
+            )?)
+            .then(self.self_curing_module.handle_message(
+                &topper_msg,
+                (&self.me(), &self.timeline_module.timeline, &database_module),
             )?)
             .then(self.group_module.handle_message(
                 &topper_msg,
@@ -0,0 +1,114 @@
+use super::battle_stats::BattleStats;
+use super::db::AetMudletDatabaseModule;
+use regex::Regex;
+use std::collections::HashMap;
+use topper_aetolia::db::AetDatabaseModule;
+use topper_aetolia::timeline::AetTimeline;
+use topper_aetolia::types::*;
+use topper_aetolia::{curatives::*, timeline::AetTimeSlice};
+use topper_core::timeline::CType;
+use topper_core_mudlet::topper::*;
+
+lazy_static! {
+    static ref USE_SET: Regex = Regex::new(r"^use (\w+)$").unwrap();
+}
+
+// How long to wait on a cure we sent before trying that balance again.
+const RESEND_DELAY: CType = 50;
+
+#[derive(Debug, Default)]
+pub struct SelfCuringModule {
+    enabled: bool,
+    curing: SelfCuring,
+    // When each balance last had a cure sent, so one prompt's cures are not repeated on the next.
+    sent: HashMap<BType, CType>,
+}
+
+impl SelfCuringModule {
+    fn get_cure_commands(
+        &mut self,
+        me: &String,
+        timeline: &AetTimeline,
+        db: &AetMudletDatabaseModule,
+    ) -> Option<String> {
+        let branches = timeline.state.get_agent(me)?;
+        let now = timeline.state.time;
+        let class = db.get_class(me);
+        let commands = self
+            .curing
+            .get_cures(timeline, branches, &class)
+            .into_iter()
+            .filter(|cure| {
+                self.sent
+                    .get(&cure.balance)
+                    .map_or(true, |sent| now - sent > RESEND_DELAY)
+            })
+            .map(|cure| {
+                self.sent.insert(cure.balance, now);
+                cure.command
+            })
+            .collect::<Vec<String>>();
+        if commands.len() > 0 {
+            Some(commands.join(";;"))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'s> TopperModule<'s, AetTimeSlice, BattleStats> for SelfCuringModule {
+    type Siblings = (&'s String, &'s AetTimeline, &'s AetMudletDatabaseModule);
+
+    fn handle_message(
+        &mut self,
+        message: &TopperMessage<AetTimeSlice>,
+        (me, timeline, db): Self::Siblings,
+    ) -> Result<TopperResponse<BattleStats>, String> {
+        let mut commands = None;
+        match message {
+            TopperMessage::TimeSlice(_timeslice) => {
+                if self.enabled {
+                    commands = self.get_cure_commands(me, timeline, db);
+                }
+            }
+            TopperMessage::Request(TopperRequest::ModuleMsg(module, command)) => {
+                if module.eq("curing") {
+                    match command.as_ref() {
+                        "on" => {
+                            println!("Self curing on.");
+                            self.enabled = true;
+                            self.sent.clear();
+                        }
+                        "off" => {
+                            println!("Self curing off.");
+                            self.enabled = false;
+                        }
+                        "check" => {
+                            println!("SelfCuringModule: {:?}", self);
+                        }
+                        _ => {
+                            if let Some(captures) = USE_SET.captures(command) {
+                                let set_name = captures.get(1).unwrap().as_str().to_string();
+                                if let Some(priorities) = db.get_first_aid_priorities(me, &set_name)
+                                {
+                                    println!("Self curing with {}", set_name);
+                                    self.curing.first_aid = FirstAid::from_priorities(priorities);
+                                } else {
+                                    println!("No first aid priorities named {}", set_name);
+                                }
+                            } else {
+                                println!("No such command: {}", command);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        if let Some(commands) = commands {
+            Ok(TopperResponse::passive("cure".to_string(), commands))
+        } else {
+            Ok(TopperResponse::silent())
+        }
+    }
+}
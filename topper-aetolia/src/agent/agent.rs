@@ -1,6 +1,6 @@
 use super::*;
 use crate::classes::Class;
-use crate::curatives::statics::RESTORE_CURE_ORDERS;
+use crate::curatives::cure_orders;
 use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
@@ -454,7 +454,7 @@ impl AgentState {
             BType::Induce => self
                 .check_if_bard(&|bard| bard.induce_ready())
                 .unwrap_or(false),
-            _ => self.balances[balance as usize].is_active(),
+            _ => !self.balances[balance as usize].is_active(),
         }
     }
 
@@ -813,7 +813,8 @@ impl AgentState {
     }
 
     pub fn get_restore_cure(&self, limb: LType) -> Option<FType> {
-        let cure_order = RESTORE_CURE_ORDERS.get(&limb).unwrap();
+        let cure_orders = cure_orders();
+        let cure_order = cure_orders.restore_order(limb).unwrap();
         for cure in cure_order {
             match cure {
                 FType::LeftLegAmputated
@@ -1030,6 +1031,24 @@ impl AgentState {
         }
     }
 
+    pub fn assume_sentinel<R>(&mut self, action: &Fn(&mut SentinelClassState) -> R) -> R {
+        if let ClassState::Sentinel(sentinel) = &mut self.class_state {
+            action(sentinel)
+        } else {
+            self.class_state = ClassState::Sentinel(SentinelClassState::default());
+            self.assume_sentinel(action)
+        }
+    }
+
+    pub fn assume_shapeshifter<R>(&mut self, action: &Fn(&mut HowlingState) -> R) -> R {
+        if let ClassState::Shifter(howling) = &mut self.class_state {
+            action(howling)
+        } else {
+            self.class_state = ClassState::Shifter(HowlingState::default());
+            self.assume_shapeshifter(action)
+        }
+    }
+
     pub fn get_predator_stance(&self) -> KnifeStance {
         if let ClassState::Predator(predator) = &self.class_state {
             predator.stance
@@ -1072,4 +1091,17 @@ mod tests {
         assert!(!state.will_be_rebounding(0.5));
         assert!(state.will_be_rebounding(1.5));
     }
+
+    #[test]
+    fn test_balanced() {
+        let mut state = AgentState::default();
+        assert!(state.balanced(BType::Balance));
+
+        state.set_balance(BType::Balance, 2.);
+        assert!(!state.balanced(BType::Balance));
+        state.wait(100);
+        assert!(!state.balanced(BType::Balance));
+        state.wait(100);
+        assert!(state.balanced(BType::Balance));
+    }
 }
@@ -142,7 +142,7 @@ impl Timer {
 }
 
 // Balances
-#[derive(Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy, TryFromPrimitive)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy, TryFromPrimitive)]
 #[repr(usize)]
 pub enum BType {
     // Actions
@@ -625,6 +625,10 @@ impl FType {
         self >= &FType::Sadness
     }
 
+    pub fn is_defence(&self) -> bool {
+        self >= &FType::Shielded && self < &FType::Manabarbs
+    }
+
     pub fn from_name(aff_name: &String) -> Option<FType> {
         let pretty = aff_name
             .split(|c| c == ' ' || c == '_' || c == '-')
@@ -654,7 +658,7 @@ impl FType {
                 word.push_str(&letter.to_lowercase().to_string());
             } else if letter.is_uppercase() {
                 words.push(word.clone());
-                word = String::from("");
+                word = letter.to_lowercase().to_string();
             } else {
                 word.push_str(&letter.to_string());
             }
@@ -899,6 +903,10 @@ impl FlagSet {
     pub fn aff_iter<'s>(&'s self) -> FlagSetIterator<'s> {
         FlagSetIterator::new(self, &|ftype: FType| ftype.is_affliction())
     }
+
+    pub fn def_iter<'s>(&'s self) -> FlagSetIterator<'s> {
+        FlagSetIterator::new(self, &|ftype: FType| ftype.is_defence())
+    }
 }
 
 impl Default for FlagSet {
@@ -1294,3 +1302,18 @@ impl Default for Elevation {
         Elevation::Ground
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_name() {
+        assert_eq!(FType::Asthma.to_name(), "asthma");
+        assert_eq!(FType::LeftArmCrippled.to_name(), "left_arm_crippled");
+        assert_eq!(
+            FType::from_name(&FType::LeftArmCrippled.to_name()),
+            Some(FType::LeftArmCrippled)
+        );
+    }
+}
@@ -47,6 +47,10 @@ impl HiddenState {
     pub fn clear_unknown(&mut self) {
         self.unknown = 0;
     }
+    pub fn clear(&mut self) {
+        self.unknown = 0;
+        self.guessed.clear();
+    }
     pub fn add_guess(&mut self, flag: FType) -> bool {
         if self.guessed.insert(flag) {
             self.unknown = self.unknown - 1;
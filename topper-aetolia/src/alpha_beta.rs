@@ -1,7 +1,9 @@
-use crate::classes::Class;
+use crate::classes::{Class, LockType};
 use crate::db::AetDatabaseModule;
 use crate::observables::ActionPlan;
 use crate::timeline::{AetObservation, AetPrompt, AetTimeSlice, AetTimeline};
+use crate::types::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use topper_core::timeline::db::DatabaseModule;
 use topper_core::timeline::BaseTimeline;
@@ -18,14 +20,166 @@ pub trait ActionPlanner {
     ) -> ActionPlan;
 }
 
-struct DuelSimulation<M: ActionPlanner, T: ActionPlanner> {
-    timeline: AetTimeline,
-    duelists: (Duelist<M>, Duelist<T>),
+// Scores a timeline from the perspective of `me` fighting `target`. Higher is better for `me`.
+pub trait DuelEvaluator {
+    fn evaluate(&self, timeline: &AetTimeline, me: &String, target: &String) -> f32;
+
+    // The least and greatest scores `evaluate` can give. Chance nodes can only be cut off early
+    // when these are finite.
+    fn bounds(&self) -> (f32, f32) {
+        (std::f32::NEG_INFINITY, std::f32::INFINITY)
+    }
+}
+
+impl<F: Fn(&AetTimeline, &String, &String) -> f32> DuelEvaluator for F {
+    fn evaluate(&self, timeline: &AetTimeline, me: &String, target: &String) -> f32 {
+        self(timeline, me, target)
+    }
 }
 
-struct Duelist<P: ActionPlanner> {
-    name: String,
-    action_planner: P,
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PressureEvaluator {
+    pub aff_weight: f32,
+    pub lock_weight: f32,
+    pub limb_weight: f32,
+    pub health_weight: f32,
+    pub locks: Vec<LockType>,
+}
+
+impl Default for PressureEvaluator {
+    fn default() -> Self {
+        PressureEvaluator {
+            aff_weight: 1.0,
+            lock_weight: 3.0,
+            limb_weight: 2.0,
+            health_weight: 10.0,
+            locks: vec![LockType::Soft, LockType::Buffered, LockType::Hard],
+        }
+    }
+}
+
+impl PressureEvaluator {
+    // How much trouble a single branch of an agent is in.
+    pub fn pressure(&self, agent: &AgentState) -> f32 {
+        let lock_progress = self
+            .locks
+            .iter()
+            .map(|lock| lock.affs().len() - lock.affs_to_lock(agent))
+            .max()
+            .unwrap_or(0);
+        let limbs = agent.get_limbs_state();
+        let limb_damage = [
+            limbs.head.damage,
+            limbs.torso.damage,
+            limbs.left_arm.damage,
+            limbs.right_arm.damage,
+            limbs.left_leg.damage,
+            limbs.right_leg.damage,
+        ]
+        .iter()
+        .map(|damage| damage / 100.0)
+        .sum::<f32>();
+        let health_lost = if agent.get_max_stat(SType::Health) > 0 {
+            1.0 - agent.get_health_percent().min(1.0)
+        } else {
+            0.0
+        };
+        self.aff_weight * agent.aff_count() as f32
+            + self.lock_weight * lock_progress as f32
+            + self.limb_weight * limb_damage
+            + self.health_weight * health_lost
+    }
+
+    // The most pressure any single branch can be under.
+    pub fn max_pressure(&self) -> f32 {
+        let most_lock_affs = self
+            .locks
+            .iter()
+            .map(|lock| lock.affs().len())
+            .max()
+            .unwrap_or(0);
+        self.aff_weight * FType::SIZE as usize as f32
+            + self.lock_weight * most_lock_affs as f32
+            + self.limb_weight * 6.0
+            + self.health_weight
+    }
+
+    // Pressure averaged across every branch we are tracking for the agent.
+    pub fn expected_pressure(&self, timeline: &AetTimeline, who: &String) -> f32 {
+        match timeline.state.get_agent(who) {
+            Some(branches) if branches.len() > 0 => {
+                branches
+                    .iter()
+                    .map(|branch| self.pressure(branch))
+                    .sum::<f32>()
+                    / branches.len() as f32
+            }
+            _ => self.pressure(&timeline.state.borrow_agent(who)),
+        }
+    }
+}
+
+impl DuelEvaluator for PressureEvaluator {
+    fn evaluate(&self, timeline: &AetTimeline, me: &String, target: &String) -> f32 {
+        self.expected_pressure(timeline, target) - self.expected_pressure(timeline, me)
+    }
+
+    fn bounds(&self) -> (f32, f32) {
+        let max_pressure = self.max_pressure();
+        (-max_pressure, max_pressure)
+    }
+}
+
+pub struct DuelSimulation<M: ActionPlanner, T: ActionPlanner> {
+    pub timeline: AetTimeline,
+    pub duelists: (Duelist<M>, Duelist<T>),
+}
+
+pub struct Duelist<P: ActionPlanner> {
+    pub name: String,
+    pub action_planner: P,
+}
+
+impl<P: ActionPlanner> Duelist<P> {
+    pub fn new(name: &str, action_planner: P) -> Self {
+        Duelist {
+            name: name.to_string(),
+            action_planner,
+        }
+    }
+
+    // Every way the next action of a strategy might play out, with relative weights.
+    fn get_outcomes<DB: AetDatabaseModule + DatabaseModule>(
+        &self,
+        timeline: &AetTimeline,
+        target: &String,
+        strategy: &str,
+        db: Option<&DB>,
+    ) -> Vec<(AetTimeline, u32)> {
+        let action_plan = self
+            .action_planner
+            .get_plan(timeline, &self.name, target, strategy, db);
+        action_plan
+            .get_time_slices(timeline)
+            .into_iter()
+            .filter_map(|(slice, weight)| {
+                let mut new_timeline = timeline.branch();
+                <AetTimeline as BaseTimeline<AetObservation, AetPrompt, DB>>::push_time_slice(
+                    &mut new_timeline,
+                    slice,
+                    db,
+                )
+                .ok()
+                .map(|_| (new_timeline, weight))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyScore {
+    pub strategy: &'static str,
+    pub score: f32,
 }
 
 struct SimulationIterator<'s, AP: ActionPlanner, DB: AetDatabaseModule> {
@@ -84,4 +238,181 @@ impl<'s, AP: ActionPlanner, DB: AetDatabaseModule + DatabaseModule> Iterator
     }
 }
 
-impl<M: ActionPlanner, T: ActionPlanner> DuelSimulation<M, T> {}
+impl<M: ActionPlanner, T: ActionPlanner> DuelSimulation<M, T> {
+    pub fn new(timeline: AetTimeline, first: Duelist<M>, second: Duelist<T>) -> Self {
+        DuelSimulation {
+            timeline: timeline.branch(),
+            duelists: (first, second),
+        }
+    }
+
+    // Scores each of the first duelist's strategies, looking `depth` balance cycles ahead.
+    // A balance cycle is one action from each duelist.
+    pub fn score_strategies<DB: AetDatabaseModule + DatabaseModule>(
+        &self,
+        depth: usize,
+        evaluator: &impl DuelEvaluator,
+        db: Option<&DB>,
+    ) -> Vec<StrategyScore> {
+        let (first, second) = &self.duelists;
+        let plies = depth.max(1) * 2 - 1;
+        first
+            .action_planner
+            .get_strategies()
+            .iter()
+            .filter_map(|strategy| {
+                let outcomes = first.get_outcomes(&self.timeline, &second.name, strategy, db);
+                if outcomes.is_empty() {
+                    None
+                } else {
+                    Some(StrategyScore {
+                        strategy,
+                        // Every strategy is scored exactly, so none of them is searched with a
+                        // narrowed window.
+                        score: self.expected_value(
+                            outcomes,
+                            plies,
+                            false,
+                            std::f32::NEG_INFINITY,
+                            std::f32::INFINITY,
+                            evaluator,
+                            db,
+                        ),
+                    })
+                }
+            })
+            .collect()
+    }
+
+    pub fn recommend_strategy<DB: AetDatabaseModule + DatabaseModule>(
+        &self,
+        depth: usize,
+        evaluator: &impl DuelEvaluator,
+        db: Option<&DB>,
+    ) -> Option<StrategyScore> {
+        self.score_strategies(depth, evaluator, db)
+            .into_iter()
+            .fold(None, |best: Option<StrategyScore>, next| match best {
+                Some(best) if best.score >= next.score => Some(best),
+                _ => Some(next),
+            })
+    }
+
+    // Chance node, pruned Star1 style. Each outcome is searched with the window it would need to
+    // move the expectation out of (alpha, beta), assuming the outcomes not yet searched score
+    // anywhere within the evaluator's bounds. Once that window is missed, the rest are skipped
+    // and a bound on the expectation is returned instead.
+    fn expected_value<DB: AetDatabaseModule + DatabaseModule>(
+        &self,
+        outcomes: Vec<(AetTimeline, u32)>,
+        plies: usize,
+        maximizing: bool,
+        alpha: f32,
+        beta: f32,
+        evaluator: &impl DuelEvaluator,
+        db: Option<&DB>,
+    ) -> f32 {
+        let (lower, upper) = evaluator.bounds();
+        let total_weight: u32 = outcomes.iter().map(|(_, weight)| weight).sum();
+        let outcome_count = outcomes.len() as f32;
+        let probabilities: Vec<f32> = outcomes
+            .iter()
+            .map(|(_, weight)| {
+                if total_weight > 0 {
+                    *weight as f32 / total_weight as f32
+                } else {
+                    1.0 / outcome_count
+                }
+            })
+            .collect();
+        let mut remaining: f32 = probabilities.iter().sum();
+        let mut expectation = 0.0;
+        for ((timeline, _weight), probability) in outcomes.iter().zip(probabilities.into_iter()) {
+            remaining -= probability;
+            if probability <= 0.0 {
+                continue;
+            }
+            // Whatever the unsearched outcomes might still add, at worst and at best.
+            let (rest_lower, rest_upper) = if remaining > 0.0 {
+                (remaining * lower, remaining * upper)
+            } else {
+                (0.0, 0.0)
+            };
+            let child_alpha = ((alpha - expectation - rest_upper) / probability).max(lower);
+            let child_beta = ((beta - expectation - rest_lower) / probability).min(upper);
+            let value = self.search(
+                timeline,
+                plies,
+                maximizing,
+                child_alpha,
+                child_beta,
+                evaluator,
+                db,
+            );
+            expectation += probability * value;
+            if value <= child_alpha {
+                return expectation + rest_upper;
+            } else if value >= child_beta {
+                return expectation + rest_lower;
+            }
+        }
+        expectation
+    }
+
+    // Max node for the first duelist, min node for the second. A duelist with no
+    // simulated action passes the turn.
+    fn search<DB: AetDatabaseModule + DatabaseModule>(
+        &self,
+        timeline: &AetTimeline,
+        plies: usize,
+        maximizing: bool,
+        mut alpha: f32,
+        mut beta: f32,
+        evaluator: &impl DuelEvaluator,
+        db: Option<&DB>,
+    ) -> f32 {
+        let (first, second) = &self.duelists;
+        if plies == 0 {
+            return evaluator.evaluate(timeline, &first.name, &second.name);
+        }
+        let strategies = if maximizing {
+            first.action_planner.get_strategies()
+        } else {
+            second.action_planner.get_strategies()
+        };
+        let mut best = None;
+        for strategy in strategies.iter() {
+            let outcomes = if maximizing {
+                first.get_outcomes(timeline, &second.name, strategy, db)
+            } else {
+                second.get_outcomes(timeline, &first.name, strategy, db)
+            };
+            if outcomes.is_empty() {
+                continue;
+            }
+            let value =
+                self.expected_value(outcomes, plies - 1, !maximizing, alpha, beta, evaluator, db);
+            if maximizing {
+                best = Some(best.map_or(value, |best: f32| best.max(value)));
+                alpha = alpha.max(value);
+            } else {
+                best = Some(best.map_or(value, |best: f32| best.min(value)));
+                beta = beta.min(value);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        best.unwrap_or_else(|| {
+            self.search(timeline, plies - 1, !maximizing, alpha, beta, evaluator, db)
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/duel_fixtures.rs"]
+pub mod duel_fixtures;
+
+#[cfg(test)]
+#[path = "./tests/alpha_beta_tests.rs"]
+mod alpha_beta_tests;
@@ -0,0 +1,471 @@
+use crate::alpha_beta::ActionPlanner;
+use crate::classes::{
+    get_class_action_plan, has_behavior_trees, Class, LockType, VenomPlan, LOADED_VENOM_PLANS,
+};
+use crate::curatives::{FirstAid, FirstAidAction};
+use crate::db::AetDatabaseModule;
+use crate::observables::{ActionPlan, ActiveTransition, ProbableEvent};
+use crate::timeline::*;
+use crate::types::*;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use topper_core::timeline::db::MemoryDatabaseModule;
+use topper_core::timeline::BALANCE_SCALE;
+
+// How far the clock advances between decisions, in centiseconds.
+const TICK: CType = 10;
+// Idle turns, and actions the handlers do not put anyone off balance for, still take some time.
+const UNTRACKED_ACTION_BALANCE: f32 = 1.0;
+
+// Plans attacks with one of a class's behavior trees, which is its only strategy.
+pub struct BehaviorTreePlanner {
+    pub class: Class,
+    // Leaked once, so the tree can be offered to searches like any other strategy.
+    strategies: &'static [&'static str],
+    // Worked from in place of the tree's usual stack, for this planner alone.
+    stack: Option<Vec<VenomPlan>>,
+}
+
+impl BehaviorTreePlanner {
+    pub fn new(class: Class, tree: &str, stack: Option<Vec<VenomPlan>>) -> Result<Self, String> {
+        if !has_behavior_trees(class) {
+            return Err(format!(
+                "{} has no behavior trees to plan with.",
+                class.to_str()
+            ));
+        }
+        let tree: &'static str = Box::leak(tree.to_string().into_boxed_str());
+        Ok(BehaviorTreePlanner {
+            class,
+            strategies: Box::leak(vec![tree].into_boxed_slice()),
+            stack,
+        })
+    }
+
+    // Stacks are found by strategy name, so ours is swapped in while we plan and the old one,
+    // perhaps the other duelist's, is put back after.
+    fn with_stack<T>(&self, strategy: &str, plan: impl FnOnce() -> T) -> T {
+        let stack = match &self.stack {
+            Some(stack) => stack,
+            None => return plan(),
+        };
+        let key = format!(
+            "{}_{}",
+            self.class.normal().to_str().to_lowercase(),
+            strategy
+        );
+        let previous = LOADED_VENOM_PLANS
+            .write()
+            .unwrap()
+            .insert(key.clone(), Some(stack.clone()));
+        let result = plan();
+        let mut stacks = LOADED_VENOM_PLANS.write().unwrap();
+        match previous {
+            Some(previous) => stacks.insert(key, previous),
+            None => stacks.remove(&key),
+        };
+        result
+    }
+}
+
+impl ActionPlanner for BehaviorTreePlanner {
+    fn get_strategies(&self) -> &'static [&'static str] {
+        self.strategies
+    }
+    fn get_plan(
+        &self,
+        timeline: &AetTimeline,
+        actor: &String,
+        target: &String,
+        strategy: &str,
+        db: Option<&impl AetDatabaseModule>,
+    ) -> ActionPlan {
+        self.with_stack(strategy, || {
+            get_class_action_plan(
+                Some(self.class.normal()),
+                timeline,
+                actor,
+                target,
+                &strategy.to_string(),
+                db,
+            )
+            .unwrap_or_else(|| ActionPlan::new(actor))
+        })
+    }
+}
+
+pub struct Combatant<P: ActionPlanner> {
+    pub name: String,
+    pub class: Class,
+    pub planner: P,
+    pub strategy: String,
+    pub first_aid: FirstAid,
+}
+
+impl<P: ActionPlanner> Combatant<P> {
+    pub fn new(name: &str, class: Class, planner: P, strategy: &str) -> Self {
+        Combatant {
+            name: name.to_string(),
+            class,
+            planner,
+            strategy: strategy.to_string(),
+            first_aid: FirstAid::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArenaConfig {
+    pub fights: usize,
+    pub seed: u64,
+    // In seconds.
+    pub time_limit: f32,
+    pub lock: LockType,
+    // A lock held this long, in seconds, counts as a kill.
+    pub lock_kill_time: f32,
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        ArenaConfig {
+            fights: 1000,
+            seed: 0,
+            time_limit: 120.0,
+            lock: LockType::Hard,
+            lock_kill_time: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct FightResult {
+    pub winner: Option<usize>,
+    // When each combatant first locked their opponent, in seconds.
+    pub time_to_lock: [Option<f32>; 2],
+    // When each combatant killed their opponent, in seconds.
+    pub time_to_kill: [Option<f32>; 2],
+    // The average number of afflictions each combatant carried.
+    pub average_affs: [f32; 2],
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct CombatantReport {
+    pub name: String,
+    pub wins: usize,
+    pub win_rate: f32,
+    pub locks: usize,
+    pub average_time_to_lock: Option<f32>,
+    pub kills: usize,
+    pub average_time_to_kill: Option<f32>,
+    pub average_affs: f32,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ArenaReport {
+    pub fights: usize,
+    pub draws: usize,
+    pub combatants: Vec<CombatantReport>,
+}
+
+fn average(values: &Vec<f32>) -> Option<f32> {
+    if values.len() > 0 {
+        Some(values.iter().sum::<f32>() / values.len() as f32)
+    } else {
+        None
+    }
+}
+
+impl ArenaReport {
+    pub fn new(names: [&String; 2], results: &Vec<FightResult>) -> Self {
+        let fights = results.len();
+        let combatants = (0..2)
+            .map(|index| {
+                let wins = results
+                    .iter()
+                    .filter(|result| result.winner == Some(index))
+                    .count();
+                let locks: Vec<f32> = results
+                    .iter()
+                    .filter_map(|result| result.time_to_lock[index])
+                    .collect();
+                let kills: Vec<f32> = results
+                    .iter()
+                    .filter_map(|result| result.time_to_kill[index])
+                    .collect();
+                CombatantReport {
+                    name: names[index].clone(),
+                    wins,
+                    win_rate: if fights > 0 {
+                        wins as f32 / fights as f32
+                    } else {
+                        0.0
+                    },
+                    locks: locks.len(),
+                    average_time_to_lock: average(&locks),
+                    kills: kills.len(),
+                    average_time_to_kill: average(&kills),
+                    average_affs: average(
+                        &results
+                            .iter()
+                            .map(|result| result.average_affs[index])
+                            .collect(),
+                    )
+                    .unwrap_or_default(),
+                }
+            })
+            .collect();
+        ArenaReport {
+            fights,
+            draws: results
+                .iter()
+                .filter(|result| result.winner.is_none())
+                .count(),
+            combatants,
+        }
+    }
+}
+
+fn format_seconds(seconds: Option<f32>) -> String {
+    seconds
+        .map(|seconds| format!("{:.1}s", seconds))
+        .unwrap_or("-".to_string())
+}
+
+impl fmt::Display for ArenaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} fights, {} draws", self.fights, self.draws)?;
+        for combatant in self.combatants.iter() {
+            writeln!(
+                f,
+                "{}: {:.1}% wins, {} locks (avg {}), {} kills (avg {}), {:.2} affs carried",
+                combatant.name,
+                combatant.win_rate * 100.0,
+                combatant.locks,
+                format_seconds(combatant.average_time_to_lock),
+                combatant.kills,
+                format_seconds(combatant.average_time_to_kill),
+                combatant.average_affs,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn choose<T>(rng: &mut StdRng, mut options: Vec<(T, u32)>) -> Option<T> {
+    let weights: Vec<u32> = options.iter().map(|(_, weight)| *weight).collect();
+    match WeightedIndex::new(&weights) {
+        Ok(distribution) => Some(options.swap_remove(distribution.sample(rng)).0),
+        Err(_) => None,
+    }
+}
+
+// Simulations know the truth, so any uncertainty the handlers leave behind is settled by chance.
+pub(crate) fn collapse_branches(timeline: &mut AetTimeline, who: &String, rng: &mut StdRng) {
+    if let Some(branches) = timeline.state.agent_states.get_mut(who) {
+        if branches.len() > 1 {
+            let kept = branches.swap_remove(rng.gen_range(0, branches.len()));
+            *branches = vec![kept];
+        }
+    }
+}
+
+fn cure_balance(cure: &FirstAidAction) -> Option<BType> {
+    match cure {
+        FirstAidAction::Simple(action) => match action.cure_type {
+            SimpleCure::Pill(_) => Some(BType::Pill),
+            SimpleCure::Salve(_, _) => Some(BType::Salve),
+            SimpleCure::Smoke(_) => Some(BType::Smoke),
+        },
+        FirstAidAction::Focus(_) => Some(BType::Focus),
+        FirstAidAction::Tree(_) => Some(BType::Tree),
+        FirstAidAction::Wait => None,
+    }
+}
+
+// Runs seeded fights between two combatants, each planning with their own trees and stacks.
+pub struct Arena<A: ActionPlanner, B: ActionPlanner> {
+    pub config: ArenaConfig,
+    pub first: Combatant<A>,
+    pub second: Combatant<B>,
+    db: MemoryDatabaseModule,
+}
+
+impl<A: ActionPlanner, B: ActionPlanner> Arena<A, B> {
+    pub fn new(config: ArenaConfig, first: Combatant<A>, second: Combatant<B>) -> Self {
+        let db = MemoryDatabaseModule::new();
+        db.set_class(&first.name, first.class);
+        db.set_class(&second.name, second.class);
+        Arena {
+            config,
+            first,
+            second,
+            db,
+        }
+    }
+
+    fn names(&self) -> [&String; 2] {
+        [&self.first.name, &self.second.name]
+    }
+
+    pub fn run(&self) -> ArenaReport {
+        let results = (0..self.config.fights)
+            .map(|fight| self.fight(self.config.seed.wrapping_add(fight as u64)))
+            .collect();
+        ArenaReport::new(self.names(), &results)
+    }
+
+    pub fn fight(&self, seed: u64) -> FightResult {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut timeline = AetTimeline::new();
+        let names = self.names();
+        for (index, name) in names.iter().enumerate() {
+            let class = if index == 0 {
+                self.first.class
+            } else {
+                self.second.class
+            };
+            timeline.state.for_agent(name, &move |me: &mut AgentState| {
+                me.class_state
+                    .initialize_for_normalized_class(class.normal());
+            });
+        }
+        let time_limit = (self.config.time_limit * BALANCE_SCALE) as CType;
+        let lock_kill_time = (self.config.lock_kill_time * BALANCE_SCALE) as CType;
+        let mut result = FightResult::default();
+        let mut locked_since: [Option<CType>; 2] = [None, None];
+        let mut aff_samples = [0usize; 2];
+        let mut samples = 0;
+        while timeline.state.time < time_limit && result.winner.is_none() {
+            // Neither side reliably gets the first move within a tick.
+            let order = if rng.gen_bool(0.5) { [0, 1] } else { [1, 0] };
+            for index in order.iter() {
+                self.cure(&mut timeline, *index, &mut rng);
+            }
+            for index in order.iter() {
+                self.attack(&mut timeline, *index, &mut rng);
+            }
+            let now = timeline.state.time;
+            samples += 1;
+            for index in 0..2 {
+                let attacker = index;
+                let defender = &names[1 - index];
+                let state = timeline.state.borrow_agent(defender);
+                aff_samples[1 - index] += state.flags.aff_iter().count();
+                if self.config.lock.affs_to_lock(&state) == 0 {
+                    if result.time_to_lock[attacker].is_none() {
+                        result.time_to_lock[attacker] = Some(now as f32 / BALANCE_SCALE);
+                    }
+                    locked_since[attacker] = locked_since[attacker].or(Some(now));
+                } else {
+                    locked_since[attacker] = None;
+                }
+                let lock_held = locked_since[attacker]
+                    .map(|since| now - since >= lock_kill_time)
+                    .unwrap_or(false);
+                if result.winner.is_none() && (state.get_stat(SType::Health) <= 0 || lock_held) {
+                    result.winner = Some(attacker);
+                    result.time_to_kill[attacker] = Some(now as f32 / BALANCE_SCALE);
+                }
+            }
+            timeline.update_time(now + TICK);
+        }
+        for index in 0..2 {
+            result.average_affs[index] = aff_samples[index] as f32 / samples.max(1) as f32;
+        }
+        result
+    }
+
+    fn push_events(
+        &self,
+        timeline: &mut AetTimeline,
+        events: Vec<ProbableEvent>,
+        observer: &String,
+        rng: &mut StdRng,
+    ) {
+        let options = events
+            .into_iter()
+            .map(|event| {
+                let weight = event.get_weight();
+                (event, weight)
+            })
+            .collect();
+        if let Some(event) = choose(rng, options) {
+            let mut slice = simulation_slice(event.get_observations().clone(), timeline.state.time);
+            slice.me = observer.clone();
+            timeline.push_time_slice(slice, Some(&self.db));
+        }
+    }
+
+    fn cure(&self, timeline: &mut AetTimeline, index: usize, rng: &mut StdRng) {
+        let (me, first_aid) = if index == 0 {
+            (&self.first.name, &self.first.first_aid)
+        } else {
+            (&self.second.name, &self.second.first_aid)
+        };
+        let opponent = self.names()[1 - index];
+        let state = timeline.state.borrow_agent(me);
+        if let Some((_aff, cure)) = first_aid.get_next_cure(me, &state) {
+            if let Some(balance) = cure_balance(&cure) {
+                if state.balanced(balance) {
+                    let events = cure.simulate(timeline);
+                    // Watching from across the room, cures are inferred from the cure orders.
+                    self.push_events(timeline, events, opponent, rng);
+                    collapse_branches(timeline, me, rng);
+                }
+            }
+        }
+    }
+
+    fn attack(&self, timeline: &mut AetTimeline, index: usize, rng: &mut StdRng) {
+        let names = self.names();
+        let (me, target) = (names[index], names[1 - index]);
+        let state = timeline.state.borrow_agent(me);
+        if !state.balanced(BType::Balance) || !state.balanced(BType::Equil) {
+            return;
+        }
+        let mut perspective = timeline.branch();
+        perspective.state.me = me.clone();
+        let plan = if index == 0 {
+            self.first.planner.get_plan(
+                &perspective,
+                me,
+                target,
+                &self.first.strategy,
+                Some(&self.db),
+            )
+        } else {
+            self.second.planner.get_plan(
+                &perspective,
+                me,
+                target,
+                &self.second.strategy,
+                Some(&self.db),
+            )
+        };
+        let now = timeline.state.time;
+        let options = plan
+            .get_time_slices(timeline)
+            .into_iter()
+            .filter(|(slice, _weight)| slice.time <= now)
+            .collect();
+        if let Some(slice) = choose(rng, options) {
+            timeline.push_time_slice(slice, Some(&self.db));
+            collapse_branches(timeline, target, rng);
+            collapse_branches(timeline, me, rng);
+        }
+        let state = timeline.state.borrow_agent(me);
+        if state.balanced(BType::Balance) && state.balanced(BType::Equil) {
+            timeline.state.for_agent(me, &|me: &mut AgentState| {
+                me.set_balance(BType::Balance, UNTRACKED_ACTION_BALANCE);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/arena_tests.rs"]
+mod arena_tests;
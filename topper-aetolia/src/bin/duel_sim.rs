@@ -0,0 +1,102 @@
+use serde::Deserialize;
+use std::{env, fs, process};
+use topper_aetolia::{
+    arena::{Arena, ArenaConfig, BehaviorTreePlanner, Combatant},
+    bt::LOAD_TREE_FUNC,
+    classes::{Class, VenomPlan, LOAD_STACK_FUNC},
+};
+
+// Usage: duel_sim <config.json> [--json]
+//
+// {
+//     "trees_dir": "behavior_trees",
+//     "stacks_dir": "stacks",
+//     "arena": { "fights": 5000, "seed": 1, "time_limit": 120.0, "lock": "Hard" },
+//     "combatants": [
+//         { "name": "Seurimas", "class": "Infiltrator", "tree": "aggro" },
+//         { "name": "Kaiza", "class": "Bard", "tree": "base", "stack": "bard/aggro.json" }
+//     ]
+// }
+
+#[derive(Deserialize)]
+struct CombatantConfig {
+    name: String,
+    class: Class,
+    tree: String,
+    // A stack file to use in place of the tree's usual stack.
+    stack: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SimulatorConfig {
+    trees_dir: String,
+    stacks_dir: String,
+    #[serde(default)]
+    arena: ArenaConfig,
+    combatants: Vec<CombatantConfig>,
+}
+
+static mut TREES_DIR: &str = "";
+static mut STACKS_DIR: &str = "";
+
+fn read_file(path: &String) -> String {
+    fs::read_to_string(path).unwrap_or_default()
+}
+
+fn load_tree(tree_name: &String) -> String {
+    read_file(&format!("{}/{}.json", unsafe { TREES_DIR }, tree_name))
+}
+
+fn load_stack(class_name: &String, stack_name: &String) -> String {
+    read_file(&format!(
+        "{}/{}/{}.json",
+        unsafe { STACKS_DIR },
+        class_name,
+        stack_name
+    ))
+}
+
+fn exit_with(message: String) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn get_combatant(config: &CombatantConfig, stacks_dir: &String) -> Combatant<BehaviorTreePlanner> {
+    let stack = config.stack.as_ref().map(|stack| {
+        let stack_path = format!("{}/{}", stacks_dir, stack);
+        serde_json::from_str::<Vec<VenomPlan>>(&read_file(&stack_path))
+            .unwrap_or_else(|err| exit_with(format!("Failed to load {}: {:?}", stack_path, err)))
+    });
+    let planner = BehaviorTreePlanner::new(config.class, &config.tree, stack)
+        .unwrap_or_else(|err| exit_with(format!("Cannot simulate {}: {}", config.name, err)));
+    Combatant::new(&config.name, config.class, planner, &config.tree)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let config_path = args
+        .get(1)
+        .unwrap_or_else(|| exit_with("Usage: duel_sim <config.json> [--json]".to_string()));
+    let config: SimulatorConfig = serde_json::from_str(&read_file(config_path))
+        .unwrap_or_else(|err| exit_with(format!("Failed to load {}: {:?}", config_path, err)));
+    if config.combatants.len() != 2 {
+        exit_with("Exactly two combatants are needed.".to_string());
+    }
+    unsafe {
+        TREES_DIR = Box::leak(config.trees_dir.clone().into_boxed_str());
+        STACKS_DIR = Box::leak(config.stacks_dir.clone().into_boxed_str());
+        LOAD_TREE_FUNC = Some(load_tree);
+        LOAD_STACK_FUNC = Some(load_stack);
+    }
+    let arena = Arena::new(
+        config.arena.clone(),
+        get_combatant(&config.combatants[0], &config.stacks_dir),
+        get_combatant(&config.combatants[1], &config.stacks_dir),
+    );
+    let report = arena.run();
+    if args.iter().any(|arg| arg.eq("--json")) {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print!("{}", report);
+    }
+}
@@ -0,0 +1,76 @@
+use std::{env, fs, process};
+use topper_aetolia::{
+    classes::{Class, VenomPlan},
+    curatives::{parse_priority_set, FirstAid, StackEvaluation},
+};
+
+// Usage: stack_eval <stack.json> <opponent class> [first aid priorities] [--trials N] [--json]
+//
+// The priorities file is the game's FIRSTAID PRIORITY output, as captured from the client.
+
+fn exit_with(message: String) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let mut positional = Vec::new();
+    let mut trials = None;
+    let mut json = false;
+    let mut arg_iter = args.iter().skip(1);
+    while let Some(arg) = arg_iter.next() {
+        match arg.as_ref() {
+            "--json" => json = true,
+            "--trials" => {
+                let value = arg_iter
+                    .next()
+                    .unwrap_or_else(|| exit_with("--trials needs a count".to_string()));
+                match value.parse::<usize>() {
+                    Ok(count) if count > 0 => trials = Some(count),
+                    Ok(_) => exit_with("--trials must be at least 1".to_string()),
+                    Err(err) => exit_with(format!("Bad trial count {}: {:?}", value, err)),
+                }
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+    if positional.len() < 2 {
+        exit_with(
+            "Usage: stack_eval <stack.json> <opponent class> [priorities] [--trials N] [--json]"
+                .to_string(),
+        );
+    }
+    let stack_json = fs::read_to_string(&positional[0])
+        .unwrap_or_else(|err| exit_with(format!("Failed to read {}: {:?}", positional[0], err)));
+    let stack = serde_json::from_str::<Vec<VenomPlan>>(&stack_json)
+        .unwrap_or_else(|err| exit_with(format!("Failed to load {}: {:?}", positional[0], err)));
+    let opponent = Class::from_str(&positional[1])
+        .unwrap_or_else(|| exit_with(format!("Unknown class {}", positional[1])));
+    let first_aid = if let Some(priorities_path) = positional.get(2) {
+        let lines = fs::read_to_string(priorities_path)
+            .unwrap_or_else(|err| {
+                exit_with(format!("Failed to read {}: {:?}", priorities_path, err))
+            })
+            .lines()
+            .enumerate()
+            .map(|(num, line)| (line.to_string(), num as u32))
+            .collect();
+        match parse_priority_set(&lines) {
+            Some((_name, priorities)) => FirstAid::from_priorities(priorities),
+            None => exit_with(format!("No priorities found in {}", priorities_path)),
+        }
+    } else {
+        FirstAid::new()
+    };
+    let mut evaluation = StackEvaluation::new(stack, opponent, first_aid);
+    if let Some(trials) = trials {
+        evaluation.config.trials = trials;
+    }
+    let report = evaluation.run();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print!("{}", report);
+    }
+}
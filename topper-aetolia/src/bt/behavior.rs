@@ -71,6 +71,7 @@ impl UnpoweredFunction for AetBehavior {
             AetBehavior::SetLimbHint(target, limb_descriptor, hint_name) => {
                 let limb = limb_descriptor.get_limb(model, controller, target);
                 if let Some(limb) = limb {
+                    controller.explain_limb(limb);
                     controller.hint_plan(hint_name.clone(), limb.to_string().to_lowercase());
                     UnpoweredFunctionState::Complete
                 } else {
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// Why a behavior tree built the plan it did, for display alongside the attack.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
+pub struct PlanExplanation {
+    pub tags: Vec<String>,
+    pub hints: BTreeMap<String, String>,
+    pub venoms: Vec<String>,
+    pub limbs: Vec<String>,
+    // Predicates which passed on the way to the planned actions, in evaluation order. Those
+    // under branches which failed are left out.
+    pub predicates: Vec<String>,
+}
+
+impl PlanExplanation {
+    pub fn add_venom(&mut self, venom: &str) {
+        if !venom.is_empty() && !self.venoms.iter().any(|known| known == venom) {
+            self.venoms.push(venom.to_string());
+        }
+    }
+
+    pub fn add_limb(&mut self, limb: String) {
+        if !self.limbs.contains(&limb) {
+            self.limbs.push(limb);
+        }
+    }
+
+    pub fn add_predicate(&mut self, predicate: String) {
+        self.predicates.push(predicate);
+    }
+
+    pub fn lengths(&self) -> (usize, usize, usize) {
+        (self.venoms.len(), self.limbs.len(), self.predicates.len())
+    }
+
+    pub fn truncate(&mut self, (venoms, limbs, predicates): (usize, usize, usize)) {
+        self.venoms.truncate(venoms);
+        self.limbs.truncate(limbs);
+        self.predicates.truncate(predicates);
+    }
+}
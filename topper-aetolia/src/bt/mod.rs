@@ -1,10 +1,12 @@
 mod behavior;
+mod explanation;
 mod limb_desc;
 mod predicate;
 mod sub_trees;
 use std::collections::{HashMap, HashSet};
 
 pub use behavior::*;
+pub use explanation::*;
 pub use limb_desc::*;
 pub use predicate::*;
 use serde::{Deserialize, Serialize};
@@ -18,7 +20,7 @@ use crate::{
         predator::{
             ComboAttack, ComboGrader, ComboPredicate, ComboSet, ComboSolver, PredatorCombo,
         },
-        VenomPlan,
+        VenomPlan, VenomType,
     },
     observables::ActionPlan,
     timeline::AetTimeline,
@@ -57,6 +59,8 @@ pub struct BehaviorController {
     pub plan_hints: HashMap<String, String>,
     pub target: Option<String>,
     pub allies: HashMap<String, i32>,
+    pub branch_focus: HashMap<String, usize>,
+    pub explanation: PlanExplanation,
     pub class_controller: ClassController,
 }
 
@@ -95,14 +99,62 @@ impl BehaviorController {
         self.plan_hints.get(&hint_name.to_string())
     }
 
-    pub fn get_venoms_from_plan(&self, count: usize, you: &AgentState) -> Vec<&'static str> {
+    pub fn get_focused_branch<'a>(
+        &self,
+        who: &String,
+        branches: &'a Vec<AgentState>,
+    ) -> Option<&'a AgentState> {
+        branches.get(self.branch_focus.get(who).cloned().unwrap_or_default())
+    }
+
+    pub fn get_most_aggroed_ally(&self) -> Option<(String, i32)> {
+        let mut best_ally: Option<(String, i32)> = None;
+        for (ally, aggro) in self.allies.iter() {
+            if *aggro
+                > best_ally
+                    .as_ref()
+                    .map(|(_, ally_aggro)| *ally_aggro)
+                    .unwrap_or(-1)
+            {
+                best_ally = Some((ally.clone(), *aggro));
+            }
+        }
+        best_ally.filter(|(_ally, aggro)| *aggro > 0)
+    }
+
+    pub fn get_venoms_from_plan(&mut self, count: usize, you: &AgentState) -> Vec<&'static str> {
         if let Some(venom_plan) = &self.aff_priorities {
-            get_venoms_from_plan(&self.aff_priorities.as_ref().unwrap(), count, &you)
+            let venoms = get_venoms_from_plan(&self.aff_priorities.as_ref().unwrap(), count, &you);
+            self.explain_venoms(&venoms);
+            venoms
         } else {
             vec![""]
         }
     }
 
+    pub fn explain_venoms(&mut self, venoms: &Vec<VenomType>) {
+        for venom in venoms {
+            self.explanation.add_venom(venom);
+        }
+    }
+
+    pub fn explain_limb(&mut self, limb: LType) {
+        self.explanation.add_limb(limb.to_string());
+    }
+
+    pub fn explain_predicate(&mut self, predicate: &AetPredicate) {
+        self.explanation.add_predicate(format!("{:?}", predicate));
+    }
+
+    pub fn into_explained_plan(mut self) -> ActionPlan {
+        let mut explanation = self.explanation;
+        explanation.tags = self.plan_tags.into_iter().collect();
+        explanation.tags.sort();
+        explanation.hints = self.plan_hints.into_iter().collect();
+        self.plan.explain(explanation);
+        self.plan
+    }
+
     pub fn init_predator(&mut self) {
         self.class_controller = ClassController::Predator {
             predator_combo_store: ComboSolver::default(),
@@ -211,6 +263,18 @@ impl BehaviorController {
     }
 }
 
+impl BranchNotes for BehaviorController {
+    type Checkpoint = (usize, usize, usize);
+
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.explanation.lengths()
+    }
+
+    fn rollback(&mut self, checkpoint: Self::Checkpoint) {
+        self.explanation.truncate(checkpoint);
+    }
+}
+
 impl UnpoweredFunction for AetBehaviorTreeNode {
     type Model = BehaviorModel;
     type Controller = BehaviorController;
@@ -222,7 +286,13 @@ impl UnpoweredFunction for AetBehaviorTreeNode {
     ) -> UnpoweredFunctionState {
         let result = match self {
             Self::Action(action) => action.resume_with(model, controller),
-            Self::Predicate(predicate) => predicate.resume_with(model, controller),
+            Self::Predicate(predicate) => {
+                let result = predicate.resume_with(model, controller);
+                if result == UnpoweredFunctionState::Complete {
+                    controller.explain_predicate(predicate);
+                }
+                result
+            }
             Self::SubTree(sub_tree) => get_tree(sub_tree)
                 .lock()
                 .unwrap()
@@ -10,7 +10,10 @@ use crate::classes::predator::PredatorPredicate;
 use crate::classes::Class;
 use crate::classes::LockType;
 use crate::classes::VenomPlan;
+use crate::curatives::get_branch_cure_depth;
 use crate::curatives::get_cure_depth;
+use crate::curatives::{forecast_branch_locks, forecast_target_locks, LockForecastConfig};
+use crate::non_agent::AetTimelinePlayersExt;
 use crate::non_agent::AetTimelineRoomExt;
 use crate::timeline::*;
 use crate::types::*;
@@ -21,10 +24,54 @@ use super::LimbDescriptor;
 
 pub const QUEUE_TIME: f32 = 0.25;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[cfg(test)]
+#[path = "./tests/predicate_tests.rs"]
+mod predicate_tests;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub enum AetTarget {
     Me,
     Target,
+    // Allies in the room, as tracked by the controller.
+    MostAggroedAlly,
+    LowestHealthAlly,
+    Ally(String),
+    // Whoever most recently attacked the given target.
+    AttackerOf(Box<AetTarget>),
+    // Enemies in the room, other than our current target.
+    OtherEnemyInRoom(EnemyFilter),
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub enum CureDepthCase {
+    Expected,
+    Best,
+    Worst,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum EnemyFilter {
+    Any,
+    ClassIn(Vec<Class>),
+    SomeAffs(Vec<FType>),
+    NoAffs(Vec<FType>),
+    HealthUnder(f32),
+}
+
+impl EnemyFilter {
+    pub fn matches(&self, enemy: &AgentState) -> bool {
+        match self {
+            EnemyFilter::Any => true,
+            EnemyFilter::ClassIn(classes) => enemy
+                .class_state
+                .get_normalized_class()
+                .map(|class| classes.contains(&class))
+                .unwrap_or(false),
+            EnemyFilter::SomeAffs(affs) => affs.iter().any(|aff| enemy.is(*aff)),
+            EnemyFilter::NoAffs(affs) => !affs.iter().any(|aff| enemy.is(*aff)),
+            EnemyFilter::HealthUnder(percent) => enemy.get_health_percent() < *percent,
+        }
+    }
 }
 
 impl AetTarget {
@@ -34,16 +81,83 @@ impl AetTarget {
         controller: &BehaviorController,
     ) -> Option<&'a AgentState> {
         match self {
-            AetTarget::Me => model
-                .state
-                .get_agent(&model.who_am_i())
-                .and_then(|branches| branches.get(0)),
             AetTarget::Target => controller
                 .target
                 .as_ref()
-                .and_then(|target| model.state.get_agent(&target))
-                .and_then(|branches| branches.get(0))
+                .and_then(|target| {
+                    model
+                        .state
+                        .get_agent(&target)
+                        .and_then(|branches| controller.get_focused_branch(target, branches))
+                })
                 .or(Some(&model.default_agent)),
+            _ => self.resolve_name(model, controller).and_then(|name| {
+                model
+                    .state
+                    .get_agent(&name)
+                    .and_then(|branches| controller.get_focused_branch(&name, branches))
+            }),
+        }
+    }
+
+    pub fn resolve_name(
+        &self,
+        model: &BehaviorModel,
+        controller: &BehaviorController,
+    ) -> Option<String> {
+        match self {
+            AetTarget::Me => Some(model.who_am_i()),
+            AetTarget::Target => controller.target.clone(),
+            AetTarget::MostAggroedAlly => controller
+                .get_most_aggroed_ally()
+                .map(|(ally, _aggro)| ally),
+            AetTarget::LowestHealthAlly => {
+                let mut lowest: Option<(String, f32)> = None;
+                for ally in sorted_names(controller.allies.keys()) {
+                    let health = model.state.borrow_agent(&ally).get_health_percent();
+                    if lowest
+                        .as_ref()
+                        .map(|(_, lowest_health)| health < *lowest_health)
+                        .unwrap_or(true)
+                    {
+                        lowest = Some((ally, health));
+                    }
+                }
+                lowest.map(|(ally, _health)| ally)
+            }
+            AetTarget::Ally(name) => controller
+                .allies
+                .keys()
+                .find(|ally| ally.eq_ignore_ascii_case(name))
+                .cloned(),
+            AetTarget::AttackerOf(victim) => {
+                let victim = victim.resolve_name(model, controller)?;
+                let me = model.who_am_i();
+                let victim_is_friendly = victim == me || controller.allies.contains_key(&victim);
+                model
+                    .state
+                    .borrow_agent(&victim)
+                    .aggro
+                    .get_aggro_attackers()
+                    .into_iter()
+                    .find(|attacker| {
+                        // Friendly fire is not what we are looking for.
+                        !victim_is_friendly
+                            || (*attacker != me && !controller.allies.contains_key(attacker))
+                    })
+            }
+            AetTarget::OtherEnemyInRoom(filter) => {
+                let my_room = model.state.borrow_me().room_id;
+                model
+                    .state
+                    .get_enemies()
+                    .into_iter()
+                    .filter(|enemy| Some(enemy) != controller.target.as_ref())
+                    .find(|enemy| {
+                        let enemy_state = model.state.borrow_agent(enemy);
+                        enemy_state.room_id == my_room && filter.matches(&enemy_state)
+                    })
+            }
         }
     }
 
@@ -52,18 +166,40 @@ impl AetTarget {
         model: &'a BehaviorModel,
         controller: &BehaviorController,
     ) -> String {
+        self.resolve_name(model, controller)
+            .unwrap_or_else(|| "enemy".to_string())
+    }
+}
+
+fn sorted_names<'a>(names: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut names: Vec<String> = names.cloned().collect();
+    names.sort();
+    names
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub enum BranchQuantifier {
+    Any,
+    All,
+    AtLeast(f32),
+}
+
+impl BranchQuantifier {
+    pub fn accepts(&self, passed: usize, branches: usize) -> bool {
         match self {
-            AetTarget::Me => model.who_am_i(),
-            AetTarget::Target => controller
-                .target
-                .clone()
-                .unwrap_or_else(|| "enemy".to_string()),
+            BranchQuantifier::Any => passed > 0,
+            BranchQuantifier::All => passed == branches,
+            BranchQuantifier::AtLeast(fraction) => {
+                branches > 0 && (passed as f32 / branches as f32) >= *fraction
+            }
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub enum AetPredicate {
+    // Branches
+    Branches(BranchQuantifier, AetTarget, Box<AetPredicate>),
     // Affs
     AllAffs(AetTarget, Vec<FType>),
     SomeAffs(AetTarget, Vec<FType>),
@@ -79,8 +215,12 @@ pub enum AetPredicate {
     // Buffer/locks
     CannotCure(AetTarget, FType),
     Buffered(AetTarget, FType),
+    // Cure time for the aff if given now, across the target's branches, over the time given.
+    CureTimeOver(AetTarget, FType, CureDepthCase, CType),
     Locked(AetTarget, bool),
     NearLocked(AetTarget, LockType, usize),
+    // Lock forecast within the seconds given, at least as often as the probability given.
+    LockLikely(AetTarget, LockType, f32, f32),
     // Timing
     ReboundingWindow(AetTarget, CType),
     SalveBlocked(AetTarget, CType),
@@ -103,6 +243,9 @@ pub enum AetPredicate {
     IsClimbing(AetTarget),
     // Room tags
     RoomIsTagged(String),
+    // Room occupancy, as seen through GMCP
+    InMyRoom(AetTarget),
+    PlayersInRoomOver(usize),
     // Parries
     KnownParry(AetTarget, LimbDescriptor),
     CanParry(AetTarget),
@@ -189,6 +332,39 @@ fn aff_counts(
     })
 }
 
+fn check_branches(
+    quantifier: &BranchQuantifier,
+    target: &AetTarget,
+    predicate: &mut AetPredicate,
+    model: &BehaviorModel,
+    controller: &mut BehaviorController,
+) -> bool {
+    let branch_count = target.resolve_name(model, controller).and_then(|name| {
+        model
+            .state
+            .get_agent(&name)
+            .map(|branches| (name, branches.len()))
+    });
+    if let Some((name, branch_count)) = branch_count {
+        let previous_focus = controller.branch_focus.remove(&name);
+        let mut passed = 0;
+        for branch in 0..branch_count {
+            controller.branch_focus.insert(name.clone(), branch);
+            if predicate.resume_with(model, controller) == UnpoweredFunctionState::Complete {
+                passed += 1;
+            }
+        }
+        controller.branch_focus.remove(&name);
+        if let Some(previous_focus) = previous_focus {
+            controller.branch_focus.insert(name, previous_focus);
+        }
+        quantifier.accepts(passed, branch_count)
+    } else {
+        // Nothing is known about them, so there is only the one default branch.
+        predicate.resume_with(model, controller) == UnpoweredFunctionState::Complete
+    }
+}
+
 pub fn get_priority_aff(
     target: &AetTarget,
     model: &BehaviorModel,
@@ -212,6 +388,13 @@ impl UnpoweredFunction for AetPredicate {
         controller: &mut Self::Controller,
     ) -> UnpoweredFunctionState {
         match self {
+            AetPredicate::Branches(quantifier, target, predicate) => {
+                if check_branches(quantifier, target, predicate, model, controller) {
+                    UnpoweredFunctionState::Complete
+                } else {
+                    UnpoweredFunctionState::Failed
+                }
+            }
             AetPredicate::AllAffs(target, affs) => {
                 if all_affs(target, model, controller, affs) {
                     UnpoweredFunctionState::Complete
@@ -304,6 +487,33 @@ impl UnpoweredFunction for AetPredicate {
                 }
                 UnpoweredFunctionState::Failed
             }
+            AetPredicate::LockLikely(target, lock_type, seconds, probability) => {
+                if let (Some(name), Some(stack)) = (
+                    target.resolve_name(model, controller),
+                    controller.aff_priorities.as_ref(),
+                ) {
+                    let config = LockForecastConfig {
+                        horizon: *seconds,
+                        ..Default::default()
+                    };
+                    // Inside a branch check, only the branch in focus is forecast.
+                    let etas = if controller.branch_focus.contains_key(&name) {
+                        target.get_target(model, controller).map(|branch| {
+                            forecast_branch_locks(model, vec![branch.clone()], stack, &config)
+                        })
+                    } else {
+                        Some(forecast_target_locks(model, &name, stack, &config))
+                    };
+                    if etas
+                        .iter()
+                        .flatten()
+                        .any(|eta| eta.lock == *lock_type && eta.probability >= *probability)
+                    {
+                        return UnpoweredFunctionState::Complete;
+                    }
+                }
+                UnpoweredFunctionState::Failed
+            }
             AetPredicate::CannotCure(target, aff) => {
                 if let Some(target) = target.get_target(model, controller) {
                     let mut afflicted = target.clone();
@@ -321,6 +531,36 @@ impl UnpoweredFunction for AetPredicate {
                 }
                 UnpoweredFunctionState::Failed
             }
+            AetPredicate::CureTimeOver(target, aff, case, minimum) => {
+                if let Some(name) = target.resolve_name(model, controller) {
+                    // Inside a branch check, only the branch in focus is considered.
+                    let mut branches = if controller.branch_focus.contains_key(&name) {
+                        target
+                            .get_target(model, controller)
+                            .map(|branch| vec![branch.clone()])
+                            .unwrap_or_default()
+                    } else {
+                        model
+                            .state
+                            .get_agent(&name)
+                            .cloned()
+                            .unwrap_or_else(|| vec![model.state.borrow_agent(&name)])
+                    };
+                    for branch in branches.iter_mut() {
+                        branch.set_flag(*aff, true);
+                    }
+                    let cure_depth = get_branch_cure_depth(&branches, *aff);
+                    let time = match case {
+                        CureDepthCase::Expected => cure_depth.expected_time,
+                        CureDepthCase::Best => cure_depth.best.time as f32,
+                        CureDepthCase::Worst => cure_depth.worst.time as f32,
+                    };
+                    if time > *minimum as f32 {
+                        return UnpoweredFunctionState::Complete;
+                    }
+                }
+                UnpoweredFunctionState::Failed
+            }
             AetPredicate::PriorityAffIs(target, aff) => {
                 if let Some(priority_aff) =
                     get_priority_aff(target, model, controller, controller.aff_priorities.clone())
@@ -524,6 +764,24 @@ impl UnpoweredFunction for AetPredicate {
                     UnpoweredFunctionState::Failed
                 }
             }
+            AetPredicate::InMyRoom(target) => {
+                if let Some(name) = target.resolve_name(model, controller) {
+                    if model.state.get_players_in_my_room().contains(&name) {
+                        UnpoweredFunctionState::Complete
+                    } else {
+                        UnpoweredFunctionState::Failed
+                    }
+                } else {
+                    UnpoweredFunctionState::Failed
+                }
+            }
+            AetPredicate::PlayersInRoomOver(count) => {
+                if model.state.get_players_in_my_room().len() > *count {
+                    UnpoweredFunctionState::Complete
+                } else {
+                    UnpoweredFunctionState::Failed
+                }
+            }
             AetPredicate::HealthUnder(target, percent) => {
                 if let Some(target) = target.get_target(model, controller) {
                     if target.get_health_percent() < *percent {
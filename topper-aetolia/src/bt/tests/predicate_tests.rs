@@ -0,0 +1,346 @@
+mod predicate_tests {
+    use topper_bt::unpowered::*;
+
+    use crate::bt::*;
+    use crate::non_agent::{format_enemies_id, AetNonAgent, AetTimelineRoomExt};
+
+    use super::super::*;
+
+    fn set_health(timeline: &mut AetTimeline, who: &str, health: CType) {
+        timeline.state.for_agent(&who.to_string(), &move |me| {
+            me.set_max_stat(SType::Health, 1000);
+            me.set_stat(SType::Health, health);
+        });
+    }
+
+    fn get_model_and_controller() -> (AetTimeline, BehaviorController) {
+        let mut timeline = AetTimeline::new();
+        timeline.state.me = "Seurimas".to_string();
+        for who in ["Seurimas", "Benedicto", "Illikaz", "Bob", "Kaiza", "Tsuyu"].iter() {
+            set_health(&mut timeline, who, 1000);
+        }
+        let mut controller = BehaviorController::default();
+        controller.target = Some("Kaiza".to_string());
+        controller.allies.insert("Benedicto".to_string(), 0);
+        controller.allies.insert("Illikaz".to_string(), 2);
+        (timeline, controller)
+    }
+
+    #[test]
+    fn test_most_aggroed_ally() {
+        let (timeline, mut controller) = get_model_and_controller();
+        assert_eq!(
+            AetTarget::MostAggroedAlly.resolve_name(&timeline, &controller),
+            Some("Illikaz".to_string())
+        );
+        controller.allies.insert("Illikaz".to_string(), 0);
+        assert_eq!(
+            AetTarget::MostAggroedAlly.resolve_name(&timeline, &controller),
+            None
+        );
+    }
+
+    #[test]
+    fn test_lowest_health_ally() {
+        let (mut timeline, controller) = get_model_and_controller();
+        set_health(&mut timeline, "Benedicto", 400);
+        let ally = AetTarget::LowestHealthAlly.get_target(&timeline, &controller);
+        assert_eq!(ally.map(|ally| ally.get_health_percent()), Some(0.4));
+        assert_eq!(
+            AetTarget::LowestHealthAlly.get_name(&timeline, &controller),
+            "Benedicto"
+        );
+    }
+
+    #[test]
+    fn test_named_ally() {
+        let (timeline, controller) = get_model_and_controller();
+        assert_eq!(
+            AetTarget::Ally("benedicto".to_string()).resolve_name(&timeline, &controller),
+            Some("Benedicto".to_string())
+        );
+        assert_eq!(
+            AetTarget::Ally("Kaiza".to_string()).resolve_name(&timeline, &controller),
+            None
+        );
+    }
+
+    #[test]
+    fn test_attacker_of() {
+        let (mut timeline, controller) = get_model_and_controller();
+        let attacker_of_ally =
+            AetTarget::AttackerOf(Box::new(AetTarget::Ally("Illikaz".to_string())));
+        assert_eq!(attacker_of_ally.resolve_name(&timeline, &controller), None);
+        timeline.state.for_agent(&"Illikaz".to_string(), &|me| {
+            me.register_hit(Some(&"Seurimas".to_string()));
+            me.register_hit(Some(&"Tsuyu".to_string()));
+        });
+        assert_eq!(
+            attacker_of_ally.resolve_name(&timeline, &controller),
+            Some("Tsuyu".to_string())
+        );
+    }
+
+    #[test]
+    fn test_other_enemy_in_room() {
+        let (mut timeline, controller) = get_model_and_controller();
+        timeline.state.non_agent_states.insert(
+            format_enemies_id("Seurimas"),
+            AetNonAgent::Players(vec![
+                "Kaiza".to_string(),
+                "Bob".to_string(),
+                "Tsuyu".to_string(),
+            ]),
+        );
+        for who in ["Seurimas", "Kaiza", "Tsuyu"].iter() {
+            timeline.state.for_agent(&who.to_string(), &|me| {
+                me.room_id = 1234;
+            });
+        }
+        set_health(&mut timeline, "Tsuyu", 500);
+        assert_eq!(
+            AetTarget::OtherEnemyInRoom(EnemyFilter::Any).resolve_name(&timeline, &controller),
+            Some("Tsuyu".to_string())
+        );
+        assert_eq!(
+            AetTarget::OtherEnemyInRoom(EnemyFilter::HealthUnder(0.25))
+                .resolve_name(&timeline, &controller),
+            None
+        );
+    }
+
+    #[test]
+    fn test_branch_quantifiers() {
+        let (mut timeline, mut controller) = get_model_and_controller();
+        let mut paralysed = timeline.state.borrow_agent(&"Kaiza".to_string());
+        paralysed.set_flag(FType::Paresis, true);
+        let mut clumsy = timeline.state.borrow_agent(&"Kaiza".to_string());
+        clumsy.set_flag(FType::Clumsiness, true);
+        timeline.state.agent_states.insert(
+            "Kaiza".to_string(),
+            vec![paralysed.clone(), paralysed, clumsy],
+        );
+        let mut check = |quantifier: BranchQuantifier| {
+            AetPredicate::Branches(
+                quantifier,
+                AetTarget::Target,
+                Box::new(AetPredicate::AllAffs(
+                    AetTarget::Target,
+                    vec![FType::Paresis],
+                )),
+            )
+            .resume_with(&timeline, &mut controller)
+        };
+        assert_eq!(
+            check(BranchQuantifier::Any),
+            UnpoweredFunctionState::Complete
+        );
+        assert_eq!(check(BranchQuantifier::All), UnpoweredFunctionState::Failed);
+        assert_eq!(
+            check(BranchQuantifier::AtLeast(0.6)),
+            UnpoweredFunctionState::Complete
+        );
+        assert_eq!(
+            check(BranchQuantifier::AtLeast(0.7)),
+            UnpoweredFunctionState::Failed
+        );
+        assert!(controller.branch_focus.is_empty());
+    }
+
+    #[test]
+    fn test_plan_explanation() {
+        let (timeline, controller) = get_model_and_controller();
+        let mut controller = controller;
+        let tree_def: AetBehaviorTreeDef = UnpoweredTreeDef::Selector(vec![
+            UnpoweredTreeDef::Sequence(vec![
+                UnpoweredTreeDef::User(AetBehaviorTreeNode::Predicate(AetPredicate::AllAffs(
+                    AetTarget::Target,
+                    vec![FType::Paresis],
+                ))),
+                UnpoweredTreeDef::User(AetBehaviorTreeNode::Action(AetBehavior::TagPlan(
+                    "paralysed".to_string(),
+                ))),
+            ]),
+            UnpoweredTreeDef::Sequence(vec![
+                UnpoweredTreeDef::User(AetBehaviorTreeNode::Predicate(AetPredicate::NoAffs(
+                    AetTarget::Target,
+                    vec![FType::Paresis],
+                ))),
+                UnpoweredTreeDef::User(AetBehaviorTreeNode::Action(AetBehavior::TagPlan(
+                    "unparalysed".to_string(),
+                ))),
+                UnpoweredTreeDef::User(AetBehaviorTreeNode::Action(AetBehavior::HintPlan(
+                    "limb".to_string(),
+                    "head".to_string(),
+                ))),
+            ]),
+        ]);
+        tree_def
+            .create_tree()
+            .resume_with(&timeline, &mut controller);
+        let plan = controller.into_explained_plan();
+        let explanation = plan.get_explanation();
+        assert_eq!(explanation.tags, vec!["unparalysed".to_string()]);
+        assert_eq!(explanation.hints.get("limb"), Some(&"head".to_string()));
+        assert_eq!(
+            explanation.predicates,
+            vec!["NoAffs(Target, [Paresis])".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_failed_branch_unexplained() {
+        let (timeline, controller) = get_model_and_controller();
+        let mut controller = controller;
+        let tree_def: AetBehaviorTreeDef = UnpoweredTreeDef::Selector(vec![
+            UnpoweredTreeDef::Sequence(vec![
+                UnpoweredTreeDef::User(AetBehaviorTreeNode::Predicate(AetPredicate::NoAffs(
+                    AetTarget::Target,
+                    vec![FType::Paresis],
+                ))),
+                UnpoweredTreeDef::User(AetBehaviorTreeNode::Predicate(AetPredicate::AllAffs(
+                    AetTarget::Target,
+                    vec![FType::Asthma],
+                ))),
+            ]),
+            UnpoweredTreeDef::User(AetBehaviorTreeNode::Predicate(AetPredicate::NoAffs(
+                AetTarget::Target,
+                vec![FType::Asthma],
+            ))),
+        ]);
+        assert_eq!(
+            tree_def
+                .create_tree()
+                .resume_with(&timeline, &mut controller),
+            UnpoweredFunctionState::Complete
+        );
+        let plan = controller.into_explained_plan();
+        assert_eq!(
+            plan.get_explanation().predicates,
+            vec!["NoAffs(Target, [Asthma])".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_failed_branch_unexplained_on_rerun() {
+        let (timeline, _controller) = get_model_and_controller();
+        let tree_def: AetBehaviorTreeDef = UnpoweredTreeDef::Selector(vec![
+            UnpoweredTreeDef::Sequence(vec![
+                UnpoweredTreeDef::User(AetBehaviorTreeNode::Predicate(AetPredicate::NoAffs(
+                    AetTarget::Target,
+                    vec![FType::Paresis],
+                ))),
+                UnpoweredTreeDef::User(AetBehaviorTreeNode::Predicate(AetPredicate::AllAffs(
+                    AetTarget::Target,
+                    vec![FType::Asthma],
+                ))),
+            ]),
+            UnpoweredTreeDef::User(AetBehaviorTreeNode::Predicate(AetPredicate::NoAffs(
+                AetTarget::Target,
+                vec![FType::Asthma],
+            ))),
+        ]);
+        // Trees are loaded once and resumed for every plan.
+        let mut tree = tree_def.create_tree();
+        for _ in 0..2 {
+            let (_timeline, mut controller) = get_model_and_controller();
+            assert_eq!(
+                tree.resume_with(&timeline, &mut controller),
+                UnpoweredFunctionState::Complete
+            );
+            let plan = controller.into_explained_plan();
+            assert_eq!(
+                plan.get_explanation().predicates,
+                vec!["NoAffs(Target, [Asthma])".to_string()]
+            );
+        }
+    }
+
+    #[test]
+    fn test_lock_likely() {
+        let (mut timeline, mut controller) = get_model_and_controller();
+        let mut predicate = AetPredicate::LockLikely(AetTarget::Target, LockType::Soft, 10.0, 0.9);
+        assert_eq!(
+            predicate.resume_with(&timeline, &mut controller),
+            UnpoweredFunctionState::Failed
+        );
+        controller.aff_priorities = Some(vec![
+            VenomPlan::Stick(FType::Asthma),
+            VenomPlan::Stick(FType::Anorexia),
+            VenomPlan::Stick(FType::Slickness),
+        ]);
+        timeline.state.for_agent(&"Kaiza".to_string(), &|you| {
+            you.set_flag(FType::Asthma, true);
+            you.set_flag(FType::Anorexia, true);
+        });
+        assert_eq!(
+            predicate.resume_with(&timeline, &mut controller),
+            UnpoweredFunctionState::Complete
+        );
+        let mut unlikely = AetPredicate::LockLikely(AetTarget::Target, LockType::Hard, 3.0, 0.5);
+        assert_eq!(
+            unlikely.resume_with(&timeline, &mut controller),
+            UnpoweredFunctionState::Failed
+        );
+    }
+
+    #[test]
+    fn test_cure_time_over() {
+        let (mut timeline, mut controller) = get_model_and_controller();
+        let clean = timeline.state.borrow_agent(&"Kaiza".to_string());
+        let mut buffered = clean.clone();
+        buffered.set_flag(FType::Clumsiness, true);
+        buffered.set_flag(FType::Slickness, true);
+        buffered.set_balance(BType::Pill, 2.0);
+        timeline
+            .state
+            .agent_states
+            .insert("Kaiza".to_string(), vec![clean, buffered]);
+        let mut check = |case: CureDepthCase| {
+            AetPredicate::CureTimeOver(AetTarget::Target, FType::Asthma, case, 200)
+                .resume_with(&timeline, &mut controller)
+        };
+        assert_eq!(check(CureDepthCase::Best), UnpoweredFunctionState::Failed);
+        assert_eq!(
+            check(CureDepthCase::Worst),
+            UnpoweredFunctionState::Complete
+        );
+        // Focused on the clean branch, the buffered one is not considered.
+        controller.branch_focus.insert("Kaiza".to_string(), 0);
+        let mut focused =
+            AetPredicate::CureTimeOver(AetTarget::Target, FType::Asthma, CureDepthCase::Worst, 200);
+        assert_eq!(
+            focused.resume_with(&timeline, &mut controller),
+            UnpoweredFunctionState::Failed
+        );
+    }
+
+    #[test]
+    fn test_in_my_room() {
+        let (mut timeline, mut controller) = get_model_and_controller();
+        timeline.state.set_player_room(1234, "Seurimas");
+        timeline.state.set_player_room(1234, "Kaiza");
+        timeline.state.set_player_room(1234, "Benedicto");
+        timeline.state.set_player_room(4321, "Illikaz");
+        let mut check =
+            |mut predicate: AetPredicate| predicate.resume_with(&timeline, &mut controller);
+        assert_eq!(
+            check(AetPredicate::InMyRoom(AetTarget::Target)),
+            UnpoweredFunctionState::Complete
+        );
+        assert_eq!(
+            check(AetPredicate::InMyRoom(AetTarget::Ally(
+                "Illikaz".to_string()
+            ))),
+            UnpoweredFunctionState::Failed
+        );
+        assert_eq!(
+            check(AetPredicate::PlayersInRoomOver(1)),
+            UnpoweredFunctionState::Complete
+        );
+        assert_eq!(
+            check(AetPredicate::PlayersInRoomOver(2)),
+            UnpoweredFunctionState::Failed
+        );
+    }
+}
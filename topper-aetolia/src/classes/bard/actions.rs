@@ -1,5 +1,6 @@
 use serde::*;
 
+use super::observation_handling::PIERCE_ORDER;
 use crate::{classes::group::*, observables::*, timeline::*, types::*};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
@@ -517,9 +518,98 @@ impl PerformanceAttackAction {
     }
 }
 
+impl PerformanceAttackAction {
+    pub fn get_skill(&self) -> &str {
+        match &self.attack {
+            PerformanceAttack::TempoNaked
+            | PerformanceAttack::TempoOne(_)
+            | PerformanceAttack::TempoTwo(_, _)
+            | PerformanceAttack::TempoThree(_, _, _) => "Tempo",
+            PerformanceAttack::Needle(_) => "Needle",
+            PerformanceAttack::Harry(_) => "Harry",
+            PerformanceAttack::Bravado(_) => "Bravado",
+            PerformanceAttack::Pierce => "Pierce",
+            PerformanceAttack::Seduce => "Seduce",
+            PerformanceAttack::Guilt => "Guilt",
+            PerformanceAttack::Ridicule => "Ridicule",
+            PerformanceAttack::Crackshot => "Crackshot",
+            PerformanceAttack::Quip => "Quip",
+            PerformanceAttack::Sock => "Sock",
+            PerformanceAttack::Hiltblow => "Hiltblow",
+            PerformanceAttack::Cadence => "Cadence",
+        }
+    }
+
+    fn get_venoms(&self) -> Vec<&str> {
+        match &self.attack {
+            PerformanceAttack::TempoOne(venom)
+            | PerformanceAttack::Harry(venom)
+            | PerformanceAttack::Bravado(venom) => vec![venom],
+            PerformanceAttack::TempoTwo(venom_one, venom_two) => vec![venom_one, venom_two],
+            PerformanceAttack::TempoThree(venom_one, venom_two, venom_three) => {
+                vec![venom_one, venom_two, venom_three]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn observation(&self, annotation: &str) -> AetObservation {
+        CombatAction::observation(
+            &self.caster,
+            &"Performance",
+            self.get_skill(),
+            annotation,
+            &self.target,
+        )
+    }
+}
+
 impl ActiveTransition for PerformanceAttackAction {
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        vec![]
+        let you = timeline.state.borrow_agent(&self.target);
+        match &self.attack {
+            PerformanceAttack::Needle(venom) => {
+                // A dodged needle is reported on the needle itself.
+                let weights = AttackWeights::against(&you, None, false);
+                let mut events = vec![ProbableEvent::new(
+                    vec![self.observation(venom)],
+                    weights.hit,
+                )];
+                if weights.dodge > 0 {
+                    events.push(ProbableEvent::new(
+                        vec![self.observation("dodge")],
+                        weights.dodge,
+                    ));
+                }
+                events
+            }
+            PerformanceAttack::Pierce => {
+                let pierced = PIERCE_ORDER
+                    .iter()
+                    .find(|def| you.is(**def))
+                    .map(|def| match def {
+                        FType::Reflection => "reflection",
+                        FType::Shielded => "shield",
+                        FType::Rebounding => "rebounding",
+                        _ => "speed",
+                    })
+                    .unwrap_or("speed");
+                ProbableEvent::certain(vec![self.observation(pierced)])
+            }
+            PerformanceAttack::Seduce
+            | PerformanceAttack::Guilt
+            | PerformanceAttack::Ridicule
+            | PerformanceAttack::Quip => ProbableEvent::certain(vec![self.observation("")]),
+            _ => {
+                let simulation = AttackSimulation::new(self.observation(""), &self.target);
+                if self.attack.needs_weapon() {
+                    simulation.reboundable().venoms(self.get_venoms())
+                } else {
+                    simulation
+                }
+                .events(timeline)
+            }
+        }
     }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         let action = match &self.attack {
@@ -602,7 +692,13 @@ impl SongAction {
 
 impl ActiveTransition for SongAction {
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        vec![]
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &self.caster,
+            "Songcalling",
+            &self.song.to_string(),
+            if self.played { "play" } else { "" },
+            "",
+        )])
     }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(if self.played {
@@ -635,8 +731,8 @@ impl ActiveTransition for InduceAction {
         ProbableEvent::certain(vec![CombatAction::observation(
             &self.caster,
             "Songcalling",
-            "Induce",
-            self.emotion.name(),
+            &format!("Induce {:?}", self.emotion),
+            "",
             &self.target,
         )])
     }
@@ -686,7 +782,13 @@ impl ColdReadAction {
 
 impl ActiveTransition for ColdReadAction {
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        vec![]
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &self.caster,
+            "Performance",
+            "Coldread",
+            "",
+            &self.target,
+        )])
     }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("coldread {}", self.target))
@@ -199,6 +199,7 @@ impl UnpoweredFunction for BardBehavior {
                         _ => 1,
                     };
                     let venoms = get_venoms_from_plan(&venom_plan.to_vec(), venom_count, &you);
+                    controller.explain_venoms(&venoms);
                     controller
                         .plan
                         .add_to_qeb(Box::new(PerformanceAttackAction::new(
@@ -313,8 +314,7 @@ impl UnpoweredFunction for BardBehavior {
                 } else if !controller.has_qeb() {
                     return UnpoweredFunctionState::Failed;
                 }
-                let best_ally = get_aggroed_ally(controller);
-                if let Some((ally, _aggro)) = &best_ally {
+                if let Some(ally) = AetTarget::MostAggroedAlly.resolve_name(model, controller) {
                     if !assure_unwielded(&me, model, controller, false) {
                         return UnpoweredFunctionState::Failed;
                     }
@@ -340,8 +340,7 @@ impl UnpoweredFunction for BardBehavior {
                 }
             }
             BardBehavior::AudienceAggroedAlly => {
-                let best_ally = get_aggroed_ally(controller);
-                if let Some((ally, _aggro)) = &best_ally {
+                if let Some(ally) = AetTarget::MostAggroedAlly.resolve_name(model, controller) {
                     controller
                         .plan
                         .add_to_front_of_qeb(Box::new(PlainAction::new(format!(
@@ -473,21 +472,6 @@ impl UnpoweredFunction for BardBehavior {
     }
 }
 
-fn get_aggroed_ally(controller: &mut BehaviorController) -> Option<(String, i32)> {
-    let mut best_ally: Option<(String, i32)> = None;
-    for (ally, aggro) in controller.allies.iter() {
-        if *aggro
-            > best_ally
-                .as_ref()
-                .map(|(_, ally_aggro)| *ally_aggro)
-                .unwrap_or(-1)
-        {
-            best_ally = Some((ally.clone(), *aggro));
-        }
-    }
-    best_ally.filter(|(_ally, aggro)| *aggro > 0)
-}
-
 fn assure_unwielded(
     me: &AgentState,
     model: &BehaviorModel,
@@ -1,8 +1,12 @@
 use std::mem;
 
 use crate::{
-    classes::remove_through, curatives::RANDOM_CURES, non_agent::AetTimelineRoomExt,
-    observables::*, timeline::*, types::*,
+    classes::{get_vitals_value, remove_through},
+    curatives::{SafetyAlert, RANDOM_CURES},
+    non_agent::AetTimelineRoomExt,
+    observables::*,
+    timeline::*,
+    types::*,
 };
 
 const BLADES_COUNT: usize = 3;
@@ -31,7 +35,7 @@ pub const THURIBLE: &str = "a golden thurible";
 pub const ANELACE: &str = "a sharp anelace";
 
 lazy_static! {
-    static ref PIERCE_ORDER: Vec<FType> = vec![
+    pub static ref PIERCE_ORDER: Vec<FType> = vec![
         FType::Reflection,
         FType::Shielded,
         FType::Rebounding,
@@ -988,6 +992,12 @@ pub fn handle_songcalling_action(
     Ok(())
 }
 
+pub fn handle_vitals(vitals: &serde_json::Value, me: &mut AgentState) {
+    if let Some(dithering) = get_vitals_value::<usize>(vitals, "dithering") {
+        me.assume_bard(&move |bard| bard.dithering = dithering);
+    }
+}
+
 pub fn handle_combat_action(
     combat_action: &CombatAction,
     agent_states: &mut AetTimelineState,
@@ -1004,3 +1014,12 @@ pub fn handle_combat_action(
         _ => Err(format!("Bad category: {}", combat_action.category)),
     }
 }
+
+pub fn get_bard_alerts(agent: &AgentState) -> Vec<SafetyAlert> {
+    match agent.bard_board.iron_collar_state {
+        IronCollarState::None => vec![],
+        // A locked collar is all heartcage needs, and no cure takes it off.
+        IronCollarState::Locked => vec![SafetyAlert::InstakillThreat(vec![])],
+        IronCollarState::Locking => vec![SafetyAlert::HighValueThreat(vec![])],
+    }
+}
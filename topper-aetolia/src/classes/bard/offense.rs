@@ -33,7 +33,7 @@ pub fn get_action_plan(
     if let Ok(mut tree) = tree.lock() {
         tree.resume_with(&timeline, &mut controller);
     }
-    controller.plan
+    controller.into_explained_plan()
 }
 
 fn add_hints(db: Option<&impl AetDatabaseModule>, controller: &mut BehaviorController) {
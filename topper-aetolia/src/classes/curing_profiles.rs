@@ -0,0 +1,123 @@
+use super::Class;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+// A class ability that cures afflictions off its own balance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassCure {
+    pub ability: String,
+    pub afflictions: Vec<FType>,
+    pub balance: BType,
+    // In seconds.
+    pub cooldown: f32,
+    // None when we know the cure is there, but not how to send it ourselves.
+    pub command: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbilityBlock {
+    pub ability: String,
+    pub afflictions: Vec<FType>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClassCuringProfile {
+    pub cures: Vec<ClassCure>,
+    pub blocked_by: Vec<AbilityBlock>,
+}
+
+impl ClassCuringProfile {
+    pub fn get_cure(&self, affliction: FType) -> Option<&ClassCure> {
+        self.cures
+            .iter()
+            .find(|cure| cure.afflictions.contains(&affliction))
+    }
+
+    pub fn is_blocked_by(&self, affliction: FType) -> bool {
+        self.blocked_by
+            .iter()
+            .any(|block| block.afflictions.contains(&affliction))
+    }
+
+    // The first cure with its balance up that would take one of the agent's afflictions, unless
+    // the agent is too afflicted to use class cures at all.
+    pub fn get_ready_cure(&self, state: &AgentState) -> Option<(&ClassCure, FType)> {
+        if self.blocked_by.iter().any(|block| {
+            block.ability == CLASS_CURES
+                && block
+                    .afflictions
+                    .iter()
+                    .any(|affliction| state.is(*affliction))
+        }) {
+            return None;
+        }
+        self.cures
+            .iter()
+            .filter(|cure| state.balanced(cure.balance))
+            .find_map(|cure| {
+                cure.afflictions
+                    .iter()
+                    .find(|affliction| state.is(**affliction))
+                    .map(|affliction| (cure, *affliction))
+            })
+    }
+
+    pub fn get_blocked_abilities(&self, affliction: FType) -> Vec<&String> {
+        self.blocked_by
+            .iter()
+            .filter(|block| block.afflictions.contains(&affliction))
+            .map(|block| &block.ability)
+            .collect()
+    }
+}
+
+// The ability name under which afflictions block every class cure.
+pub const CLASS_CURES: &str = "class cures";
+
+// Shared, so lookups on the hot path don't copy a profile out from under the lock.
+pub type CuringProfiles = HashMap<Class, Arc<ClassCuringProfile>>;
+
+// Parses profiles keyed by class name. Mirror classes are folded into the class they mirror.
+pub fn parse_curing_profiles(profiles_json: &str) -> Result<CuringProfiles, String> {
+    let profiles = serde_json::from_str::<HashMap<String, ClassCuringProfile>>(profiles_json)
+        .map_err(|err| err.to_string())?;
+    let mut parsed = HashMap::new();
+    for (class_name, profile) in profiles {
+        let class =
+            Class::from_str(&class_name).ok_or_else(|| format!("Unknown class {}", class_name))?;
+        parsed.insert(class.normal(), Arc::new(profile));
+    }
+    Ok(parsed)
+}
+
+lazy_static! {
+    static ref DEFAULT_PROFILE: Arc<ClassCuringProfile> = Arc::new(ClassCuringProfile::default());
+    static ref CURING_PROFILES: RwLock<CuringProfiles> = RwLock::new(
+        parse_curing_profiles(include_str!("./curing_profiles.json"))
+            .expect("Bad curing_profiles.json")
+    );
+}
+
+pub fn curing_profiles() -> RwLockReadGuard<'static, CuringProfiles> {
+    CURING_PROFILES.read().unwrap()
+}
+
+pub fn get_curing_profile(class: &Class) -> Arc<ClassCuringProfile> {
+    curing_profiles()
+        .get(&class.normal())
+        .unwrap_or(&DEFAULT_PROFILE)
+        .clone()
+}
+
+pub fn load_curing_profiles(profiles_json: &str) -> Result<(), String> {
+    let profiles = parse_curing_profiles(profiles_json)?;
+    *CURING_PROFILES.write().unwrap() = profiles;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "./tests/curing_profiles_tests.rs"]
+mod curing_profiles_tests;
@@ -41,8 +41,19 @@ impl DoublestabAction {
 }
 
 impl ActiveTransition for DoublestabAction {
-    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        Vec::new()
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &self.caster,
+                &"Assassination",
+                &"Doublestab",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .venoms(vec![self.venoms.0, self.venoms.1])
+        .events(timeline)
     }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         if self.venoms.1.eq("") {
@@ -102,20 +113,23 @@ impl FlayAction {
 
 impl ActiveTransition for FlayAction {
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        let mut observations = vec![CombatAction::observation(
-            &self.caster,
-            &"Assassination",
-            &"Flay",
-            &self.annotation,
+        let mut simulation = AttackSimulation::new(
+            CombatAction::observation(
+                &self.caster,
+                &"Assassination",
+                &"Flay",
+                &self.annotation,
+                &self.target,
+            ),
             &self.target,
-        )];
+        );
         if self.venom.len() > 0
             && (self.annotation.eq_ignore_ascii_case("shield")
                 || self.annotation.eq_ignore_ascii_case("rebounding"))
         {
-            observations.push(AetObservation::Devenoms(self.venom.to_string()));
+            simulation = simulation.on_hit(AetObservation::Devenoms(self.venom.to_string()));
         }
-        ProbableEvent::certain(observations)
+        simulation.events(timeline)
     }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         let action = format!("envenom whip with {};;flay {}", self.venom, self.target);
@@ -147,15 +161,12 @@ impl SlitAction {
 
 impl ActiveTransition for SlitAction {
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        let mut observations = vec![CombatAction::observation(
-            &self.caster,
-            &"Assassination",
-            &"Slit",
-            &"",
+        AttackSimulation::new(
+            CombatAction::observation(&self.caster, &"Assassination", &"Slit", &"", &self.target),
             &self.target,
-        )];
-        observations.push(AetObservation::Devenoms(self.venom.to_string()));
-        ProbableEvent::certain(observations)
+        )
+        .venom(self.venom)
+        .events(timeline)
     }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         let action = format!("slit {} {}", self.target, self.venom);
@@ -249,11 +260,20 @@ impl BiteAction {
 
 impl ActiveTransition for BiteAction {
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        let annotation = if timeline
+            .state
+            .borrow_agent(&self.target)
+            .is(FType::Fangbarrier)
+        {
+            "failure"
+        } else {
+            self.venom
+        };
         ProbableEvent::certain(vec![CombatAction::observation(
             &self.caster,
             &"Assassination",
             &"Bite",
-            &self.venom,
+            annotation,
             &self.target,
         )])
     }
@@ -286,13 +306,18 @@ impl GarroteAction {
 
 impl ActiveTransition for GarroteAction {
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        ProbableEvent::certain(vec![CombatAction::observation(
-            &self.caster,
-            &"Assassination",
-            &"Garrote",
-            &"",
+        AttackSimulation::new(
+            CombatAction::observation(
+                &self.caster,
+                &"Assassination",
+                &"Garrote",
+                &"",
+                &self.target,
+            ),
             &self.target,
-        )])
+        )
+        .reboundable()
+        .events(timeline)
     }
 
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
@@ -316,7 +341,13 @@ impl BedazzleAction {
 
 impl ActiveTransition for BedazzleAction {
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        vec![]
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &self.caster,
+            &"Subterfuge",
+            &"Bedazzle",
+            &"",
+            &self.target,
+        )])
     }
 
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
@@ -412,7 +443,7 @@ impl ActiveTransition for SealAction {
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
         ProbableEvent::certain(vec![
             AetObservation::Sent(format!("seal {} {}", self.target, self.duration)),
-            CombatAction::observation(&self.caster, &"Hypnosis", &"Suggest", &"", &self.target),
+            CombatAction::observation(&self.caster, &"Hypnosis", &"Seal", &"", &self.target),
         ])
     }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
@@ -159,6 +159,7 @@ impl UnpoweredFunction for InfiltratorBehavior {
                         if venoms.len() < 1 {
                             return UnpoweredFunctionState::Failed;
                         }
+                        controller.explain_venoms(&venoms);
                         controller.plan.add_to_qeb(Box::new(BiteAction::new(
                             model.who_am_i(),
                             target.get_name(model, controller),
@@ -192,6 +193,7 @@ impl UnpoweredFunction for InfiltratorBehavior {
                         return UnpoweredFunctionState::Failed;
                     }
                     let v1 = venoms[0];
+                    controller.explain_venoms(&venoms);
                     controller
                         .plan
                         .add_to_qeb(Box::new(DoublestabAction::new_asp(
@@ -224,6 +226,7 @@ impl UnpoweredFunction for InfiltratorBehavior {
                     }
                     let v1 = venoms[1];
                     let v2 = venoms[0];
+                    controller.explain_venoms(&venoms);
                     controller.plan.add_to_qeb(Box::new(DoublestabAction::new(
                         model.who_am_i(),
                         aet_target.get_name(model, controller),
@@ -252,6 +255,7 @@ impl UnpoweredFunction for InfiltratorBehavior {
                     }
                     let v1 = venoms[1];
                     let v2 = venoms[0];
+                    controller.explain_venoms(&venoms);
                     controller.plan.add_to_qeb(Box::new(DoublestabAction::new(
                         model.who_am_i(),
                         aet_target.get_name(model, controller),
@@ -281,6 +285,7 @@ impl UnpoweredFunction for InfiltratorBehavior {
                     if venoms.len() < 1 {
                         return UnpoweredFunctionState::Failed;
                     }
+                    controller.explain_venoms(&venoms);
                     controller.plan.add_to_qeb(Box::new(FlayAction::new(
                         model.who_am_i(),
                         target.get_name(model, controller),
@@ -385,6 +390,7 @@ impl UnpoweredFunction for InfiltratorBehavior {
                     if venoms.len() < 1 {
                         return UnpoweredFunctionState::Failed;
                     }
+                    controller.explain_venoms(&venoms);
                     controller.plan.add_to_qeb(Box::new(SlitAction::new(
                         model.who_am_i(),
                         target.get_name(model, controller),
@@ -42,7 +42,7 @@ pub fn get_action_plan(
         }
         tree.resume_with(&timeline, &mut controller);
     }
-    controller.plan
+    controller.into_explained_plan()
 }
 
 pub fn get_attack(
@@ -3,6 +3,7 @@ use crate::agent::FType;
 use crate::agent::Hypnosis;
 use crate::alpha_beta::ActionPlanner;
 use crate::classes::*;
+use crate::curatives::SafetyAlert;
 use crate::observables::*;
 use crate::timeline::*;
 use crate::timeline::*;
@@ -109,6 +110,12 @@ pub fn handle_sent(command: &String, agent_states: &mut AetTimelineState) {
     }
 }
 
+pub fn handle_vitals(vitals: &serde_json::Value, me: &mut AgentState) {
+    if let Some(finesse) = get_vitals_value::<u32>(vitals, "finesse") {
+        me.assume_infiltrator(&move |infiltrator| infiltrator.finesse = finesse);
+    }
+}
+
 pub fn handle_combat_action(
     combat_action: &CombatAction,
     agent_states: &mut AetTimelineState,
@@ -449,3 +456,12 @@ pub fn handle_combat_action(
     }
     Ok(())
 }
+
+pub fn get_infiltrator_alerts(agent: &AgentState) -> Vec<SafetyAlert> {
+    if agent.hypno_state.is_sealed() || agent.hypno_state.is_firing() {
+        // Nothing cures a sealed suggestion; it can only be weathered.
+        vec![SafetyAlert::HighValueThreat(vec![])]
+    } else {
+        vec![]
+    }
+}
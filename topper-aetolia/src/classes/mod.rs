@@ -1,7 +1,7 @@
-use crate::bt::BehaviorController;
+use crate::bt::{BehaviorController, PlanExplanation};
 use crate::curatives::{SafetyAlert, MENTAL_AFFLICTIONS, RANDOM_CURES};
 use crate::db::AetDatabaseModule;
-use crate::non_agent::AetNonAgent;
+use crate::non_agent::{AetNonAgent, AetTimelinePlayersExt};
 use crate::observables::*;
 use crate::timeline::*;
 use crate::types::*;
@@ -13,6 +13,7 @@ pub mod archivist;
 pub mod ascendril;
 pub mod bard;
 pub mod carnifex;
+pub mod curing_profiles;
 pub mod group;
 pub mod indorani;
 pub mod infiltrator;
@@ -28,12 +29,15 @@ pub mod shaman;
 pub mod shapeshifter;
 pub mod templar;
 pub mod teradrim;
+pub mod vitals;
 pub mod wayfarer;
 pub mod zealot;
 use serde::{Deserialize, Serialize};
 
 use self::archivist::get_archivist_alerts;
+pub use self::curing_profiles::*;
 use self::mirrors::normalize_combat_action;
+pub use self::vitals::*;
 
 pub struct FitnessAction {
     pub caster: String,
@@ -47,7 +51,13 @@ impl FitnessAction {
 
 impl ActiveTransition for FitnessAction {
     fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        Vec::new()
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &self.caster,
+            &"Hunting",
+            &"Fitness",
+            &"",
+            &"",
+        )])
     }
     fn act(&self, _timeline: &AetTimeline) -> ActivateResult {
         Ok("fitness".to_string())
@@ -215,9 +225,15 @@ impl Class {
         }
     }
 
-    pub fn get_safety_alerts(&self, agent: &AgentState) -> Vec<SafetyAlert> {
-        match self {
+    // Threats this class poses to an agent, given the attacker's own state.
+    pub fn get_safety_alerts(&self, agent: &AgentState, attacker: &AgentState) -> Vec<SafetyAlert> {
+        match self.normal() {
             Class::Archivists => get_archivist_alerts(agent),
+            Class::Bard => bard::get_bard_alerts(agent),
+            Class::Infiltrator => infiltrator::get_infiltrator_alerts(agent),
+            Class::Predator => predator::get_predator_alerts(agent),
+            Class::Zealot => zealot::get_zealot_alerts(agent, attacker),
+            Class::Sentinel => sentinel::get_sentinel_alerts(agent),
             _ => Vec::new(),
         }
     }
@@ -266,51 +282,8 @@ pub fn get_skill_class(category: &String) -> Option<Class> {
     }
 }
 
-pub fn has_special_cure(class: &Class, affliction: FType) -> bool {
-    if class.is_mirror() {
-        return has_special_cure(&class.normal(), affliction);
-    }
-    match (affliction, class) {
-        (FType::Asthma, Class::Monk) => true,
-        (FType::Asthma, Class::Infiltrator) => true,
-        (FType::Paresis, Class::Zealot) => true,
-        _ => false,
-    }
-}
-
 pub fn is_affected_by(class: Class, affliction: FType) -> bool {
-    if class.is_mirror() {
-        return is_affected_by(class.normal(), affliction);
-    }
-    match (affliction, class) {
-        (FType::Clumsiness, Class::Infiltrator) => true,
-        (FType::Clumsiness, Class::Bard) => true,
-        (FType::Clumsiness, Class::Templar) => true,
-        (FType::Clumsiness, Class::Sentinel) => true,
-        (FType::Clumsiness, Class::Wayfarer) => true,
-        (FType::Clumsiness, Class::Teradrim) => true,
-        (FType::Clumsiness, Class::Predator) => true,
-        (FType::Clumsiness, Class::Zealot) => true,
-        (FType::Weariness, Class::Carnifex) => true,
-        (FType::Weariness, Class::Sciomancer) => true,
-        (FType::Peace, Class::Luminary) => true,
-        (FType::Disfigurement, Class::Sentinel) => true,
-        (FType::Disfigurement, Class::Carnifex) => true,
-        (FType::Disfigurement, Class::Luminary) => true,
-        (FType::Disfigurement, Class::Indorani) => true,
-        (FType::Disfigurement, Class::Teradrim) => true,
-        (FType::Lethargy, Class::Bard) => true,
-        (FType::Lethargy, Class::Infiltrator) => true,
-        (FType::Lethargy, Class::Sentinel) => true,
-        (FType::Lethargy, Class::Carnifex) => true,
-        (FType::Lethargy, Class::Templar) => true,
-        // Class cures get blocked!
-        (FType::Paresis, Class::Indorani) => true,
-        (FType::Paresis, Class::Sciomancer) => true,
-        (FType::Paresis, Class::Ascendril) => true,
-        (FType::Paresis, Class::Teradrim) => true,
-        _ => false,
-    }
+    get_curing_profile(&class).is_blocked_by(affliction)
 }
 
 lazy_static! {
@@ -335,21 +308,82 @@ pub fn get_attack(
     strategy: &String,
     db: Option<&impl AetDatabaseModule>,
 ) -> String {
-    if let Some(class) = db.and_then(|db| db.get_class(me)) {
-        match class {
-            Class::Sentinel => sentinel::get_attack(timeline, target, strategy, db),
-            Class::Infiltrator => infiltrator::get_attack(timeline, target, strategy, db),
-            Class::Bard => bard::get_attack(timeline, target, strategy, db),
-            Class::Zealot => zealot::get_attack(timeline, target, strategy, db),
-            Class::Predator => predator::get_attack(timeline, target, strategy, db),
-            Class::Monk => monk::get_attack(timeline, target, strategy, db),
-            _ => infiltrator::get_attack(timeline, target, strategy, db),
-        }
-    } else {
-        infiltrator::get_attack(timeline, target, strategy, db)
+    get_explained_attack(timeline, me, target, strategy, db).0
+}
+
+pub fn get_explained_attack(
+    timeline: &AetTimeline,
+    me: &String,
+    target: &String,
+    strategy: &String,
+    db: Option<&impl AetDatabaseModule>,
+) -> (String, PlanExplanation) {
+    let class = db.and_then(|db| db.get_class(me));
+    let who_am_i = &timeline.who_am_i();
+    match get_class_action_plan(class, timeline, who_am_i, target, strategy, db) {
+        Some(action_plan) => (
+            action_plan.get_inputs(timeline),
+            action_plan.get_explanation().clone(),
+        ),
+        // Zealot attacks are not planned by behavior trees, so there is nothing to explain.
+        None => (
+            zealot::get_attack(timeline, target, strategy, db),
+            PlanExplanation::default(),
+        ),
+    }
+}
+
+// Classes whose attacks are planned by behavior trees of their own. The rest borrow the
+// infiltrator's, or, like Zealot, have none at all.
+pub fn has_behavior_trees(class: Class) -> bool {
+    match class.normal() {
+        Class::Sentinel | Class::Bard | Class::Predator | Class::Monk | Class::Infiltrator => true,
+        _ => false,
     }
 }
 
+pub fn get_class_action_plan(
+    class: Option<Class>,
+    timeline: &AetTimeline,
+    me: &String,
+    target: &String,
+    strategy: &String,
+    db: Option<&impl AetDatabaseModule>,
+) -> Option<ActionPlan> {
+    match class {
+        Some(Class::Sentinel) => Some(sentinel::get_action_plan(
+            timeline, me, target, strategy, db,
+        )),
+        Some(Class::Bard) => Some(bard::get_action_plan(timeline, me, target, strategy, db)),
+        Some(Class::Zealot) => None,
+        Some(Class::Predator) => Some(predator::get_action_plan(
+            timeline, me, target, strategy, db,
+        )),
+        Some(Class::Monk) => Some(monk::get_action_plan(timeline, me, target, strategy, db)),
+        _ => Some(infiltrator::get_action_plan(
+            timeline, me, target, strategy, db,
+        )),
+    }
+}
+
+// The stack a class's trees would be working from, for classes that build toward venom locks.
+pub fn get_class_stack(
+    class: Option<Class>,
+    timeline: &AetTimeline,
+    target: &String,
+    strategy: &String,
+    db: Option<&impl AetDatabaseModule>,
+) -> Option<Vec<VenomPlan>> {
+    let attack_class = match class {
+        Some(Class::Sentinel) | Some(Class::Zealot) => return None,
+        Some(Class::Bard) => "bard",
+        Some(Class::Predator) => "predator",
+        Some(Class::Monk) => "monk",
+        _ => "infiltrator",
+    };
+    get_stack(timeline, attack_class, target, strategy, db)
+}
+
 pub fn handle_combat_action(
     combat_action: &CombatAction,
     agent_states: &mut AetTimelineState,
@@ -809,26 +843,17 @@ fn get_controller(
         plan: ActionPlan::new(me),
         target: Some(target.clone()),
         aff_priorities: get_stack(timeline, attack_class, target, strategy, db),
-        allies: timeline
-            .state
-            .non_agent_states
-            .get(&format!("{}_allies", me))
-            .map(|ally_list| {
-                if let AetNonAgent::Players(ally_list) = ally_list {
-                    let mut ally_aggros = HashMap::new();
-                    let my_room = timeline.state.borrow_me().room_id;
-                    for ally in ally_list {
-                        let ally_state = timeline.state.borrow_agent(ally);
-                        if ally_state.room_id == my_room {
-                            ally_aggros.insert(ally.clone(), ally_state.get_aggro());
-                        }
-                    }
-                    ally_aggros
-                } else {
-                    panic!("Non-player list in allies spot!")
+        allies: {
+            let mut ally_aggros = HashMap::new();
+            let my_room = timeline.state.borrow_me().room_id;
+            for ally in timeline.state.get_allies() {
+                let ally_state = timeline.state.borrow_agent(&ally);
+                if ally_state.room_id == my_room {
+                    ally_aggros.insert(ally, ally_state.get_aggro());
                 }
-            })
-            .unwrap_or_default(),
+            }
+            ally_aggros
+        },
         ..Default::default()
     }
 }
@@ -961,7 +986,7 @@ pub fn add_buffers<'s>(ready: &mut Vec<&'s str>, buffers: &Vec<&'s str>) {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum VenomPlan {
     Stick(FType),
     OnTree(FType),
@@ -1272,7 +1297,7 @@ impl Action {
 
 impl ActiveTransition for Action {
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        vec![]
+        ProbableEvent::certain(vec![AetObservation::Sent(self.command.clone())])
     }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(self.command.clone())
@@ -20,6 +20,36 @@ pub struct MonkComboAction {
 }
 
 impl ActiveTransition for MonkComboAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        let caster = timeline.who_am_i();
+        let attacks: &[MonkComboAttack] = match &self.combo {
+            MonkCombo::Standard(_, attacks) => attacks,
+            MonkCombo::ChangeStance(_, attacks) => attacks,
+            MonkCombo::Cobra(attacks) => attacks,
+        };
+        attacks
+            .iter()
+            .fold(ProbableEvent::certain(vec![]), |events, attack| {
+                let limb = attack.get_limb_damage().map(|(limb, _damage)| limb);
+                let observation = CombatAction::observation(
+                    &caster,
+                    &"Tekura",
+                    &format!("{:?}", attack),
+                    &limb.map(|limb| limb.to_string()).unwrap_or_default(),
+                    &self.target,
+                );
+                let attack_events = if attack.is_feint() {
+                    ProbableEvent::certain(vec![observation])
+                } else if let Some(limb) = limb {
+                    AttackSimulation::new(observation, &self.target)
+                        .limb(limb)
+                        .events(timeline)
+                } else {
+                    AttackSimulation::new(observation, &self.target).events(timeline)
+                };
+                ProbableEvent::combine(&events, &attack_events)
+            })
+    }
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         match &self.combo {
             MonkCombo::Standard(_, attacks) => Ok(format!(
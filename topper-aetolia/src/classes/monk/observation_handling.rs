@@ -1,6 +1,16 @@
+use crate::classes::{get_vitals_str, get_vitals_value};
 use crate::timeline::*;
 use crate::types::*;
 
+pub fn handle_vitals(vitals: &serde_json::Value, me: &mut AgentState) {
+    if let Some(stance) = get_vitals_str(vitals, "stance").map(MonkStance::from_name) {
+        me.assume_monk(&move |monk| monk.stance = stance);
+    }
+    if let Some(kai) = get_vitals_value::<i32>(vitals, "kai") {
+        me.assume_monk(&move |monk| monk.kai = kai);
+    }
+}
+
 pub fn handle_combat_action(
     combat_action: &CombatAction,
     agent_states: &mut AetTimelineState,
@@ -80,6 +80,104 @@ impl ParamComboAttack {
             ParamComboAttack::Swiftkick => "swiftkick".to_string(),
         }
     }
+
+    pub fn get_skill(&self) -> &'static str {
+        match self {
+            ParamComboAttack::Tidalslash => "Tidalslash",
+            ParamComboAttack::Freefall => "Freefall",
+            ParamComboAttack::Pheromones => "Pheromones",
+            ParamComboAttack::Pindown => "Pindown",
+            ParamComboAttack::Mindnumb => "Mindnumb",
+            ParamComboAttack::Jab(_) => "Jab",
+            ParamComboAttack::Pinprick => "Pinprick",
+            ParamComboAttack::Lateral => "Lateral",
+            ParamComboAttack::Vertical => "Vertical",
+            ParamComboAttack::Crescentcut => "Crescentcut",
+            ParamComboAttack::Spinslash => "Spinslash",
+            ParamComboAttack::Lowhook(_) => "Lowhook",
+            ParamComboAttack::Butterfly => "Butterfly",
+            ParamComboAttack::Flashkick => "Flashkick",
+            ParamComboAttack::Trip => "Trip",
+            ParamComboAttack::Veinrip => "Veinrip",
+            ParamComboAttack::Feint(_) => "Feint",
+            ParamComboAttack::Raze => "Raze",
+            ParamComboAttack::Gouge => "Gouge",
+            ParamComboAttack::Bleed => "Bleed",
+            ParamComboAttack::Swiftkick => "Swiftkick",
+        }
+    }
+
+    pub fn get_limb(&self) -> Option<LType> {
+        match self {
+            ParamComboAttack::Jab(limb) | ParamComboAttack::Lowhook(limb)
+                if *limb != LType::SIZE =>
+            {
+                Some(*limb)
+            }
+            ParamComboAttack::Lateral | ParamComboAttack::Gouge => Some(LType::TorsoDamage),
+            ParamComboAttack::Flashkick => Some(LType::HeadDamage),
+            _ => None,
+        }
+    }
+
+    pub fn carries_venom(&self) -> bool {
+        match self {
+            ParamComboAttack::Vertical
+            | ParamComboAttack::Crescentcut
+            | ParamComboAttack::Butterfly
+            | ParamComboAttack::Freefall => true,
+            _ => false,
+        }
+    }
+
+    fn simulate(
+        &self,
+        timeline: &AetTimeline,
+        caster: &String,
+        target: &String,
+        venom: VenomType,
+    ) -> Vec<ProbableEvent> {
+        let you = timeline.state.borrow_agent(target);
+        let annotation = match self {
+            ParamComboAttack::Feint(limb) => limb.to_string(),
+            ParamComboAttack::Raze => RAZE_ORDER
+                .iter()
+                .find(|def| you.is(**def))
+                .map(|def| match def {
+                    FType::Reflection => "reflection",
+                    FType::Shielded => "shield",
+                    FType::Rebounding => "rebounding",
+                    _ => "speed",
+                })
+                .unwrap_or("speed")
+                .to_string(),
+            _ => self
+                .get_limb()
+                .map(|limb| limb.to_string())
+                .unwrap_or_default(),
+        };
+        let observation =
+            CombatAction::observation(caster, &"Knifeplay", self.get_skill(), &annotation, target);
+        match self {
+            ParamComboAttack::Feint(_) | ParamComboAttack::Raze | ParamComboAttack::Tidalslash => {
+                ProbableEvent::certain(vec![observation])
+            }
+            _ => {
+                let simulation = AttackSimulation::new(observation, target).reboundable();
+                let simulation = if let Some(limb) = self.get_limb() {
+                    simulation.limb(limb)
+                } else {
+                    simulation
+                };
+                if venom.len() > 0 {
+                    simulation.venom(venom)
+                } else {
+                    simulation
+                }
+                .events(timeline)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -175,6 +273,28 @@ impl SeriesAttack {
 }
 
 impl ActiveTransition for SeriesAttack {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        let caster = timeline.who_am_i();
+        // The venom rides on the first attack that can deliver it.
+        let venom_index = self
+            .attacks
+            .iter()
+            .position(|attack| attack.carries_venom());
+        self.attacks.iter().enumerate().fold(
+            ProbableEvent::certain(vec![]),
+            |events, (index, attack)| {
+                let venom = if Some(index) == venom_index {
+                    self.venom
+                } else {
+                    ""
+                };
+                ProbableEvent::combine(
+                    &events,
+                    &attack.simulate(timeline, &caster, &self.target, venom),
+                )
+            },
+        )
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         if should_call_venoms(timeline) {
             Ok(format!(
@@ -219,6 +339,20 @@ impl BloodscourgeAction {
 }
 
 impl ActiveTransition for BloodscourgeAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Knifeplay",
+                &"Bloodscourge",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .venom(self.venom)
+        .events(timeline)
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         if should_call_venoms(timeline) {
             Ok(format!(
@@ -249,6 +383,20 @@ impl FleshbaneAction {
 }
 
 impl ActiveTransition for FleshbaneAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Knifeplay",
+                &"Fleshbane",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .venom(self.venom)
+        .events(timeline)
+    }
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         if should_call_venoms(timline) {
             Ok(format!(
@@ -275,6 +423,19 @@ impl AcidAction {
 }
 
 impl ActiveTransition for AcidAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Beastmastery",
+                &"Acid",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .events(timeline)
+    }
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         Ok(format!("spider acid {}", self.target))
     }
@@ -292,6 +453,19 @@ impl StrandsAction {
 }
 
 impl ActiveTransition for StrandsAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Beastmastery",
+                &"Strands",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .events(timeline)
+    }
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         Ok(format!("spider strands {}", self.target))
     }
@@ -309,6 +483,19 @@ impl NegateAction {
 }
 
 impl ActiveTransition for NegateAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Beastmastery",
+                &"Negate",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .events(timeline)
+    }
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         Ok(format!("spider negate {}", self.target))
     }
@@ -326,6 +513,19 @@ impl WebAction {
 }
 
 impl ActiveTransition for WebAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Beastmastery",
+                &"Web",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .events(timeline)
+    }
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         Ok(format!("spider web {}", self.target))
     }
@@ -343,6 +543,15 @@ impl IntoxicateAction {
 }
 
 impl ActiveTransition for IntoxicateAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &timeline.who_am_i(),
+            &"Beastmastery",
+            &"Intoxicate",
+            &"",
+            &self.target,
+        )])
+    }
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         Ok(format!("spider intoxicate {}", self.target))
     }
@@ -364,6 +573,20 @@ impl DartshotAction {
 }
 
 impl ActiveTransition for DartshotAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Predation",
+                &"Dartshot",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .venom(self.venom)
+        .events(timeline)
+    }
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         if should_call_venoms(timline) {
             Ok(format!(
@@ -396,6 +619,20 @@ impl TwinshotAction {
 }
 
 impl ActiveTransition for TwinshotAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Predation",
+                &"Twinshot",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .venoms(vec![self.venom_0, self.venom_1])
+        .events(timeline)
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         if should_call_venoms(timeline) {
             Ok(format!(
@@ -430,6 +667,20 @@ impl RakeAction {
 }
 
 impl ActiveTransition for RakeAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Beastmastery",
+                &"Rake",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .reboundable()
+        .events(timeline)
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("orgyuk rake {}", self.target))
     }
@@ -447,6 +698,20 @@ impl SwipeAction {
 }
 
 impl ActiveTransition for SwipeAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Beastmastery",
+                &"Swipe",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .reboundable()
+        .events(timeline)
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("orgyuk swipe {}", self.target))
     }
@@ -464,6 +729,20 @@ impl ThrowAction {
 }
 
 impl ActiveTransition for ThrowAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Beastmastery",
+                &"Throw",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .reboundable()
+        .events(timeline)
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("orgyuk throw {}", self.target))
     }
@@ -479,6 +758,15 @@ impl RoarAction {
 }
 
 impl ActiveTransition for RoarAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &timeline.who_am_i(),
+            &"Beastmastery",
+            &"Roar",
+            &"",
+            &"",
+        )])
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("orgyuk roar"))
     }
@@ -496,6 +784,20 @@ impl WeakenAction {
 }
 
 impl ActiveTransition for WeakenAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Beastmastery",
+                &"Weaken",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .reboundable()
+        .events(timeline)
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("orgyuk weaken {}", self.target))
     }
@@ -513,6 +815,20 @@ impl MawcrushAction {
 }
 
 impl ActiveTransition for MawcrushAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Beastmastery",
+                &"Mawrcrush",
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .reboundable()
+        .events(timeline)
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("orgyuk mawcrush {}", self.target))
     }
@@ -531,6 +847,21 @@ impl PummelAction {
 }
 
 impl ActiveTransition for PummelAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(
+                &timeline.who_am_i(),
+                &"Beastmastery",
+                &"Pummel",
+                &self.limb.to_string(),
+                &self.target,
+            ),
+            &self.target,
+        )
+        .limb(self.limb)
+        .reboundable()
+        .events(timeline)
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!(
             "orgyuk pummel {} {}",
@@ -550,6 +881,15 @@ impl FerocityAction {
 }
 
 impl ActiveTransition for FerocityAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &timeline.who_am_i(),
+            &"Predation",
+            &"Ferocity",
+            &"",
+            &"",
+        )])
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("ferocity"))
     }
@@ -565,6 +905,15 @@ impl ArouseAction {
 }
 
 impl ActiveTransition for ArouseAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &timeline.who_am_i(),
+            &"Predation",
+            &"Arouse",
+            &"",
+            &"",
+        )])
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("arouse"))
     }
@@ -582,6 +931,15 @@ impl QuickassessAction {
 }
 
 impl ActiveTransition for QuickassessAction {
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &timeline.who_am_i(),
+            &"Predation",
+            &"Quickassess",
+            &"",
+            &self.target,
+        )])
+    }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("quickassess {}", self.target))
     }
@@ -570,10 +570,13 @@ impl UnpoweredFunction for PredatorBehavior {
                     } else if me.is(FType::Disfigurement) {
                         return UnpoweredFunctionState::Failed;
                     }
+                    let limb = limb
+                        .get_limb(model, controller, target)
+                        .unwrap_or(LType::TorsoDamage);
+                    controller.explain_limb(limb);
                     controller.plan.add_to_qeb(Box::new(PummelAction::new(
                         controller.target.clone().unwrap_or("".to_string()),
-                        limb.get_limb(model, controller, target)
-                            .unwrap_or(LType::TorsoDamage),
+                        limb,
                     )));
                     UnpoweredFunctionState::Complete
                 } else {
@@ -626,6 +629,11 @@ fn use_combo(
         .iter()
         .filter_map(|limb| limb.get_limb(model, controller, target))
         .collect::<Vec<LType>>();
+    if best_combo.is_some() {
+        for limb in preferred_limbs.iter() {
+            controller.explain_limb(*limb);
+        }
+    }
     if let (Some(you), Some(combo)) = (target.get_target(model, controller), &best_combo) {
         let venom = controller.get_venoms_from_plan(1, you);
         controller
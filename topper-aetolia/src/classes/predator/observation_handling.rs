@@ -1,4 +1,5 @@
-use crate::classes::remove_through;
+use crate::classes::{get_vitals_str, get_vitals_value, remove_through};
+use crate::curatives::SafetyAlert;
 use crate::curatives::RANDOM_CURES;
 use crate::db::AetDatabaseModule;
 use crate::timeline::*;
@@ -7,7 +8,7 @@ use crate::types::*;
 use super::MAWCRUSH_FREELY_HINT;
 
 lazy_static! {
-    static ref RAZE_ORDER: Vec<FType> = vec![
+    pub static ref RAZE_ORDER: Vec<FType> = vec![
         FType::Reflection,
         FType::Shielded,
         FType::Rebounding,
@@ -48,6 +49,15 @@ pub fn sitara_strike(
     }
 }
 
+pub fn handle_vitals(vitals: &serde_json::Value, me: &mut AgentState) {
+    if let Some(stance) = get_vitals_str(vitals, "knife_stance").map(KnifeStance::from_name) {
+        me.assume_predator(&move |predator| predator.stance = stance);
+    }
+    if let Some(apex) = get_vitals_value::<u32>(vitals, "apex") {
+        me.assume_predator(&move |predator| predator.apex = apex);
+    }
+}
+
 pub fn handle_combat_action(
     combat_action: &CombatAction,
     agent_states: &mut AetTimelineState,
@@ -580,3 +590,15 @@ fn toggle_mawcrush_freely(db: Option<&impl AetDatabaseModule>, value: bool) {
         db.insert_hint(&MAWCRUSH_FREELY_HINT.to_string(), &value.to_string());
     }
 }
+
+pub fn get_predator_alerts(agent: &AgentState) -> Vec<SafetyAlert> {
+    // Veinrip keeps the blood pooling while bloodscourge feeds it venom.
+    if agent.is(FType::Veinrip) && agent.is(FType::Bloodscourge) {
+        vec![SafetyAlert::HighValueThreat(vec![
+            FType::Veinrip,
+            FType::Bloodscourge,
+        ])]
+    } else {
+        vec![]
+    }
+}
@@ -41,7 +41,7 @@ pub fn get_action_plan(
         }
         tree.resume_with(&timeline, &mut controller);
     }
-    controller.plan
+    controller.into_explained_plan()
 }
 
 fn add_hints(db: Option<&impl AetDatabaseModule>, controller: &mut BehaviorController) {
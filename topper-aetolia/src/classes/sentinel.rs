@@ -1,7 +1,9 @@
 use crate::alpha_beta::ActionPlanner;
 #[macro_use(affliction_stacker, affliction_plan_stacker)]
 use crate::{affliction_stacker, affliction_plan_stacker};
+use crate::bt::BehaviorController;
 use crate::classes::*;
+use crate::curatives::SafetyAlert;
 use crate::defense::*;
 use crate::observables::*;
 use crate::timeline::*;
@@ -66,6 +68,24 @@ impl FirstStrike {
         }
     }
 
+    pub fn skill(&self) -> (&'static str, &'static str) {
+        match self {
+            FirstStrike::Slash(_) => ("Slash", ""),
+            FirstStrike::Ambush(_) => ("Ambush", ""),
+            FirstStrike::Blind => ("Blind", ""),
+            FirstStrike::Twirl => ("Twirl", ""),
+            FirstStrike::Strike => ("Strike", ""),
+            FirstStrike::Crosscut => ("Crosscut", ""),
+            FirstStrike::WeakenArms => ("Weaken", "arms"),
+            FirstStrike::WeakenLegs => ("Weaken", "legs"),
+            FirstStrike::Reave => ("Reave", ""),
+            FirstStrike::Trip => ("Trip", ""),
+            FirstStrike::Slam => ("Slam", ""),
+            FirstStrike::Daunt(animal) => ("Daunt", animal),
+            FirstStrike::Icebreath => ("Icebreath", ""),
+        }
+    }
+
     pub fn ignores_rebounding(&self) -> bool {
         match self {
             FirstStrike::Twirl => false, // TODO: We need to handle for second strike rebounding if we try this.
@@ -107,6 +127,19 @@ impl SecondStrike {
         }
     }
 
+    pub fn skill(&self) -> &'static str {
+        match self {
+            SecondStrike::Stab(_) => "Stab",
+            SecondStrike::Slice(_) => "Slice",
+            SecondStrike::Thrust(_) => "Thrust",
+            SecondStrike::Flourish(_) => "Flourish",
+            SecondStrike::Disarm => "Disarm",
+            SecondStrike::Gouge => "Gouge",
+            SecondStrike::Heartbreaker => "Heartbreaker",
+            SecondStrike::Slit => "Slit",
+        }
+    }
+
     pub fn venom(&self) -> &'static str {
         match self {
             SecondStrike::Stab(venom)
@@ -218,8 +251,56 @@ impl ComboAction {
 }
 
 impl ActiveTransition for ComboAction {
-    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        Vec::new()
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        let first_strike = if self.first_strike.flourish() {
+            let (skill, annotation) = self.first_strike.skill();
+            ProbableEvent::certain(vec![CombatAction::observation(
+                &self.caster,
+                &"Woodlore",
+                skill,
+                annotation,
+                &self.target,
+            )])
+        } else {
+            let (skill, annotation) = match self.first_strike {
+                FirstStrike::Reave => (
+                    "Reave",
+                    get_razed(&timeline.state.borrow_agent(&self.target), &REAVE_ORDER)
+                        .map(|def| {
+                            if def == FType::Shielded {
+                                "shielded"
+                            } else {
+                                "rebounding"
+                            }
+                        })
+                        .unwrap_or(""),
+                ),
+                _ => self.first_strike.skill(),
+            };
+            let simulation = AttackSimulation::new(
+                CombatAction::observation(&self.caster, &"Dhuriv", skill, annotation, &self.target),
+                &self.target,
+            );
+            if self.first_strike.ignores_rebounding() {
+                simulation
+            } else {
+                simulation.venom(self.first_strike.venom())
+            }
+            .events(timeline)
+        };
+        let second_strike = AttackSimulation::new(
+            CombatAction::observation(
+                &self.caster,
+                &"Dhuriv",
+                self.second_strike.skill(),
+                &"",
+                &self.target,
+            ),
+            &self.target,
+        )
+        .venom(self.second_strike.venom())
+        .events(timeline);
+        ProbableEvent::combine(&first_strike, &second_strike)
     }
     fn act(&self, timeline: &AetTimeline) -> ActivateResult {
         Ok(get_combo_action(
@@ -273,8 +354,16 @@ impl PierceAction {
 }
 
 impl ActiveTransition for PierceAction {
-    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        Vec::new()
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        let limb = format!("{} leg", self.side);
+        AttackSimulation::new(
+            CombatAction::observation(&self.caster, &"Dhuriv", &"Pierce", &"", &self.target),
+            &self.target,
+        )
+        .limb(LType::from_name(&limb))
+        .reboundable()
+        .on_hit(AetObservation::Damaged(self.target.clone(), limb))
+        .events(timeline)
     }
     fn act(&self, _timeline: &AetTimeline) -> ActivateResult {
         Ok(format!(
@@ -301,8 +390,16 @@ impl SeverAction {
 }
 
 impl ActiveTransition for SeverAction {
-    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        Vec::new()
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        let limb = format!("{} arm", self.side);
+        AttackSimulation::new(
+            CombatAction::observation(&self.caster, &"Dhuriv", &"Sever", &"", &self.target),
+            &self.target,
+        )
+        .limb(LType::from_name(&limb))
+        .reboundable()
+        .on_hit(AetObservation::Damaged(self.target.clone(), limb))
+        .events(timeline)
     }
     fn act(&self, _timeline: &AetTimeline) -> ActivateResult {
         Ok(format!(
@@ -324,7 +421,13 @@ impl MightAction {
 
 impl ActiveTransition for MightAction {
     fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        Vec::new()
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &self.caster,
+            &"Dhuriv",
+            &"Might",
+            &"",
+            &"",
+        )])
     }
     fn act(&self, _timeline: &AetTimeline) -> ActivateResult {
         Ok("might".to_string())
@@ -343,8 +446,19 @@ impl DualrazeAction {
 }
 
 impl ActiveTransition for DualrazeAction {
-    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        Vec::new()
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        let razed = match get_razed(&timeline.state.borrow_agent(&self.target), &DUALRAZE_ORDER) {
+            Some(FType::Shielded) => "shield",
+            Some(FType::Rebounding) => "rebounding",
+            _ => "speed",
+        };
+        ProbableEvent::certain(vec![CombatAction::observation(
+            &self.caster,
+            &"Dhuriv",
+            &"Dualraze",
+            razed,
+            &self.target,
+        )])
     }
     fn act(&self, _timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("dhuriv dualraze {}", self.target))
@@ -363,8 +477,13 @@ impl SpinecutAction {
 }
 
 impl ActiveTransition for SpinecutAction {
-    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        Vec::new()
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        AttackSimulation::new(
+            CombatAction::observation(&self.caster, &"Dhuriv", &"Spinecut", &"", &self.target),
+            &self.target,
+        )
+        .reboundable()
+        .events(timeline)
     }
     fn act(&self, _timeline: &AetTimeline) -> ActivateResult {
         Ok(format!("dhuriv spinecut {}", self.target))
@@ -383,6 +502,18 @@ lazy_static! {
     static ref REAVE_ORDER: Vec<FType> = vec![FType::Shielded, FType::Rebounding];
 }
 
+fn get_razed(you: &AgentState, order: &Vec<FType>) -> Option<FType> {
+    order.iter().find(|def| you.is(**def)).cloned()
+}
+
+// Alacrity is the only Sentinel resource in vitals. Resin layers sit on whoever was coated, not
+// on us, so they are followed from the combat lines into each victim's resin state instead.
+pub fn handle_vitals(vitals: &serde_json::Value, me: &mut AgentState) {
+    if let Some(alacrity) = get_vitals_value::<u32>(vitals, "alacrity") {
+        me.assume_sentinel(&move |sentinel| sentinel.alacrity = alacrity);
+    }
+}
+
 pub fn handle_combat_action(
     combat_action: &CombatAction,
     agent_states: &mut AetTimelineState,
@@ -859,6 +990,7 @@ pub fn get_balance_attack<'s>(
     target: &String,
     strategy: &String,
     db: Option<&impl AetDatabaseModule>,
+    controller: &mut BehaviorController,
 ) -> Box<dyn ActiveTransition> {
     if strategy.eq("damage") {
         return Box::new(Inactivity);
@@ -902,6 +1034,11 @@ pub fn get_balance_attack<'s>(
                     get_second_strike_from_plan(&stack, 1, &you).pop()
                 };
                 if let Some(second_strike) = second_strike {
+                    controller.explain_venoms(&vec![first_strike.venom()]);
+                    controller.explain_venoms(&vec![match second_strike {
+                        SecondStrike::Flourish(venom) => venom,
+                        _ => second_strike.venom(),
+                    }]);
                     return Box::new(ComboAction::new(
                         who_am_i.to_string(),
                         target.clone(),
@@ -922,18 +1059,23 @@ pub fn get_action_plan(
     strategy: &String,
     db: Option<&impl AetDatabaseModule>,
 ) -> ActionPlan {
-    let mut action_plan = ActionPlan::new(me);
-    let mut balance = get_balance_attack(timeline, me, target, strategy, db);
+    let mut controller = BehaviorController {
+        plan: ActionPlan::new(me),
+        ..Default::default()
+    };
+    controller.tag_plan(strategy.clone());
+    let mut balance = get_balance_attack(timeline, me, target, strategy, db, &mut controller);
     if let Some(parry) = get_needed_parry(timeline, me, target, strategy, db) {
+        controller.explain_limb(parry);
         balance = Box::new(SeparatorAction::pair(
             Box::new(ParryAction::new(me.to_string(), parry)),
             balance,
         ));
     }
     if let Ok(_activation) = balance.act(&timeline) {
-        action_plan.add_to_qeb(balance);
+        controller.plan.add_to_qeb(balance);
     }
-    action_plan
+    controller.into_explained_plan()
 }
 
 pub fn get_attack(
@@ -946,6 +1088,15 @@ pub fn get_attack(
     action_plan.get_inputs(&timeline)
 }
 
+pub fn get_sentinel_alerts(agent: &AgentState) -> Vec<SafetyAlert> {
+    if agent.resin_state.burning && agent.resin_state.cold.is_some() {
+        // Burning resin runs its course; no cure puts it out.
+        vec![SafetyAlert::HighValueThreat(vec![])]
+    } else {
+        vec![]
+    }
+}
+
 #[cfg(test)]
 #[path = "./tests/sentinel_tests.rs"]
 mod sentinel_timeline_tests;
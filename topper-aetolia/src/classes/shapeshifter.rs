@@ -1,8 +1,24 @@
+use crate::classes::{get_vitals_flag, Class};
 use crate::curatives::remove_in_order;
 use crate::curatives::STEROID_ORDER;
 use crate::timeline::*;
 use crate::types::*;
 
+pub fn handle_vitals(vitals: &serde_json::Value, me: &mut AgentState) {
+    if let Some(snarling) = get_vitals_flag(vitals, "snarling") {
+        me.assume_shapeshifter(&move |howling| howling.snarling = snarling);
+    }
+    if let Some(echoing) = get_vitals_flag(vitals, "echoing") {
+        me.assume_shapeshifter(&move |howling| howling.echoing = echoing);
+    }
+    if let Some(boneshaking) = get_vitals_flag(vitals, "boneshaking") {
+        me.assume_shapeshifter(&move |howling| howling.boneshaking = boneshaking);
+    }
+    if let Some(attuning) = get_vitals_flag(vitals, "attuning") {
+        me.assume_shapeshifter(&move |howling| howling.attuning = attuning);
+    }
+}
+
 pub fn handle_combat_action(
     combat_action: &CombatAction,
     agent_states: &mut AetTimelineState,
@@ -0,0 +1,56 @@
+mod curing_profiles_tests {
+    use super::super::*;
+    use crate::classes::is_affected_by;
+
+    fn has_cure(class: Class, affliction: FType) -> bool {
+        get_curing_profile(&class).get_cure(affliction).is_some()
+    }
+
+    #[test]
+    fn test_builtin_profiles() {
+        assert!(has_cure(Class::Monk, FType::Asthma));
+        assert!(has_cure(Class::Zealot, FType::Paresis));
+        assert!(!has_cure(Class::Bard, FType::Asthma));
+        // Mirrors share the profile of the class they mirror.
+        assert!(has_cure(Class::Ravager, FType::Paresis));
+        assert!(is_affected_by(Class::Bard, FType::Clumsiness));
+        assert!(!is_affected_by(Class::Ascendril, FType::Clumsiness));
+        assert!(is_affected_by(Class::Teradrim, FType::Paresis));
+        // Scour, Shedding and Might cure slickness, as the class handlers already do.
+        assert!(has_cure(Class::Teradrim, FType::Slickness));
+        assert!(has_cure(Class::Shapeshifter, FType::Slickness));
+        assert!(has_cure(Class::Sentinel, FType::Slickness));
+        let cure = get_curing_profile(&Class::Monk)
+            .get_cure(FType::Asthma)
+            .cloned()
+            .unwrap();
+        assert_eq!(cure.balance, BType::Fitness);
+        assert_eq!(cure.command, Some("fitness".to_string()));
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        let profiles = parse_curing_profiles(
+            r#"{
+                "Revenant": {
+                    "cures": [{
+                        "ability": "Purify",
+                        "afflictions": ["Paresis"],
+                        "balance": "ClassCure2",
+                        "cooldown": 15.0,
+                        "command": null
+                    }],
+                    "blocked_by": [{"ability": "offense", "afflictions": ["Clumsiness"]}]
+                }
+            }"#,
+        )
+        .unwrap();
+        let profile = profiles.get(&Class::Templar).unwrap();
+        assert_eq!(profile.get_cure(FType::Paresis).unwrap().cooldown, 15.0);
+        assert_eq!(
+            profile.get_blocked_abilities(FType::Clumsiness),
+            vec![&"offense".to_string()]
+        );
+        assert!(parse_curing_profiles(r#"{"Nobody": {}}"#).is_err());
+    }
+}
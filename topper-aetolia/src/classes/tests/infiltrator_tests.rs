@@ -1,4 +1,6 @@
 mod infiltrator_timeline_tests {
+    use crate::classes::infiltrator::{get_attack, DoublestabAction};
+    use crate::observables::*;
     use crate::timeline::*;
     use crate::types::*;
     use topper_core::timeline::db::DummyDatabaseModule;
@@ -19,6 +21,7 @@ mod infiltrator_timeline_tests {
             })]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -53,6 +56,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -85,6 +89,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -117,6 +122,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -149,6 +155,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -181,6 +188,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -213,6 +221,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -248,6 +257,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -284,6 +294,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -320,6 +331,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -355,6 +367,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -383,6 +396,7 @@ mod infiltrator_timeline_tests {
             })]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -401,6 +415,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -418,6 +433,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -429,6 +445,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 220,
             me: "Seurimas".into(),
@@ -468,6 +485,7 @@ mod infiltrator_timeline_tests {
             })]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -487,6 +505,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -504,6 +523,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -512,6 +532,7 @@ mod infiltrator_timeline_tests {
             observations: Some(vec![AetObservation::Relapse("Benedicto".into())]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 220,
             me: "Seurimas".into(),
@@ -523,6 +544,7 @@ mod infiltrator_timeline_tests {
             })]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 225,
             me: "Seurimas".into(),
@@ -531,6 +553,7 @@ mod infiltrator_timeline_tests {
             observations: Some(vec![AetObservation::Relapse("Benedicto".into())]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 270,
             me: "Seurimas".into(),
@@ -581,6 +604,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -609,6 +633,7 @@ mod infiltrator_timeline_tests {
             })]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -638,6 +663,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -667,6 +693,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -696,6 +723,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -727,6 +755,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -759,6 +788,7 @@ mod infiltrator_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -771,8 +801,6 @@ mod infiltrator_timeline_tests {
         );
     }
 
-    use crate::classes::infiltrator::get_attack;
-
     #[test]
     fn test_bedazzling() {
         let mut timeline = AetTimeline::new();
@@ -890,4 +918,61 @@ mod infiltrator_timeline_tests {
             "qeb parry head;;bite Benedicto scytherus;;hypnotise Benedicto;;suggest Benedicto Hypochondria%%qs shadow sleight void Benedicto",
         );
     }
+
+    #[test]
+    fn test_dstab_simulation_rebounds() {
+        let mut timeline = AetTimeline::new();
+        timeline
+            .state
+            .for_agent(&"Benedicto".to_string(), &move |bene| {
+                bene.set_flag(FType::Rebounding, true);
+            });
+        let dstab = DoublestabAction::new(
+            "Seurimas".to_string(),
+            "Benedicto".to_string(),
+            "kalmia",
+            "slike",
+        );
+        let events = dstab.simulate(&timeline);
+        assert_eq!(events.len(), 1);
+        assert!(events[0]
+            .get_observations()
+            .iter()
+            .any(|observation| match observation {
+                AetObservation::Rebounds => true,
+                _ => false,
+            }));
+    }
+
+    #[test]
+    fn test_dstab_simulation_outcomes() {
+        let mut timeline = AetTimeline::new();
+        timeline
+            .state
+            .for_agent(&"Benedicto".to_string(), &move |bene| {
+                bene.set_flag(FType::Rebounding, false);
+            });
+        let dstab = DoublestabAction::new(
+            "Seurimas".to_string(),
+            "Benedicto".to_string(),
+            "kalmia",
+            "slike",
+        );
+        let events = dstab.simulate(&timeline);
+        let hit = events
+            .iter()
+            .find(|event| event.get_observations().len() == 3)
+            .unwrap();
+        let total: u32 = events.iter().map(|event| event.get_weight()).sum();
+        assert!(hit.get_weight() * 2 > total);
+        assert!(events
+            .iter()
+            .any(|event| event
+                .get_observations()
+                .iter()
+                .any(|observation| match observation {
+                    AetObservation::Misses(_) => true,
+                    _ => false,
+                })));
+    }
 }
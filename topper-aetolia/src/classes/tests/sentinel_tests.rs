@@ -26,6 +26,7 @@ mod sentinel_timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Rinata".into(),
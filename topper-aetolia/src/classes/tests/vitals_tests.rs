@@ -0,0 +1,115 @@
+mod vitals_tests {
+    use super::super::*;
+    use crate::timeline::*;
+    use serde_json::json;
+    use topper_core::timeline::db::DummyDatabaseModule;
+
+    fn apply_vitals(class: Class, vitals: serde_json::Value) -> AgentState {
+        let mut me = AgentState::default();
+        get_vitals_handler(&class).unwrap()(&vitals, &mut me);
+        me
+    }
+
+    #[test]
+    fn test_bard_vitals() {
+        let me = apply_vitals(Class::Bard, json!({"dithering": "3"}));
+        assert_eq!(me.check_if_bard(&|bard| bard.dithering), Some(3));
+    }
+
+    #[test]
+    fn test_predator_vitals() {
+        let me = apply_vitals(
+            Class::Predator,
+            json!({"knife_stance": "Gyanis", "apex": "4"}),
+        );
+        assert_eq!(me.get_predator_stance(), KnifeStance::Gyanis);
+        assert_eq!(me.check_if_predator(&|predator| predator.apex), Some(4));
+    }
+
+    #[test]
+    fn test_monk_vitals() {
+        let me = apply_vitals(Class::Monk, json!({"stance": "scorpion", "kai": "12"}));
+        assert_eq!(
+            me.check_if_monk(&|monk| (monk.stance, monk.kai)),
+            Some((MonkStance::Scorpion, 12))
+        );
+    }
+
+    #[test]
+    fn test_infiltrator_vitals() {
+        let me = apply_vitals(Class::Infiltrator, json!({"finesse": "2"}));
+        assert_eq!(
+            me.check_if_infiltrator(&|infiltrator| infiltrator.finesse),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_zealot_vitals() {
+        let mut me = apply_vitals(Class::Zealot, json!({"zenith": "Rising"}));
+        if let ClassState::Zealot(zealot) = &me.class_state {
+            assert!(!zealot.zenith.can_initiate());
+            assert!(!zealot.zenith.active());
+        } else {
+            panic!("Not a zealot");
+        }
+        get_vitals_handler(&Class::Zealot).unwrap()(&json!({"zenith": "Active"}), &mut me);
+        if let ClassState::Zealot(zealot) = &me.class_state {
+            assert!(zealot.zenith.active());
+        } else {
+            panic!("Not a zealot");
+        }
+    }
+
+    #[test]
+    fn test_sentinel_vitals() {
+        let me = apply_vitals(Class::Sentinel, json!({"alacrity": "5"}));
+        if let ClassState::Sentinel(sentinel) = &me.class_state {
+            assert_eq!(sentinel.alacrity, 5);
+        } else {
+            panic!("Not a sentinel");
+        }
+    }
+
+    #[test]
+    fn test_shapeshifter_vitals() {
+        let me = apply_vitals(
+            Class::Shapeshifter,
+            json!({"snarling": "1", "echoing": "0"}),
+        );
+        if let ClassState::Shifter(howling) = &me.class_state {
+            assert!(howling.snarling);
+            assert!(!howling.echoing);
+        } else {
+            panic!("Not a shapeshifter");
+        }
+    }
+
+    #[test]
+    fn test_vitals_dispatch() {
+        let mut timeline = AetTimeline::new();
+        timeline.state.me = "Seurimas".to_string();
+        timeline.state.for_agent(&"Seurimas".to_string(), &|me| {
+            me.class_state.initialize_for_normalized_class(Class::Monk);
+        });
+        let slice = AetTimeSlice {
+            observations: None,
+            lines: Vec::new(),
+            gmcp: vec![(
+                "gmcp.Char.Vitals".to_string(),
+                json!({"stance": "cat", "kai": "3", "dithering": "2"}),
+            )],
+            observation_lines: Vec::new(),
+            prompt: AetPrompt::Promptless,
+            time: 0,
+            me: "Seurimas".to_string(),
+        };
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        let me = timeline.state.borrow_me();
+        assert_eq!(
+            me.check_if_monk(&|monk| (monk.stance, monk.kai)),
+            Some((MonkStance::Cat, 3))
+        );
+        assert_eq!(me.check_if_bard(&|bard| bard.dithering), None);
+    }
+}
@@ -32,6 +32,7 @@ mod zealot_timeline_tests {
                 ("Balance Used: 3.37 seconds".to_string(), 0),
             ],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Rinata".into(),
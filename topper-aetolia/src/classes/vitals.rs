@@ -0,0 +1,61 @@
+use super::Class;
+use crate::types::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+// Reads a class's own fields out of Char.Vitals onto one of our branches.
+pub type VitalsHandler = fn(&serde_json::Value, &mut AgentState);
+
+fn default_vitals_handlers() -> HashMap<Class, VitalsHandler> {
+    let mut handlers: HashMap<Class, VitalsHandler> = HashMap::new();
+    handlers.insert(Class::Bard, super::bard::handle_vitals);
+    handlers.insert(Class::Infiltrator, super::infiltrator::handle_vitals);
+    handlers.insert(Class::Monk, super::monk::handle_vitals);
+    handlers.insert(Class::Predator, super::predator::handle_vitals);
+    handlers.insert(Class::Sentinel, super::sentinel::handle_vitals);
+    handlers.insert(Class::Shapeshifter, super::shapeshifter::handle_vitals);
+    handlers.insert(Class::Zealot, super::zealot::handle_vitals);
+    handlers
+}
+
+lazy_static! {
+    static ref VITALS_HANDLERS: RwLock<HashMap<Class, VitalsHandler>> =
+        RwLock::new(default_vitals_handlers());
+}
+
+pub fn get_vitals_handler(class: &Class) -> Option<VitalsHandler> {
+    VITALS_HANDLERS
+        .read()
+        .unwrap()
+        .get(&class.normal())
+        .cloned()
+}
+
+pub fn set_vitals_handler(class: Class, handler: VitalsHandler) {
+    VITALS_HANDLERS
+        .write()
+        .unwrap()
+        .insert(class.normal(), handler);
+}
+
+// Vitals are all sent as strings, numbers included.
+pub fn get_vitals_str<'a>(vitals: &'a serde_json::Value, field: &str) -> Option<&'a str> {
+    vitals.get(field).and_then(|value| value.as_str())
+}
+
+pub fn get_vitals_value<T: FromStr>(vitals: &serde_json::Value, field: &str) -> Option<T> {
+    get_vitals_str(vitals, field).and_then(|value| value.parse::<T>().ok())
+}
+
+pub fn get_vitals_flag(vitals: &serde_json::Value, field: &str) -> Option<bool> {
+    match get_vitals_str(vitals, field)?.to_lowercase().as_ref() {
+        "1" | "yes" | "true" | "on" => Some(true),
+        "0" | "no" | "false" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/vitals_tests.rs"]
+mod vitals_tests;
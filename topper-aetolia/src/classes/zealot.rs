@@ -1,5 +1,5 @@
-use crate::classes::{is_affected_by, Class};
-use crate::curatives::{MENTAL_AFFLICTIONS, NORMAL_SALVE_AFFS, SOOTHING_SKIN_ORDER};
+use crate::classes::{get_vitals_str, is_affected_by, Class};
+use crate::curatives::{SafetyAlert, MENTAL_AFFLICTIONS, NORMAL_SALVE_AFFS, SOOTHING_SKIN_ORDER};
 use crate::db::AetDatabaseModule;
 use crate::defense::*;
 use crate::strum::IntoEnumIterator;
@@ -19,6 +19,25 @@ const DIREBLOW_WEAK_DAMAGE: f32 = 10.0;
 const DIREBLOW_STRONG_DAMAGE: f32 = 20.0;
 const SWAGGER_LIMIT: u8 = 4;
 
+pub fn handle_vitals(vitals: &serde_json::Value, me: &mut AgentState) {
+    match get_vitals_str(vitals, "zenith").map(|zenith| zenith.to_lowercase()) {
+        Some(zenith) if zenith.eq("active") => me.assume_zealot(|zealot| {
+            if !zealot.zenith.active() {
+                zealot.zenith.activate();
+            }
+        }),
+        Some(zenith) if zenith.eq("rising") => me.assume_zealot(|zealot| {
+            if zealot.zenith.can_initiate() {
+                zealot.zenith.initiate();
+            }
+        }),
+        Some(zenith) if zenith.eq("inactive") => {
+            me.assume_zealot(|zealot| zealot.zenith.deactivate())
+        }
+        _ => {}
+    }
+}
+
 pub fn handle_combat_action(
     combat_action: &CombatAction,
     agent_states: &mut AetTimelineState,
@@ -1895,6 +1914,16 @@ pub fn get_attack(
     attack
 }
 
+pub fn get_zealot_alerts(agent: &AgentState, zealot: &AgentState) -> Vec<SafetyAlert> {
+    if let ClassState::Zealot(ZealotClassState { pyromania, .. }) = &zealot.class_state {
+        // Pyromania stokes every flame, so putting out the fire is the way out.
+        if pyromania.active() && agent.is(FType::Ablaze) {
+            return vec![SafetyAlert::HighValueThreat(vec![FType::Ablaze])];
+        }
+    }
+    vec![]
+}
+
 #[cfg(test)]
 #[path = "./tests/zealot_tests.rs"]
 mod zealot_timeline_tests;
@@ -1,47 +1,186 @@
-use std::{fmt::Display, ops::DerefMut};
-
-use serde::*;
-use topper_bt::unpowered::*;
-
-use crate::{classes::*, db::AetDatabaseModule, timeline::*, types::*};
-
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
-pub enum SafetyAlert {
-    LowHealth,
-    LockThreat(LockType, Vec<FType>),
-    HighValueThreat(Vec<FType>),
-    InstakillThreat(Vec<FType>),
-}
-
-impl Display for SafetyAlert {
-    fn fmt(&self, f: &mut __private::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SafetyAlert::LowHealth => write!(f, "Low Health"),
-            SafetyAlert::LockThreat(lock, affs) => write!(f, "Lock Threat ({})", lock),
-            SafetyAlert::HighValueThreat(affs) => write!(f, "High Value Threat"),
-            SafetyAlert::InstakillThreat(affs) => write!(f, "Instakill Threat"),
-        }
-    }
-}
-
-pub fn gather_alerts(
-    timeline: &AetTimeline,
-    who: String,
-    db: Option<&impl AetDatabaseModule>,
-) -> Vec<SafetyAlert> {
-    let mut alerts = vec![];
-    let HEALTH_ALERT = db
-        .and_then(|db| db.get_hint(&"health_alert".to_string()))
-        .and_then(|hint| hint.parse::<i32>().ok())
-        .unwrap_or(2000);
-
-    if let Some(me_branches) = timeline.state.get_agent(&who) {
-        if me_branches
-            .iter()
-            .any(|me| me.get_stat(SType::Health) < HEALTH_ALERT)
-        {
-            alerts.push(SafetyAlert::LowHealth);
-        }
-    }
-    alerts
-}
+use std::{fmt::Display, ops::DerefMut};
+
+use serde::*;
+use topper_bt::unpowered::*;
+
+use crate::{
+    classes::*, db::AetDatabaseModule, non_agent::AetTimelinePlayersExt, timeline::*, types::*,
+};
+
+// How close a lock has to be before we hear about it.
+const LOCK_ALERT_AFFS: usize = 2;
+// As a fraction of max health. Most executes land from a quarter health, whatever we carry.
+const INSTAKILL_ALERT: f32 = 0.25;
+
+// Threat alerts carry the afflictions that would clear them, if any.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum SafetyAlert {
+    LowHealth,
+    LockThreat(LockType, Vec<FType>),
+    HighValueThreat(Vec<FType>),
+    InstakillThreat(Vec<FType>),
+}
+
+impl SafetyAlert {
+    fn same_threat(&self, other: &SafetyAlert) -> bool {
+        match (self, other) {
+            (SafetyAlert::LowHealth, SafetyAlert::LowHealth) => true,
+            (SafetyAlert::LockThreat(lock, _), SafetyAlert::LockThreat(other_lock, _)) => {
+                lock == other_lock
+            }
+            (SafetyAlert::HighValueThreat(_), SafetyAlert::HighValueThreat(_)) => true,
+            (SafetyAlert::InstakillThreat(_), SafetyAlert::InstakillThreat(_)) => true,
+            _ => false,
+        }
+    }
+
+    pub fn get_clearing_affs(&self) -> Vec<FType> {
+        match self {
+            SafetyAlert::LowHealth => vec![],
+            SafetyAlert::LockThreat(_, affs)
+            | SafetyAlert::HighValueThreat(affs)
+            | SafetyAlert::InstakillThreat(affs) => affs.clone(),
+        }
+    }
+
+    fn add_clearing_affs(&mut self, more_affs: Vec<FType>) {
+        match self {
+            SafetyAlert::LowHealth => {}
+            SafetyAlert::LockThreat(_, affs)
+            | SafetyAlert::HighValueThreat(affs)
+            | SafetyAlert::InstakillThreat(affs) => {
+                for aff in more_affs {
+                    if !affs.contains(&aff) {
+                        affs.push(aff);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn format_affs(affs: &Vec<FType>) -> String {
+    affs.iter()
+        .map(|aff| aff.to_name())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl Display for SafetyAlert {
+    fn fmt(&self, f: &mut __private::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SafetyAlert::LowHealth => write!(f, "Low Health"),
+            SafetyAlert::LockThreat(lock, affs) => {
+                write!(f, "Lock Threat ({}): {}", lock, format_affs(affs))
+            }
+            SafetyAlert::HighValueThreat(affs) if affs.is_empty() => write!(f, "High Value Threat"),
+            SafetyAlert::HighValueThreat(affs) => {
+                write!(f, "High Value Threat: {}", format_affs(affs))
+            }
+            SafetyAlert::InstakillThreat(affs) if affs.is_empty() => write!(f, "Instakill Threat"),
+            SafetyAlert::InstakillThreat(affs) => {
+                write!(f, "Instakill Threat: {}", format_affs(affs))
+            }
+        }
+    }
+}
+
+// Alerts from different branches are folded together, so each threat shows up once.
+fn push_alert(alerts: &mut Vec<SafetyAlert>, alert: SafetyAlert) {
+    if let Some(existing) = alerts
+        .iter_mut()
+        .find(|existing| existing.same_threat(&alert))
+    {
+        existing.add_clearing_affs(alert.get_clearing_affs());
+    } else {
+        alerts.push(alert);
+    }
+}
+
+fn get_lock_alerts(me: &AgentState) -> Vec<SafetyAlert> {
+    let mut alerts = vec![];
+    for lock in vec![
+        LockType::Soft,
+        LockType::Buffered,
+        LockType::HardVenom,
+        LockType::Hard,
+    ] {
+        if lock.affs_to_lock(me) <= LOCK_ALERT_AFFS {
+            // Curing any lock aff we already have pushes the lock back out of reach.
+            let affs = lock.affs().into_iter().filter(|aff| me.is(*aff)).collect();
+            alerts.push(SafetyAlert::LockThreat(lock, affs));
+        }
+    }
+    alerts
+}
+
+// Everyone who has been hitting us, plus any known enemies, with whatever class we know them by.
+fn get_threats(
+    timeline: &AetTimeline,
+    who: &String,
+    db: Option<&impl AetDatabaseModule>,
+) -> Vec<(Class, AgentState)> {
+    let me = timeline.state.borrow_agent(who);
+    let mut names = me.aggro.get_aggro_attackers();
+    if who.eq(&timeline.who_am_i()) {
+        for enemy in timeline.state.get_enemies() {
+            if !names.contains(&enemy) {
+                names.push(enemy);
+            }
+        }
+    }
+    names
+        .into_iter()
+        .filter(|name| !name.eq(who))
+        .filter_map(|name| {
+            let threat = timeline.state.borrow_agent(&name);
+            db.and_then(|db| db.get_class(&name))
+                .or_else(|| threat.get_normalized_class())
+                .map(|class| (class, threat))
+        })
+        .collect()
+}
+
+pub fn gather_alerts(
+    timeline: &AetTimeline,
+    who: String,
+    db: Option<&impl AetDatabaseModule>,
+) -> Vec<SafetyAlert> {
+    let mut alerts = vec![];
+    let HEALTH_ALERT = db
+        .and_then(|db| db.get_hint(&"health_alert".to_string()))
+        .and_then(|hint| hint.parse::<i32>().ok())
+        .unwrap_or(2000);
+    let instakill_alert = db
+        .and_then(|db| db.get_hint(&"instakill_alert".to_string()))
+        .and_then(|hint| hint.parse::<f32>().ok())
+        .unwrap_or(INSTAKILL_ALERT);
+
+    if let Some(me_branches) = timeline.state.get_agent(&who) {
+        if me_branches
+            .iter()
+            .any(|me| me.get_stat(SType::Health) < HEALTH_ALERT)
+        {
+            alerts.push(SafetyAlert::LowHealth);
+        }
+        let threats = get_threats(timeline, &who, db);
+        for me in me_branches.iter() {
+            if me.get_health_percent() < instakill_alert {
+                push_alert(&mut alerts, SafetyAlert::InstakillThreat(vec![]));
+            }
+            for alert in get_lock_alerts(me) {
+                push_alert(&mut alerts, alert);
+            }
+            for (class, threat) in threats.iter() {
+                for alert in class.get_safety_alerts(me, threat) {
+                    push_alert(&mut alerts, alert);
+                }
+            }
+        }
+    }
+    alerts
+}
+
+#[cfg(test)]
+#[path = "./tests/alerts_tests.rs"]
+mod alerts_tests;
@@ -0,0 +1,349 @@
+use super::statics::*;
+use crate::types::*;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{RwLock, RwLockReadGuard};
+
+// The cure orders every cure is resolved against. Starts out as the built-in tables, and can be
+// updated from the game's own HELP text when curing is rebalanced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CureOrders {
+    pub pills: HashMap<String, Vec<FType>>,
+    pub salves: HashMap<(String, String), Vec<FType>>,
+    pub smokes: HashMap<String, Vec<FType>>,
+    pub restoration: HashMap<LType, Vec<FType>>,
+    pub pill_defences: HashMap<String, FType>,
+    affliction_pills: HashMap<FType, String>,
+    affliction_salves: HashMap<FType, (String, String)>,
+    affliction_smokes: HashMap<FType, String>,
+}
+
+lazy_static! {
+    static ref CURE_ORDERS: RwLock<CureOrders> = RwLock::new(CureOrders::builtin());
+}
+
+pub fn cure_orders() -> RwLockReadGuard<'static, CureOrders> {
+    CURE_ORDERS.read().unwrap()
+}
+
+pub fn set_cure_orders(orders: CureOrders) {
+    *CURE_ORDERS.write().unwrap() = orders;
+}
+
+pub fn reset_cure_orders() {
+    set_cure_orders(CureOrders::builtin());
+}
+
+// Loads the HELP text on top of the built-in tables, from JSON shaped like docs/cure_order.json.
+pub fn load_cure_orders(help_json: &str) -> Result<(), String> {
+    let help = serde_json::from_str::<CureOrderHelp>(help_json).map_err(|err| err.to_string())?;
+    let mut orders = CureOrders::builtin();
+    orders.update_from(parse_cure_order_help(&help.body));
+    set_cure_orders(orders);
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct CureOrderHelp {
+    body: Vec<String>,
+}
+
+// Keeps each affliction on the cure it had before, so long as that cure still takes it.
+fn index_afflictions<K: Clone + Ord + Eq + Hash>(
+    orders: &HashMap<K, Vec<FType>>,
+    previous: &HashMap<FType, K>,
+) -> HashMap<FType, K> {
+    let mut index = HashMap::new();
+    let mut keys: Vec<&K> = orders.keys().collect();
+    keys.sort();
+    for key in keys {
+        for aff in orders[key].iter() {
+            match previous.get(aff) {
+                Some(previous_key) if orders[previous_key].contains(aff) => {
+                    index.insert(*aff, previous_key.clone());
+                }
+                _ => {
+                    index.entry(*aff).or_insert_with(|| key.clone());
+                }
+            }
+        }
+    }
+    index
+}
+
+fn update_orders<K: Eq + Hash>(
+    orders: &mut HashMap<K, Vec<FType>>,
+    loaded: HashMap<K, Vec<FType>>,
+) {
+    for (key, order) in loaded {
+        // Anything the built-in tables do not know is handled specially, if at all.
+        if order.len() > 0 && orders.contains_key(&key) {
+            orders.insert(key, order);
+        }
+    }
+}
+
+impl CureOrders {
+    fn empty() -> Self {
+        CureOrders {
+            pills: HashMap::new(),
+            salves: HashMap::new(),
+            smokes: HashMap::new(),
+            restoration: HashMap::new(),
+            pill_defences: HashMap::new(),
+            affliction_pills: HashMap::new(),
+            affliction_salves: HashMap::new(),
+            affliction_smokes: HashMap::new(),
+        }
+    }
+
+    pub fn builtin() -> Self {
+        CureOrders {
+            pills: PILL_CURE_ORDERS.clone(),
+            salves: SALVE_CURE_ORDERS.clone(),
+            smokes: SMOKE_CURE_ORDERS.clone(),
+            restoration: RESTORE_CURE_ORDERS.clone(),
+            pill_defences: PILL_DEFENCES.clone(),
+            affliction_pills: AFFLICTION_PILLS.clone(),
+            affliction_salves: AFFLICTION_SALVES.clone(),
+            affliction_smokes: AFFLICTION_SMOKES.clone(),
+        }
+    }
+
+    pub fn update_from(&mut self, loaded: CureOrders) {
+        update_orders(&mut self.pills, loaded.pills);
+        update_orders(&mut self.salves, loaded.salves);
+        update_orders(&mut self.smokes, loaded.smokes);
+        update_orders(&mut self.restoration, loaded.restoration);
+        for (pill, defence) in loaded.pill_defences {
+            if self.pill_defences.contains_key(&pill) {
+                self.pill_defences.insert(pill, defence);
+            }
+        }
+        self.affliction_pills = index_afflictions(&self.pills, &self.affliction_pills);
+        self.affliction_salves = index_afflictions(&self.salves, &self.affliction_salves);
+        self.affliction_smokes = index_afflictions(&self.smokes, &self.affliction_smokes);
+    }
+
+    pub fn pill_order(&self, pill: &str) -> Option<&Vec<FType>> {
+        self.pills.get(pill)
+    }
+
+    pub fn salve_order(&self, salve: &str, location: &str) -> Option<&Vec<FType>> {
+        self.salves.get(&(salve.to_string(), location.to_string()))
+    }
+
+    pub fn smoke_order(&self, herb: &str) -> Option<&Vec<FType>> {
+        self.smokes.get(herb)
+    }
+
+    pub fn restore_order(&self, limb: LType) -> Option<&Vec<FType>> {
+        self.restoration.get(&limb)
+    }
+
+    pub fn pill_defence(&self, pill: &str) -> Option<FType> {
+        self.pill_defences.get(pill).cloned()
+    }
+
+    pub fn affliction_pill(&self, aff: FType) -> Option<&String> {
+        self.affliction_pills.get(&aff)
+    }
+
+    pub fn affliction_salve(&self, aff: FType) -> Option<&(String, String)> {
+        self.affliction_salves.get(&aff)
+    }
+
+    pub fn affliction_smoke(&self, aff: FType) -> Option<&String> {
+        self.affliction_smokes.get(&aff)
+    }
+
+    pub fn pill_afflictions(&self) -> Vec<FType> {
+        self.affliction_pills.keys().cloned().collect()
+    }
+
+    pub fn salve_afflictions(&self) -> Vec<FType> {
+        self.affliction_salves.keys().cloned().collect()
+    }
+
+    pub fn smoke_afflictions(&self) -> Vec<FType> {
+        self.affliction_smokes.keys().cloned().collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum HelpSection {
+    Preamble,
+    Pills,
+    PillDefences,
+    Smoked,
+    Elixirs,
+    Poultices,
+}
+
+lazy_static! {
+    static ref CURES_HEADER: Regex = Regex::new(r"^\*?\s*(\w+) cures:$").unwrap();
+    static ref LOCATION_LINE: Regex = Regex::new(r"^([A-Z][A-Z ]+): (.*)$").unwrap();
+    static ref PILL_DEFENCE_LINE: Regex = Regex::new(r"^(\S.*?)\s{2,}(\S.*?)\.?$").unwrap();
+}
+
+fn limb_prefix(location: &str) -> Option<&'static str> {
+    match location {
+        "head" => Some("Head"),
+        "torso" => Some("Torso"),
+        "left arm" => Some("LeftArm"),
+        "right arm" => Some("RightArm"),
+        "left leg" => Some("LeftLeg"),
+        "right leg" => Some("RightLeg"),
+        _ => None,
+    }
+}
+
+// Limb afflictions are named generically in the HELP text, and only make sense with a location.
+fn parse_limb_aff(name: &str, location: &str) -> Option<FType> {
+    let prefix = limb_prefix(location)?;
+    let suffix = match name {
+        "crit. bruising" => "BruisedCritical",
+        "mod. bruising" => "BruisedModerate",
+        "bruising" => "Bruised",
+        "broken arm" | "broken leg" => "Crippled",
+        "dislocation" => "Dislocated",
+        _ if name.starts_with("mangled ") => "Mangled",
+        _ if name.starts_with("damaged ") => "Broken",
+        _ if name.starts_with("amputated ") => "Amputated",
+        _ => return None,
+    };
+    format!("{}{}", prefix, suffix).parse::<FType>().ok()
+}
+
+fn parse_cure_item(item: &str, location: Option<&str>) -> Vec<FType> {
+    let mut affs = Vec::new();
+    for name in item.split('/') {
+        let name = name.trim();
+        if name.eq("rot") {
+            affs.extend(vec![
+                FType::RotBody,
+                FType::RotWither,
+                FType::RotHeat,
+                FType::RotSpirit,
+                FType::RotBenign,
+            ]);
+        } else if let Some(aff) = location.and_then(|location| parse_limb_aff(name, location)) {
+            affs.push(aff);
+        } else if let Some(aff) = FType::from_name(&name.to_string()) {
+            affs.push(aff);
+        }
+        // Anything else is a defence or a heal, and not part of the order.
+    }
+    affs
+}
+
+fn parse_cure_list(list: &str, location: Option<&str>) -> Vec<FType> {
+    list.split(',')
+        .map(|item| item.trim())
+        .map(|item| item.strip_prefix("and ").unwrap_or(item))
+        // Two item lists skip the comma, as in "sore wrist and weak grip".
+        .flat_map(|item| item.split(" and "))
+        .flat_map(|item| parse_cure_item(item, location))
+        .collect()
+}
+
+fn flush_lists(
+    orders: &mut CureOrders,
+    section: &HelpSection,
+    curative: &Option<String>,
+    pending: &mut Vec<(Option<String>, String)>,
+) {
+    if let Some(curative) = curative {
+        for (location, list) in pending.drain(..) {
+            let affs = parse_cure_list(&list, location.as_deref());
+            match (section, location) {
+                (HelpSection::Poultices, Some(location)) if curative.eq("restoration") => {
+                    let limb = LType::from_name(&location);
+                    if limb != LType::SIZE {
+                        orders.restoration.insert(limb, affs);
+                    }
+                }
+                (HelpSection::Poultices, Some(location)) => {
+                    orders.salves.insert((curative.clone(), location), affs);
+                }
+                (HelpSection::Smoked, None) => {
+                    orders.smokes.insert(curative.clone(), affs);
+                }
+                (HelpSection::Pills, None) => {
+                    orders.pills.insert(curative.clone(), affs);
+                }
+                _ => {}
+            }
+        }
+    }
+    pending.clear();
+}
+
+// Parses the HELP CURE ORDER text into cure orders. Only what is listed is filled in.
+pub fn parse_cure_order_help(lines: &Vec<String>) -> CureOrders {
+    let mut orders = CureOrders::empty();
+    let mut section = HelpSection::Preamble;
+    let mut curative: Option<String> = None;
+    // Lists wrap across lines, so each is gathered up until a blank line ends it.
+    let mut pending: Vec<(Option<String>, String)> = Vec::new();
+    for line in lines.iter() {
+        let line = line.trim();
+        let new_section = match line {
+            "PILL CURES" => Some(HelpSection::Pills),
+            "PILL DEFENCES" => Some(HelpSection::PillDefences),
+            "SMOKED" => Some(HelpSection::Smoked),
+            "ELIXIRS" => Some(HelpSection::Elixirs),
+            "POULTICES" => Some(HelpSection::Poultices),
+            _ => None,
+        };
+        if let Some(new_section) = new_section {
+            flush_lists(&mut orders, &section, &curative, &mut pending);
+            curative = None;
+            section = new_section;
+            continue;
+        }
+        if line.is_empty() {
+            flush_lists(&mut orders, &section, &curative, &mut pending);
+            continue;
+        } else if line.starts_with("---") {
+            continue;
+        }
+        match section {
+            HelpSection::Pills | HelpSection::Smoked | HelpSection::Poultices => {
+                if let Some(captures) = CURES_HEADER.captures(line) {
+                    flush_lists(&mut orders, &section, &curative, &mut pending);
+                    curative = Some(captures[1].to_ascii_lowercase());
+                } else if curative.is_none() {
+                } else if let Some(captures) = LOCATION_LINE.captures(line) {
+                    pending.push((
+                        Some(captures[1].to_ascii_lowercase()),
+                        captures[2].to_string(),
+                    ));
+                } else if let Some((_location, list)) = pending.last_mut() {
+                    list.push(' ');
+                    list.push_str(line);
+                } else {
+                    pending.push((None, line.to_string()));
+                }
+            }
+            HelpSection::PillDefences => {
+                if let Some(captures) = PILL_DEFENCE_LINE.captures(line) {
+                    if let Some(defence) = FType::from_name(&captures[2].to_ascii_lowercase()) {
+                        orders
+                            .pill_defences
+                            .insert(captures[1].to_ascii_lowercase(), defence);
+                    }
+                }
+            }
+            HelpSection::Preamble | HelpSection::Elixirs => {}
+        }
+    }
+    flush_lists(&mut orders, &section, &curative, &mut pending);
+    orders
+}
+
+#[cfg(test)]
+#[path = "./tests/cure_orders_tests.rs"]
+mod cure_orders_tests;
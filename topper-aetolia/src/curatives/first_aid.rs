@@ -1,3 +1,4 @@
+use super::cure_orders::cure_orders;
 use super::statics::*;
 use crate::observables::*;
 use crate::timeline::*;
@@ -222,6 +223,9 @@ lazy_static! {
 
 pub type FirstAidPriorities = HashMap<FType, u32>;
 
+// The lowest slot in the game's priority list.
+const LAST_PRIORITY: u32 = 26;
+
 fn add_priorities(priorities: &mut FirstAidPriorities, priority: u32, aff_list: &str) {
     for mut aff_str in aff_list.split(", ") {
         aff_str = aff_str.trim_end_matches(&[',', ' '][..]);
@@ -262,7 +266,7 @@ pub fn parse_priority_set(lines: &Vec<(String, u32)>) -> Option<(String, FirstAi
     priority_name.map(|name| (name, parse_priorities(&priority_lines)))
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct FirstAid {
     simple_priorities: HashMap<FType, u32>,
     use_tree: bool,
@@ -324,18 +328,70 @@ impl FirstAid {
         }
     }
 
+    pub fn from_priorities(simple_priorities: FirstAidPriorities) -> Self {
+        FirstAid {
+            simple_priorities,
+            use_tree: true,
+            use_focus: true,
+        }
+    }
+
+    pub fn get_priorities(&self) -> &FirstAidPriorities {
+        &self.simple_priorities
+    }
+
+    // Whether every priority in the set is already in place, ignoring anything the set leaves out.
+    pub fn has_priorities(&self, priorities: &FirstAidPriorities) -> bool {
+        priorities
+            .iter()
+            .all(|(aff, priority)| self.simple_priorities.get(aff) == Some(priority))
+    }
+
+    // The priorities we end up with after swapping to the given set. Anything the set leaves out
+    // goes back to its default, or to the last slot if it has none.
+    pub fn get_swapped_priorities(&self, priorities: &FirstAidPriorities) -> FirstAidPriorities {
+        let defaults = FirstAid::new();
+        let mut swapped = priorities.clone();
+        for aff in self.simple_priorities.keys() {
+            if !swapped.contains_key(aff) {
+                let priority = defaults
+                    .simple_priorities
+                    .get(aff)
+                    .cloned()
+                    .unwrap_or(LAST_PRIORITY);
+                swapped.insert(*aff, priority);
+            }
+        }
+        swapped
+    }
+
+    // The in-game commands that move these priorities over to the given set.
+    pub fn get_priority_commands(&self, priorities: &FirstAidPriorities) -> Vec<String> {
+        let swapped = self.get_swapped_priorities(priorities);
+        let mut changes: Vec<(&FType, &u32)> = swapped
+            .iter()
+            .filter(|(aff, priority)| self.simple_priorities.get(aff) != Some(priority))
+            .collect();
+        changes.sort_by_key(|(aff, priority)| (**priority, aff.to_name()));
+        changes
+            .into_iter()
+            .map(|(aff, priority)| format!("firstaid priority {} {}", aff.to_name(), priority))
+            .collect()
+    }
+
     fn best_cure(&self, who_am_i: &str, state: &AgentState, aff: &FType) -> FirstAidAction {
-        if let Some(herb) = AFFLICTION_SMOKES.get(aff) {
+        let cure_orders = cure_orders();
+        if let Some(herb) = cure_orders.affliction_smoke(*aff) {
             if state.can_smoke(false) {
                 return FirstAidAction::Simple(SimpleCureAction::smoke(&who_am_i, &herb));
             }
         }
-        if let Some(pill) = AFFLICTION_PILLS.get(aff) {
+        if let Some(pill) = cure_orders.affliction_pill(*aff) {
             if state.can_pill(false) {
                 return FirstAidAction::Simple(SimpleCureAction::pill(&who_am_i, &pill));
             }
         }
-        if let Some((salve, location)) = AFFLICTION_SALVES.get(aff) {
+        if let Some((salve, location)) = cure_orders.affliction_salve(*aff) {
             if state.can_salve(false) {
                 return FirstAidAction::Simple(SimpleCureAction::salve(
                     &who_am_i, &salve, &location,
@@ -0,0 +1,297 @@
+use crate::classes::{Class, VENOM_AFFLICTS};
+use crate::timeline::*;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+// How to weigh an opponent's hidden afflictions, and what each way of finding them costs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HiddenAffTable {
+    // How much leaving each affliction uncured hurts. Afflictions not listed are not guessed at.
+    pub values: HashMap<FType, f32>,
+    // Relative odds that this class is the source of each affliction. Defaults to 1.
+    pub likelihoods: HashMap<FType, f32>,
+    // Skills that can hide afflictions, and the afflictions they hide.
+    pub skills: HashMap<String, Vec<FType>>,
+    // How much each sighting of a venom or skill shifts the odds.
+    pub evidence_weight: f32,
+    pub diagnose_cost: f32,
+    // Lost to a wrong prediction, which keeps first aid curing nothing.
+    pub predict_cost: f32,
+    // Lost to a wrong blind cure, which burns the curative's balance.
+    pub blind_cure_cost: f32,
+    // Gained by a right blind cure, for not waiting on first aid.
+    pub blind_cure_bonus: f32,
+}
+
+impl Default for HiddenAffTable {
+    fn default() -> Self {
+        let mut values = HashMap::new();
+        values.insert(FType::Impatience, 11.0);
+        values.insert(FType::Anorexia, 11.0);
+        values.insert(FType::Asthma, 10.0);
+        values.insert(FType::Faintness, 10.0);
+        values.insert(FType::Lethargy, 9.0);
+        values.insert(FType::Dizziness, 8.0);
+        values.insert(FType::Clumsiness, 8.0);
+        values.insert(FType::Hypersomnia, 7.0);
+        values.insert(FType::Stupidity, 6.0);
+        values.insert(FType::Paresis, 5.0);
+        values.insert(FType::Weariness, 4.0);
+        values.insert(FType::Recklessness, 3.0);
+        HiddenAffTable {
+            values,
+            likelihoods: HashMap::new(),
+            skills: HashMap::new(),
+            evidence_weight: 0.5,
+            diagnose_cost: 6.0,
+            predict_cost: 1.0,
+            blind_cure_cost: 3.0,
+            blind_cure_bonus: 2.0,
+        }
+    }
+}
+
+lazy_static! {
+    static ref HIDDEN_AFF_TABLES: RwLock<HashMap<Class, HiddenAffTable>> =
+        RwLock::new(HashMap::new());
+}
+
+pub fn get_hidden_aff_table(class: &Option<Class>) -> HiddenAffTable {
+    class
+        .and_then(|class| {
+            HIDDEN_AFF_TABLES
+                .read()
+                .unwrap()
+                .get(&class.normal())
+                .cloned()
+        })
+        .unwrap_or_default()
+}
+
+pub fn set_hidden_aff_table(class: Class, table: HiddenAffTable) {
+    HIDDEN_AFF_TABLES
+        .write()
+        .unwrap()
+        .insert(class.normal(), table);
+}
+
+// Loads tables from JSON keyed by class name. Classes left out fall back to the default table.
+pub fn load_hidden_aff_tables(tables_json: &str) -> Result<(), String> {
+    let tables = serde_json::from_str::<HashMap<String, HiddenAffTable>>(tables_json)
+        .map_err(|err| err.to_string())?;
+    let mut loaded = HashMap::new();
+    for (class_name, table) in tables {
+        let class =
+            Class::from_str(&class_name).ok_or_else(|| format!("Unknown class {}", class_name))?;
+        loaded.insert(class.normal(), table);
+    }
+    *HIDDEN_AFF_TABLES.write().unwrap() = loaded;
+    Ok(())
+}
+
+// What the opponent has been seen doing to us since our afflictions were last all known.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HiddenAffEvidence {
+    pub skills: HashMap<String, u32>,
+    pub venoms: HashMap<String, u32>,
+}
+
+impl HiddenAffEvidence {
+    pub fn observe(&mut self, observations: &Vec<AetObservation>, me: &String, foe: &String) {
+        let mut from_foe = false;
+        for observation in observations.iter() {
+            match observation {
+                AetObservation::CombatAction(CombatAction {
+                    caster,
+                    target,
+                    skill,
+                    ..
+                }) => {
+                    from_foe = caster.eq(foe) && target.eq(me);
+                    if from_foe {
+                        *self.skills.entry(skill.to_lowercase()).or_insert(0) += 1;
+                    }
+                }
+                AetObservation::Devenoms(venom) if from_foe => {
+                    *self.venoms.entry(venom.clone()).or_insert(0) += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.skills.clear();
+        self.venoms.clear();
+    }
+
+    fn sightings(&self, table: &HiddenAffTable, aff: FType) -> u32 {
+        let venoms: u32 = self
+            .venoms
+            .iter()
+            .filter(|(venom, _count)| VENOM_AFFLICTS.get(*venom) == Some(&aff))
+            .map(|(_venom, count)| *count)
+            .sum();
+        let skills: u32 = self
+            .skills
+            .iter()
+            .filter(|(skill, _count)| {
+                table
+                    .skills
+                    .get(*skill)
+                    .map_or(false, |affs| affs.contains(&aff))
+            })
+            .map(|(_skill, count)| *count)
+            .sum();
+        venoms + skills
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HiddenAffChoice {
+    Diagnose,
+    Predict(FType),
+    BlindCure(FType),
+}
+
+// Afflictions with a cure that can be used without knowing we have them.
+pub fn has_blind_cure(aff: FType) -> bool {
+    match aff {
+        FType::Anorexia
+        | FType::Hypersomnia
+        | FType::Paresis
+        | FType::Asthma
+        | FType::Weariness
+        | FType::Impatience
+        | FType::Superstition => true,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HiddenAffPrediction {
+    // The chance each candidate is actually hidden on us.
+    pub probabilities: Vec<(FType, f32)>,
+    pub choice: Option<(HiddenAffChoice, f32)>,
+}
+
+impl HiddenAffPrediction {
+    pub fn probability(&self, aff: FType) -> f32 {
+        self.probabilities
+            .iter()
+            .find(|(candidate, _probability)| *candidate == aff)
+            .map_or(0.0, |(_candidate, probability)| *probability)
+    }
+}
+
+fn shift_odds(probability: f32, weight: f32) -> f32 {
+    let shifted = probability * weight;
+    if shifted > 0.0 {
+        shifted / (shifted + (1.0 - probability))
+    } else {
+        0.0
+    }
+}
+
+// Combines what each branch has guessed with what the opponent is likely to have given us.
+// Guessed afflictions take the share of branches guessing them, and afflictions that are
+// hidden without a guess are spread across every affliction the table cares about.
+pub fn predict_hidden_affs(
+    branches: &Vec<AgentState>,
+    table: &HiddenAffTable,
+    evidence: &HiddenAffEvidence,
+) -> HiddenAffPrediction {
+    let weight = |aff: FType| {
+        table.likelihoods.get(&aff).cloned().unwrap_or(1.0)
+            * (1.0 + table.evidence_weight * evidence.sightings(table, aff) as f32)
+    };
+    let branch_count = branches.len().max(1) as f32;
+    // Guesses know nothing of the weights, so only they get their odds shifted. The unknown
+    // spread is already drawn by weight.
+    let mut guessed: HashMap<FType, f32> = HashMap::new();
+    let mut spread: HashMap<FType, f32> = HashMap::new();
+    for branch in branches.iter() {
+        for aff in branch.hidden_state.iter_guesses() {
+            if table.values.contains_key(aff) {
+                *guessed.entry(*aff).or_insert(0.0) += 1.0 / branch_count;
+            }
+        }
+        let unknown = branch.hidden_state.unknown();
+        if unknown > 0 {
+            let candidates: Vec<FType> = table
+                .values
+                .keys()
+                .filter(|aff| !branch.is(**aff))
+                .cloned()
+                .collect();
+            let total: f32 = candidates.iter().map(|aff| weight(*aff)).sum();
+            if total > 0.0 {
+                for aff in candidates {
+                    let missed = (1.0 - weight(aff) / total).powi(unknown as i32);
+                    *spread.entry(aff).or_insert(0.0) += (1.0 - missed) / branch_count;
+                }
+            }
+        }
+    }
+    let mut shares: HashMap<FType, f32> = guessed
+        .into_iter()
+        .map(|(aff, share)| (aff, shift_odds(share.min(1.0), weight(aff))))
+        .collect();
+    for (aff, share) in spread.into_iter() {
+        *shares.entry(aff).or_insert(0.0) += share;
+    }
+    let mut probabilities: Vec<(FType, f32)> = shares
+        .into_iter()
+        .map(|(aff, share)| (aff, share.min(1.0)))
+        .collect();
+    probabilities.sort_by(|(aff_a, a), (aff_b, b)| b.total_cmp(a).then(aff_a.cmp(aff_b)));
+    let choice = choose_hidden_cure(&probabilities, table);
+    HiddenAffPrediction {
+        probabilities,
+        choice,
+    }
+}
+
+// Picks whichever way of dealing with the hidden afflictions is worth the most, if any are.
+pub fn choose_hidden_cure(
+    probabilities: &Vec<(FType, f32)>,
+    table: &HiddenAffTable,
+) -> Option<(HiddenAffChoice, f32)> {
+    let value = |aff: &FType| table.values.get(aff).cloned().unwrap_or(0.0);
+    let mut options = Vec::new();
+    if probabilities.len() > 1 {
+        let known_value: f32 = probabilities
+            .iter()
+            .map(|(aff, probability)| probability * value(aff))
+            .sum();
+        options.push((HiddenAffChoice::Diagnose, known_value - table.diagnose_cost));
+    }
+    for (aff, probability) in probabilities.iter() {
+        let gained = probability * value(aff);
+        options.push((
+            HiddenAffChoice::Predict(*aff),
+            gained - (1.0 - probability) * table.predict_cost,
+        ));
+        if has_blind_cure(*aff) {
+            options.push((
+                HiddenAffChoice::BlindCure(*aff),
+                gained + probability * table.blind_cure_bonus
+                    - (1.0 - probability) * table.blind_cure_cost,
+            ));
+        }
+    }
+    options
+        .into_iter()
+        .filter(|(_choice, expected)| *expected > 0.0)
+        .fold(None, |best, option| match best {
+            Some((_, best_expected)) if best_expected >= option.1 => best,
+            _ => Some(option),
+        })
+}
+
+#[cfg(test)]
+#[path = "./tests/hidden_prediction_tests.rs"]
+mod hidden_prediction_tests;
@@ -0,0 +1,222 @@
+use super::first_aid::FirstAid;
+use super::stack_evaluation::{lock_types, StackRollout, TrialResult};
+use crate::classes::{get_curing_profile, ClassCuringProfile, LockType, VenomPlan};
+use crate::timeline::*;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockForecastConfig {
+    // Rollouts per branch of the target.
+    pub samples: usize,
+    pub seed: u64,
+    // In seconds. Locks further out than this are not forecast.
+    pub horizon: f32,
+    pub venoms_per_attack: usize,
+    // Seconds between attacks, when we have not yet seen how long our own attacks take.
+    pub attack_balance: f32,
+}
+
+impl Default for LockForecastConfig {
+    fn default() -> Self {
+        LockForecastConfig {
+            samples: 8,
+            seed: 0,
+            horizon: 15.0,
+            venoms_per_attack: 2,
+            attack_balance: 2.8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockEta {
+    pub lock: LockType,
+    // Chance of reaching the lock within the horizon.
+    pub probability: f32,
+    // In seconds from now.
+    pub earliest: Option<f32>,
+    pub latest: Option<f32>,
+    pub expected: Option<f32>,
+}
+
+impl fmt::Display for LockEta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.earliest, self.latest) {
+            (Some(earliest), Some(latest)) => write!(
+                f,
+                "{} {:.1}-{:.1}s ({:.0}%)",
+                self.lock,
+                earliest,
+                latest,
+                self.probability * 100.0
+            ),
+            _ => write!(f, "{} -", self.lock),
+        }
+    }
+}
+
+// Forecasts when each lock lands, playing the stack out from every branch the target might be in.
+// Branches are weighted equally, and our first attack waits out our remaining balance.
+pub fn forecast_locks(
+    branches: &Vec<AgentState>,
+    stack: &Vec<VenomPlan>,
+    first_aid: &FirstAid,
+    profile: &ClassCuringProfile,
+    config: &LockForecastConfig,
+    first_attack: f32,
+) -> Vec<LockEta> {
+    let rollout = StackRollout {
+        stack,
+        first_aid,
+        profile,
+        venoms_per_attack: config.venoms_per_attack,
+        attack_balance: config.attack_balance,
+        time_limit: config.horizon,
+    };
+    let mut results: Vec<TrialResult> = Vec::new();
+    for branch in branches.iter() {
+        for _sample in 0..config.samples {
+            let seed = config.seed.wrapping_add(results.len() as u64);
+            results.push(rollout.run(branch.clone(), first_attack, seed));
+        }
+    }
+    lock_types()
+        .into_iter()
+        .enumerate()
+        .map(|(index, lock)| {
+            let times: Vec<f32> = results
+                .iter()
+                .filter_map(|result| result.lock_times[index])
+                .collect();
+            LockEta {
+                lock,
+                probability: times.len() as f32 / results.len().max(1) as f32,
+                earliest: times.iter().cloned().reduce(f32::min),
+                latest: times.iter().cloned().reduce(f32::max),
+                expected: if times.len() > 0 {
+                    Some(times.iter().sum::<f32>() / times.len() as f32)
+                } else {
+                    None
+                },
+            }
+        })
+        .collect()
+}
+
+// Everything a forecast was rolled out from. The branches themselves are part of it, so timelines
+// branched off for simulation never share a forecast by accident.
+#[derive(PartialEq)]
+struct ForecastKey {
+    branches: Vec<AgentState>,
+    stack: Vec<VenomPlan>,
+    config: LockForecastConfig,
+    first_attack: f32,
+}
+
+const MAX_CACHED_FORECASTS: usize = 16;
+
+lazy_static! {
+    // Forecasts made at the latest timeline time. Trees and the battle stats both ask on every
+    // prompt, often more than once, and the rollouts are far too costly to repeat.
+    static ref FORECASTS: Mutex<(CType, Vec<(ForecastKey, Vec<LockEta>)>)> =
+        Mutex::new((0, Vec::new()));
+}
+
+fn cached_forecast(
+    time: CType,
+    key: ForecastKey,
+    forecast: impl FnOnce(&ForecastKey) -> Vec<LockEta>,
+) -> Vec<LockEta> {
+    if let Some(etas) = FORECASTS.lock().ok().and_then(|forecasts| {
+        if forecasts.0 == time {
+            forecasts
+                .1
+                .iter()
+                .find(|(cached, _etas)| *cached == key)
+                .map(|(_key, etas)| etas.clone())
+        } else {
+            None
+        }
+    }) {
+        return etas;
+    }
+    let etas = forecast(&key);
+    if let Ok(mut forecasts) = FORECASTS.lock() {
+        if forecasts.0 != time {
+            *forecasts = (time, Vec::new());
+        } else if forecasts.1.len() >= MAX_CACHED_FORECASTS {
+            forecasts.1.remove(0);
+        }
+        forecasts.1.push((key, etas.clone()));
+    }
+    etas
+}
+
+// How long our last attack kept us off balance, if we have seen one.
+pub fn get_attack_balance(timeline: &AetTimeline, config: &LockForecastConfig) -> f32 {
+    timeline
+        .state
+        .get_player_hint(&timeline.who_am_i(), &ATTACK_BALANCE_HINT.to_string())
+        .and_then(|seconds| seconds.parse::<f32>().ok())
+        .unwrap_or(config.attack_balance)
+}
+
+// Forecasts locks on the given branches of a target. The target cures with their class's cures,
+// and with first aid on the game's default priorities, since nobody else's are visible to us. Our
+// attacks come as often as our last one kept us off balance, once we have seen one.
+pub fn forecast_branch_locks(
+    timeline: &AetTimeline,
+    branches: Vec<AgentState>,
+    stack: &Vec<VenomPlan>,
+    config: &LockForecastConfig,
+) -> Vec<LockEta> {
+    let config = LockForecastConfig {
+        attack_balance: get_attack_balance(timeline, config),
+        ..config.clone()
+    };
+    let key = ForecastKey {
+        branches,
+        stack: stack.clone(),
+        config,
+        first_attack: timeline.state.borrow_me().get_qeb_balance(),
+    };
+    cached_forecast(timeline.state.time, key, |key| {
+        let profile = key
+            .branches
+            .iter()
+            .find_map(|branch| branch.class_state.get_normalized_class())
+            .map(|class| get_curing_profile(&class))
+            .unwrap_or_default();
+        forecast_locks(
+            &key.branches,
+            &key.stack,
+            &FirstAid::new(),
+            &profile,
+            &key.config,
+            key.first_attack,
+        )
+    })
+}
+
+// Forecasts locks on every branch of a target we are tracking.
+pub fn forecast_target_locks(
+    timeline: &AetTimeline,
+    target: &String,
+    stack: &Vec<VenomPlan>,
+    config: &LockForecastConfig,
+) -> Vec<LockEta> {
+    let branches = timeline
+        .state
+        .get_agent(target)
+        .cloned()
+        .unwrap_or_else(|| vec![timeline.state.borrow_agent(target)]);
+    forecast_branch_locks(timeline, branches, stack, config)
+}
+
+#[cfg(test)]
+#[path = "./tests/lock_forecast_tests.rs"]
+mod lock_forecast_tests;
@@ -1,6 +1,11 @@
 pub mod alerts;
 pub mod behavior;
+pub mod cure_orders;
 pub mod first_aid;
+pub mod hidden_prediction;
+pub mod lock_forecast;
+pub mod self_curing;
+pub mod stack_evaluation;
 pub mod statics;
 
 use crate::timeline::*;
@@ -8,7 +13,12 @@ use crate::types::*;
 
 pub use alerts::*;
 pub use behavior::*;
+pub use cure_orders::*;
 pub use first_aid::*;
+pub use hidden_prediction::*;
+pub use lock_forecast::*;
+pub use self_curing::*;
+pub use stack_evaluation::*;
 pub use statics::*;
 
 pub fn top_aff(who: &AgentState, afflictions: Vec<FType>) -> Option<FType> {
@@ -109,7 +119,7 @@ pub fn handle_simple_cure_action(
     Ok(())
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct CureDepth {
     pub time: CType,
     pub cures: CType,
@@ -131,11 +141,20 @@ const SMOKE_TIME: CType = 150;
 
 fn get_cure_depth_locked(me: &AgentState, target_aff: FType, checked: u32) -> CureDepth {
     let mut val = CureDepth::default();
-    if let Some(salve) = AFFLICTION_SALVES.get(&target_aff) {
+    // The orders are looked up again after any recursion, rather than held across it.
+    let (salve, smoke, pill) = {
+        let cure_orders = cure_orders();
+        (
+            cure_orders.affliction_salve(target_aff).cloned(),
+            cure_orders.affliction_smoke(target_aff).cloned(),
+            cure_orders.affliction_pill(target_aff).cloned(),
+        )
+    };
+    if let Some(salve) = salve {
         if me.is(FType::Slickness) && checked < 2 {
             val = get_cure_depth_locked(me, FType::Slickness, checked + 1);
         }
-        for aff in SALVE_CURE_ORDERS.get(salve).unwrap() {
+        for aff in cure_orders().salve_order(&salve.0, &salve.1).unwrap() {
             if me.is(*aff) {
                 val.affs.push(*aff);
                 val.time = val.time + SALVE_TIME;
@@ -155,11 +174,11 @@ fn get_cure_depth_locked(me: &AgentState, target_aff: FType, checked: u32) -> Cu
             }
         }
         val
-    } else if let Some(smoke) = AFFLICTION_SMOKES.get(&target_aff) {
+    } else if let Some(smoke) = smoke {
         if me.is(FType::Asthma) && checked < 2 {
             val = get_cure_depth_locked(me, FType::Asthma, checked + 1);
         }
-        for aff in SMOKE_CURE_ORDERS.get(smoke).unwrap() {
+        for aff in cure_orders().smoke_order(&smoke).unwrap() {
             if me.is(*aff) {
                 val.affs.push(*aff);
                 val.time = val.time + SMOKE_TIME;
@@ -174,11 +193,11 @@ fn get_cure_depth_locked(me: &AgentState, target_aff: FType, checked: u32) -> Cu
             }
         }
         val
-    } else if let Some(pill) = AFFLICTION_PILLS.get(&target_aff) {
+    } else if let Some(pill) = pill {
         if me.is(FType::Anorexia) && checked < 2 {
             val = get_cure_depth_locked(me, FType::Anorexia, checked + 1);
         }
-        for aff in PILL_CURE_ORDERS.get(pill).unwrap() {
+        for aff in cure_orders().pill_order(&pill).unwrap() {
             if me.is(*aff) {
                 val.affs.push(*aff);
                 val.time = val.time + PILL_TIME;
@@ -211,27 +230,92 @@ pub fn get_cure_depth(me: &AgentState, target_aff: FType) -> CureDepth {
     }
 }
 
+// Cure depth across every branch an agent might be in.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BranchCureDepth {
+    // Share of the branches with the aff at all.
+    pub afflicted: f32,
+    pub expected_time: f32,
+    pub expected_cures: f32,
+    pub best: CureDepth,
+    pub worst: CureDepth,
+}
+
+// Branches without the aff count as already cured. Weights need not sum to one.
+pub fn get_weighted_cure_depth(
+    branches: &Vec<(&AgentState, f32)>,
+    target_aff: FType,
+) -> BranchCureDepth {
+    let total_weight: f32 = branches.iter().map(|(_branch, weight)| weight).sum();
+    if total_weight <= 0.0 {
+        return BranchCureDepth::default();
+    }
+    let mut depth = BranchCureDepth::default();
+    let mut extremes: Option<(CureDepth, CureDepth)> = None;
+    for (branch, weight) in branches.iter() {
+        let share = weight / total_weight;
+        let cure_depth = get_cure_depth(branch, target_aff);
+        if branch.is(target_aff) {
+            depth.afflicted += share;
+        }
+        depth.expected_time += cure_depth.time as f32 * share;
+        depth.expected_cures += cure_depth.cures as f32 * share;
+        extremes = Some(match extremes {
+            None => (cure_depth.clone(), cure_depth),
+            Some((best, worst)) => {
+                let key = |depth: &CureDepth| (depth.time, depth.cures);
+                (
+                    if key(&cure_depth) < key(&best) {
+                        cure_depth.clone()
+                    } else {
+                        best
+                    },
+                    if key(&cure_depth) > key(&worst) {
+                        cure_depth
+                    } else {
+                        worst
+                    },
+                )
+            }
+        });
+    }
+    if let Some((best, worst)) = extremes {
+        depth.best = best;
+        depth.worst = worst;
+    }
+    depth
+}
+
+// The timeline does not weigh its branches, so each counts the same.
+pub fn get_branch_cure_depth(branches: &Vec<AgentState>, target_aff: FType) -> BranchCureDepth {
+    get_weighted_cure_depth(
+        &branches.iter().map(|branch| (branch, 1.0)).collect(),
+        target_aff,
+    )
+}
+
 pub fn get_cure_depths(me: &AgentState) -> CureDepths {
     let mut salve = CureDepth::default();
     let mut pill = CureDepth::default();
     let mut smoke = CureDepth::default();
     let mut focus = CureDepth::default();
 
-    for aff in AFFLICTION_PILLS.keys() {
-        if me.is(*aff) {
-            pill.affs.push(*aff);
+    let cure_orders = cure_orders();
+    for aff in cure_orders.pill_afflictions() {
+        if me.is(aff) {
+            pill.affs.push(aff);
         }
     }
 
-    for aff in AFFLICTION_SMOKES.keys() {
-        if me.is(*aff) {
-            smoke.affs.push(*aff);
+    for aff in cure_orders.smoke_afflictions() {
+        if me.is(aff) {
+            smoke.affs.push(aff);
         }
     }
 
-    for aff in AFFLICTION_SALVES.keys() {
-        if me.is(*aff) {
-            salve.affs.push(*aff);
+    for aff in cure_orders.salve_afflictions() {
+        if me.is(aff) {
+            salve.affs.push(aff);
         }
     }
 
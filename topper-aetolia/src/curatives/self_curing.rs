@@ -0,0 +1,193 @@
+use super::cure_orders::cure_orders;
+use super::first_aid::{FirstAid, FirstAidAction, FocusAction, TreeAction};
+use super::statics::MENTAL_AFFLICTIONS;
+use crate::classes::{get_curing_profile, is_affected_by, Class};
+use crate::observables::*;
+use crate::timeline::*;
+use crate::types::*;
+use std::collections::HashMap;
+
+// Afflictions that only hamper some classes, and can wait for everyone else.
+const CLASS_DEPENDENT_AFFLICTIONS: [FType; 5] = [
+    FType::Clumsiness,
+    FType::Weariness,
+    FType::Peace,
+    FType::Disfigurement,
+    FType::Lethargy,
+];
+
+// Pushes afflictions that do not hamper us behind every listed priority.
+const UNAFFECTED_PRIORITY: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfCure {
+    pub balance: BType,
+    pub affliction: FType,
+    // Share of our branches that have the affliction.
+    pub share: f32,
+    pub command: String,
+}
+
+// Picks our own cures each prompt, rather than leaving them to the game's first aid.
+#[derive(Debug, Clone)]
+pub struct SelfCuring {
+    pub first_aid: FirstAid,
+    // Afflictions in fewer of our branches than this are not worth a cure yet.
+    pub min_share: f32,
+}
+
+impl Default for SelfCuring {
+    fn default() -> Self {
+        SelfCuring {
+            first_aid: FirstAid::new(),
+            min_share: 0.5,
+        }
+    }
+}
+
+fn share_of(branches: &Vec<AgentState>, test: impl Fn(&AgentState) -> bool) -> f32 {
+    branches.iter().filter(|branch| test(branch)).count() as f32 / branches.len().max(1) as f32
+}
+
+impl SelfCuring {
+    pub fn new(first_aid: FirstAid) -> Self {
+        SelfCuring {
+            first_aid,
+            ..Default::default()
+        }
+    }
+
+    fn get_priority(&self, class: &Option<Class>, affliction: FType) -> Option<u32> {
+        let priority = *self.first_aid.get_priorities().get(&affliction)?;
+        match class {
+            Some(class)
+                if CLASS_DEPENDENT_AFFLICTIONS.contains(&affliction)
+                    && !is_affected_by(*class, affliction) =>
+            {
+                Some(priority + UNAFFECTED_PRIORITY)
+            }
+            _ => Some(priority),
+        }
+    }
+
+    // The afflictions worth curing, most urgent first.
+    pub fn get_candidates(
+        &self,
+        branches: &Vec<AgentState>,
+        class: &Option<Class>,
+    ) -> Vec<(FType, u32, f32)> {
+        let mut shares: HashMap<FType, f32> = HashMap::new();
+        for branch in branches.iter() {
+            for affliction in branch.flags.aff_iter() {
+                *shares.entry(affliction).or_insert(0.0) += 1.0 / branches.len() as f32;
+            }
+        }
+        let mut candidates: Vec<(FType, u32, f32)> = shares
+            .into_iter()
+            .filter(|(_affliction, share)| *share >= self.min_share)
+            .filter_map(|(affliction, share)| {
+                self.get_priority(class, affliction)
+                    .map(|priority| (affliction, priority, share))
+            })
+            .collect();
+        candidates.sort_by(
+            |(aff_a, priority_a, share_a), (aff_b, priority_b, share_b)| {
+                priority_a
+                    .cmp(priority_b)
+                    .then(share_b.partial_cmp(share_a).unwrap())
+                    .then(aff_a.cmp(aff_b))
+            },
+        );
+        candidates
+    }
+
+    // One cure for each curing balance we can use, going down the priorities. A balance only
+    // counts as free when most of our branches agree on it.
+    pub fn get_cures(
+        &self,
+        timeline: &AetTimeline,
+        branches: &Vec<AgentState>,
+        class: &Option<Class>,
+    ) -> Vec<SelfCure> {
+        let who_am_i = timeline.who_am_i();
+        let can = |test: &dyn Fn(&AgentState) -> bool| share_of(branches, test) > 0.5;
+        let mut free: HashMap<BType, bool> = HashMap::new();
+        free.insert(BType::Pill, can(&|branch| branch.can_pill(false)));
+        free.insert(BType::Salve, can(&|branch| branch.can_salve(false)));
+        free.insert(BType::Smoke, can(&|branch| branch.can_smoke(false)));
+        free.insert(BType::Focus, can(&|branch| branch.can_focus(false)));
+        free.insert(BType::Tree, can(&|branch| branch.can_tree(false)));
+        for balance in [BType::Fitness, BType::ClassCure1, BType::ClassCure2].iter() {
+            free.insert(*balance, can(&|branch| branch.balanced(*balance)));
+        }
+        let mut cures = Vec::new();
+        let mut use_balance = |balance: BType, affliction: FType, share: f32, command| {
+            if free.get(&balance) == Some(&true) {
+                free.insert(balance, false);
+                cures.push(SelfCure {
+                    balance,
+                    affliction,
+                    share,
+                    command,
+                });
+                true
+            } else {
+                false
+            }
+        };
+        let cure_orders = cure_orders();
+        let profile = class
+            .map(|class| get_curing_profile(&class))
+            .unwrap_or_default();
+        for (affliction, _priority, share) in self.get_candidates(branches, class) {
+            if let Some(class_cure) = profile.get_cure(affliction) {
+                if let Some(command) = &class_cure.command {
+                    if use_balance(class_cure.balance, affliction, share, command.clone()) {
+                        continue;
+                    }
+                }
+            }
+            let mut actions = Vec::new();
+            if let Some(herb) = cure_orders.affliction_smoke(affliction) {
+                actions.push((
+                    BType::Smoke,
+                    FirstAidAction::Simple(SimpleCureAction::smoke(&who_am_i, herb)),
+                ));
+            }
+            if let Some(pill) = cure_orders.affliction_pill(affliction) {
+                actions.push((
+                    BType::Pill,
+                    FirstAidAction::Simple(SimpleCureAction::pill(&who_am_i, pill)),
+                ));
+            }
+            if let Some((salve, location)) = cure_orders.affliction_salve(affliction) {
+                actions.push((
+                    BType::Salve,
+                    FirstAidAction::Simple(SimpleCureAction::salve(&who_am_i, salve, location)),
+                ));
+            }
+            if MENTAL_AFFLICTIONS.contains(&affliction) {
+                actions.push((
+                    BType::Focus,
+                    FirstAidAction::Focus(FocusAction::new(&who_am_i)),
+                ));
+            }
+            actions.push((
+                BType::Tree,
+                FirstAidAction::Tree(TreeAction::new(&who_am_i)),
+            ));
+            for (balance, action) in actions {
+                if let Ok(command) = action.act(timeline) {
+                    if use_balance(balance, affliction, share, command) {
+                        break;
+                    }
+                }
+            }
+        }
+        cures
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/self_curing_tests.rs"]
+mod self_curing_tests;
@@ -0,0 +1,321 @@
+use super::first_aid::FirstAid;
+use crate::arena::{choose, collapse_branches};
+use crate::classes::{
+    get_curing_profile, get_venoms_from_plan, Class, ClassCuringProfile, LockType, VenomPlan,
+};
+use crate::observables::ActiveTransition;
+use crate::timeline::*;
+use crate::types::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use topper_core::timeline::db::DummyDatabaseModule;
+use topper_core::timeline::{BaseAgentState, BALANCE_SCALE};
+
+const TICK: CType = 10;
+const ATTACKER: &str = "Attacker";
+const CURER: &str = "Curer";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StackEvaluationConfig {
+    pub trials: usize,
+    pub seed: u64,
+    // In seconds.
+    pub time_limit: f32,
+    pub venoms_per_attack: usize,
+    // Seconds between attacks.
+    pub attack_balance: f32,
+}
+
+impl Default for StackEvaluationConfig {
+    fn default() -> Self {
+        StackEvaluationConfig {
+            trials: 1000,
+            seed: 0,
+            time_limit: 60.0,
+            venoms_per_attack: 2,
+            attack_balance: 2.8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LockForecast {
+    pub lock: LockType,
+    // How many trials reached the lock at all.
+    pub reached: usize,
+    pub average_time: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StackEntryReport {
+    pub index: usize,
+    pub affliction: FType,
+    pub applied: usize,
+    // Cured before the next attack could build on it.
+    pub cured_early: usize,
+}
+
+impl StackEntryReport {
+    pub fn cured_early_rate(&self) -> f32 {
+        if self.applied > 0 {
+            self.cured_early as f32 / self.applied as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StackReport {
+    pub trials: usize,
+    pub locks: Vec<LockForecast>,
+    pub entries: Vec<StackEntryReport>,
+}
+
+impl fmt::Display for StackReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} trials", self.trials)?;
+        for forecast in self.locks.iter() {
+            writeln!(
+                f,
+                "{}: reached in {:.1}% of trials, avg {}",
+                forecast.lock,
+                forecast.reached as f32 * 100.0 / self.trials.max(1) as f32,
+                forecast
+                    .average_time
+                    .map(|time| format!("{:.1}s", time))
+                    .unwrap_or("-".to_string()),
+            )?;
+        }
+        for entry in self.entries.iter() {
+            writeln!(
+                f,
+                "{:>3} {:?}: applied {}, cured early {:.1}%",
+                entry.index,
+                entry.affliction,
+                entry.applied,
+                entry.cured_early_rate() * 100.0,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn lock_types() -> Vec<LockType> {
+    vec![
+        LockType::Soft,
+        LockType::Buffered,
+        LockType::HardVenom,
+        LockType::Hard,
+    ]
+}
+
+fn covers(plan: &VenomPlan, affliction: FType) -> bool {
+    match plan {
+        VenomPlan::OneOf(priority, secondary) => {
+            *priority == affliction || *secondary == affliction
+        }
+        VenomPlan::IfDo(_, plan)
+        | VenomPlan::IfNotDo(_, plan)
+        | VenomPlan::IfClassHates(_, plan)
+        | VenomPlan::IfNotClassHates(_, plan) => covers(plan, affliction),
+        _ => plan.affliction() == affliction,
+    }
+}
+
+// Plays a venom stack against a simulated curer, using the cure orders to decide what each cure
+// takes. The curer's class cures are used as soon as they are up.
+pub struct StackEvaluation {
+    pub config: StackEvaluationConfig,
+    pub stack: Vec<VenomPlan>,
+    pub opponent: Class,
+    pub first_aid: FirstAid,
+}
+
+pub(crate) struct TrialResult {
+    pub lock_times: Vec<Option<f32>>,
+    pub applied: Vec<usize>,
+    pub cured_early: Vec<usize>,
+}
+
+// A single play-through of a stack, starting from whatever state the curer is already in.
+pub(crate) struct StackRollout<'s> {
+    pub stack: &'s Vec<VenomPlan>,
+    pub first_aid: &'s FirstAid,
+    // Class cures the curer has besides first aid.
+    pub profile: &'s ClassCuringProfile,
+    pub venoms_per_attack: usize,
+    // Seconds between attacks.
+    pub attack_balance: f32,
+    // In seconds.
+    pub time_limit: f32,
+}
+
+impl<'s> StackRollout<'s> {
+    pub fn run(&self, curer: AgentState, first_attack: f32, seed: u64) -> TrialResult {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut timeline = AetTimeline::new();
+        // Cures are watched from the attacker's side, so they are inferred from the cure orders.
+        timeline.state.me = ATTACKER.to_string();
+        timeline
+            .state
+            .agent_states
+            .insert(CURER.to_string(), vec![curer]);
+        let time_limit = (self.time_limit * BALANCE_SCALE) as CType;
+        let attack_balance = (self.attack_balance * BALANCE_SCALE) as CType;
+        let locks = lock_types();
+        let mut result = TrialResult {
+            lock_times: vec![None; locks.len()],
+            applied: vec![0; self.stack.len()],
+            cured_early: vec![0; self.stack.len()],
+        };
+        let mut pending: Vec<(usize, FType)> = Vec::new();
+        let mut next_attack = (first_attack * BALANCE_SCALE) as CType;
+        while timeline.state.time < time_limit {
+            let now = timeline.state.time;
+            if now >= next_attack {
+                let you = timeline.state.borrow_agent(&CURER.to_string());
+                for (index, affliction) in pending.drain(..) {
+                    if !you.is(affliction) {
+                        result.cured_early[index] += 1;
+                    }
+                }
+                for venom in get_venoms_from_plan(self.stack, self.venoms_per_attack, &you) {
+                    let before = timeline.state.borrow_agent(&CURER.to_string());
+                    let venom = venom.to_string();
+                    timeline
+                        .state
+                        .for_agent(&CURER.to_string(), &move |you: &mut AgentState| {
+                            apply_venom(you, &venom, false);
+                        });
+                    let after = timeline.state.borrow_agent(&CURER.to_string());
+                    for affliction in after.flags.aff_iter() {
+                        if before.is(affliction) {
+                            continue;
+                        }
+                        if let Some(index) =
+                            self.stack.iter().position(|plan| covers(plan, affliction))
+                        {
+                            result.applied[index] += 1;
+                            pending.push((index, affliction));
+                        }
+                    }
+                }
+                next_attack = now + attack_balance;
+            }
+            // A lock counts once it lands, even if a cure breaks it straight after.
+            let you = timeline.state.borrow_agent(&CURER.to_string());
+            for (index, lock) in locks.iter().enumerate() {
+                if result.lock_times[index].is_none() && lock.affs_to_lock(&you) == 0 {
+                    result.lock_times[index] = Some(now as f32 / BALANCE_SCALE);
+                }
+            }
+            if let Some((_affliction, cure)) = self.first_aid.get_cure(CURER, &you) {
+                let events = cure
+                    .simulate(&timeline)
+                    .into_iter()
+                    .map(|event| {
+                        let weight = event.get_weight();
+                        (event, weight)
+                    })
+                    .collect();
+                if let Some(event) = choose(&mut rng, events) {
+                    let mut slice = simulation_slice(event.get_observations().clone(), now);
+                    slice.me = ATTACKER.to_string();
+                    timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+                    collapse_branches(&mut timeline, &CURER.to_string(), &mut rng);
+                }
+            }
+            let you = timeline.state.borrow_agent(&CURER.to_string());
+            if let Some((cure, affliction)) = self.profile.get_ready_cure(&you) {
+                let (balance, cooldown) = (cure.balance, cure.cooldown);
+                timeline
+                    .state
+                    .for_agent(&CURER.to_string(), &move |you: &mut AgentState| {
+                        you.set_flag(affliction, false);
+                        you.set_balance(balance, cooldown);
+                    });
+            }
+            timeline.update_time(now + TICK);
+        }
+        result
+    }
+}
+
+impl StackEvaluation {
+    pub fn new(stack: Vec<VenomPlan>, opponent: Class, first_aid: FirstAid) -> Self {
+        StackEvaluation {
+            config: StackEvaluationConfig::default(),
+            stack,
+            opponent,
+            first_aid,
+        }
+    }
+
+    pub fn run(&self) -> StackReport {
+        let profile = get_curing_profile(&self.opponent);
+        let rollout = StackRollout {
+            stack: &self.stack,
+            first_aid: &self.first_aid,
+            profile: &profile,
+            venoms_per_attack: self.config.venoms_per_attack,
+            attack_balance: self.config.attack_balance,
+            time_limit: self.config.time_limit,
+        };
+        let mut curer = AgentState::get_base_state();
+        curer
+            .class_state
+            .initialize_for_normalized_class(self.opponent.normal());
+        let results: Vec<TrialResult> = (0..self.config.trials)
+            .map(|trial| {
+                rollout.run(
+                    curer.clone(),
+                    0.0,
+                    self.config.seed.wrapping_add(trial as u64),
+                )
+            })
+            .collect();
+        let locks = lock_types()
+            .into_iter()
+            .enumerate()
+            .map(|(index, lock)| {
+                let times: Vec<f32> = results
+                    .iter()
+                    .filter_map(|result| result.lock_times[index])
+                    .collect();
+                LockForecast {
+                    lock,
+                    reached: times.len(),
+                    average_time: if times.len() > 0 {
+                        Some(times.iter().sum::<f32>() / times.len() as f32)
+                    } else {
+                        None
+                    },
+                }
+            })
+            .collect();
+        let entries = self
+            .stack
+            .iter()
+            .enumerate()
+            .map(|(index, plan)| StackEntryReport {
+                index,
+                affliction: plan.affliction(),
+                applied: results.iter().map(|result| result.applied[index]).sum(),
+                cured_early: results.iter().map(|result| result.cured_early[index]).sum(),
+            })
+            .collect();
+        StackReport {
+            trials: results.len(),
+            locks,
+            entries,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/stack_evaluation_tests.rs"]
+mod stack_evaluation_tests;
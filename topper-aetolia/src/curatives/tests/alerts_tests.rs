@@ -0,0 +1,122 @@
+mod alerts_tests {
+    use super::super::*;
+    use topper_core::timeline::db::MemoryDatabaseModule;
+
+    fn get_timeline() -> AetTimeline {
+        let mut timeline = AetTimeline::new();
+        timeline.state.me = "Seurimas".to_string();
+        timeline.state.for_agent(&"Seurimas".to_string(), &|me| {
+            me.set_max_stat(SType::Health, 4000);
+            me.set_stat(SType::Health, 4000);
+        });
+        timeline
+    }
+
+    #[test]
+    fn test_lock_threat() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Seurimas".to_string(), &|me| {
+            me.set_flag(FType::Asthma, true);
+            me.set_flag(FType::Anorexia, true);
+        });
+        let alerts = gather_alerts(
+            &timeline,
+            "Seurimas".to_string(),
+            None as Option<&MemoryDatabaseModule>,
+        );
+        assert_eq!(
+            alerts,
+            vec![
+                SafetyAlert::LockThreat(LockType::Soft, vec![FType::Anorexia, FType::Asthma]),
+                SafetyAlert::LockThreat(LockType::HardVenom, vec![FType::Anorexia, FType::Asthma]),
+            ]
+        );
+        assert_eq!(
+            alerts[0].to_string(),
+            "Lock Threat (Soft): anorexia, asthma"
+        );
+    }
+
+    #[test]
+    fn test_branches_merge() {
+        let mut timeline = get_timeline();
+        let mut first = timeline.state.borrow_me();
+        first.set_flag(FType::Asthma, true);
+        first.set_flag(FType::Anorexia, true);
+        let mut second = timeline.state.borrow_me();
+        second.set_flag(FType::Asthma, true);
+        second.set_flag(FType::Slickness, true);
+        timeline
+            .state
+            .agent_states
+            .insert("Seurimas".to_string(), vec![first, second]);
+        let alerts = gather_alerts(
+            &timeline,
+            "Seurimas".to_string(),
+            None as Option<&MemoryDatabaseModule>,
+        );
+        assert_eq!(
+            alerts[0],
+            SafetyAlert::LockThreat(
+                LockType::Soft,
+                vec![FType::Anorexia, FType::Asthma, FType::Slickness]
+            )
+        );
+    }
+
+    #[test]
+    fn test_class_threats() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Seurimas".to_string(), &|me| {
+            me.register_hit(Some(&"Kaiza".to_string()));
+            me.bard_board.iron_collar_state = IronCollarState::Locking;
+        });
+        let db = MemoryDatabaseModule::new();
+        db.set_class(&"Kaiza".to_string(), Class::Bard);
+        let alerts = gather_alerts(&timeline, "Seurimas".to_string(), Some(&db));
+        assert_eq!(alerts, vec![SafetyAlert::HighValueThreat(vec![])]);
+        // Standing or not, a locked collar can be heartcaged.
+        timeline.state.for_agent(&"Seurimas".to_string(), &|me| {
+            me.bard_board.iron_collar_state = IronCollarState::Locked;
+        });
+        let alerts = gather_alerts(&timeline, "Seurimas".to_string(), Some(&db));
+        assert_eq!(alerts, vec![SafetyAlert::InstakillThreat(vec![])]);
+    }
+
+    #[test]
+    fn test_instakill_health() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Seurimas".to_string(), &|me| {
+            me.set_stat(SType::Health, 900);
+        });
+        let db = MemoryDatabaseModule::new();
+        let alerts = gather_alerts(&timeline, "Seurimas".to_string(), Some(&db));
+        assert_eq!(
+            alerts,
+            vec![SafetyAlert::LowHealth, SafetyAlert::InstakillThreat(vec![])]
+        );
+        db.insert_hint(&"instakill_alert".to_string(), &"0.2".to_string());
+        let alerts = gather_alerts(&timeline, "Seurimas".to_string(), Some(&db));
+        assert_eq!(alerts, vec![SafetyAlert::LowHealth]);
+    }
+
+    #[test]
+    fn test_zealot_pyromania() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Seurimas".to_string(), &|me| {
+            me.register_hit(Some(&"Tsuyu".to_string()));
+            me.set_flag(FType::Ablaze, true);
+        });
+        let db = MemoryDatabaseModule::new();
+        let alerts = gather_alerts(&timeline, "Seurimas".to_string(), Some(&db));
+        assert_eq!(alerts, vec![]);
+        timeline.state.for_agent(&"Tsuyu".to_string(), &|you| {
+            you.assume_zealot(|zealot| zealot.pyromania.activate(2000));
+        });
+        let alerts = gather_alerts(&timeline, "Seurimas".to_string(), Some(&db));
+        assert_eq!(
+            alerts,
+            vec![SafetyAlert::HighValueThreat(vec![FType::Ablaze])]
+        );
+    }
+}
@@ -90,4 +90,48 @@ mod cure_depth_tests {
         assert_eq!(cure_depth.time, 150);
         assert_eq!(cure_depth.cures, 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_branch_cure_depth() {
+        let mut clumsy = AgentState::default();
+        clumsy.set_flag(FType::Clumsiness, true);
+        clumsy.set_flag(FType::Asthma, true);
+        let mut asthmatic = AgentState::default();
+        asthmatic.set_flag(FType::Asthma, true);
+        let clean = AgentState::default();
+        let shallow = get_cure_depth(&asthmatic, FType::Asthma);
+        let deep = get_cure_depth(&clumsy, FType::Asthma);
+        let branches = vec![clumsy.clone(), asthmatic, clean, clumsy];
+        let cure_depth = get_branch_cure_depth(&branches, FType::Asthma);
+        assert_eq!(cure_depth.afflicted, 0.75);
+        assert_eq!(cure_depth.best, CureDepth::default());
+        assert_eq!(cure_depth.worst, deep);
+        assert_eq!(
+            cure_depth.expected_time,
+            (deep.time * 2 + shallow.time) as f32 / 4.0
+        );
+        assert_eq!(cure_depth.expected_cures, 5.0 / 4.0);
+    }
+
+    #[test]
+    fn test_weighted_cure_depth() {
+        let mut clumsy = AgentState::default();
+        clumsy.set_flag(FType::Clumsiness, true);
+        clumsy.set_flag(FType::Asthma, true);
+        let mut asthmatic = AgentState::default();
+        asthmatic.set_flag(FType::Asthma, true);
+        let cure_depth =
+            get_weighted_cure_depth(&vec![(&clumsy, 3.0), (&asthmatic, 1.0)], FType::Asthma);
+        assert_eq!(cure_depth.afflicted, 1.0);
+        assert_eq!(cure_depth.best.affs, vec![FType::Asthma]);
+        assert_eq!(
+            cure_depth.worst.affs,
+            vec![FType::Clumsiness, FType::Asthma]
+        );
+        assert_eq!(cure_depth.expected_cures, 1.75);
+        assert_eq!(
+            get_weighted_cure_depth(&vec![], FType::Asthma),
+            BranchCureDepth::default()
+        );
+    }
+}
@@ -0,0 +1,68 @@
+mod cure_orders_tests {
+    use super::super::*;
+
+    fn get_help_lines() -> Vec<String> {
+        serde_json::from_str::<CureOrderHelp>(include_str!("../../../../docs/cure_order.json"))
+            .unwrap()
+            .body
+    }
+
+    #[test]
+    fn test_parse_pills() {
+        let orders = parse_cure_order_help(&get_help_lines());
+        assert_eq!(
+            orders.pill_order("depressant"),
+            Some(&DEPRESSANT_ORDER.to_vec())
+        );
+        let opiate = orders.pill_order("opiate").unwrap();
+        assert!(opiate.contains(&FType::Paresis));
+        assert!(opiate.contains(&FType::Paralysis));
+        let panacea = orders.pill_order("panacea").unwrap();
+        assert!(panacea.contains(&FType::RotWither));
+        assert_eq!(orders.pill_defence("kawhe"), Some(FType::Insomnia));
+        // Energetic is not tracked, so the built-in defence is kept.
+        assert_eq!(orders.pill_defence("stimulant"), None);
+    }
+
+    #[test]
+    fn test_parse_poultices() {
+        let orders = parse_cure_order_help(&get_help_lines());
+        assert_eq!(
+            orders.salve_order("mending", "left arm"),
+            Some(&MENDING_LEFT_ARM_ORDER.to_vec())
+        );
+        assert_eq!(
+            orders.salve_order("soothing", "arms"),
+            Some(&SOOTHING_ARMS_ORDER.to_vec())
+        );
+        assert_eq!(
+            orders.restore_order(LType::TorsoDamage),
+            Some(&RESTORATION_TORSO_ORDER.to_vec())
+        );
+        assert_eq!(orders.smoke_order("willow"), Some(&WILLOW_ORDER.to_vec()));
+    }
+
+    #[test]
+    fn test_update_from() {
+        let mut loaded = CureOrders::empty();
+        loaded
+            .smokes
+            .insert("yarrow".into(), vec![FType::Migraine, FType::Slickness]);
+        loaded.smokes.insert("reishi".into(), vec![FType::Aeon]);
+        let mut orders = CureOrders::builtin();
+        orders.update_from(loaded);
+        assert_eq!(
+            orders.smoke_order("yarrow"),
+            Some(&vec![FType::Migraine, FType::Slickness])
+        );
+        assert_eq!(orders.smoke_order("reishi"), None);
+        assert_eq!(orders.affliction_smoke(FType::Aeon), Some(&"willow".into()));
+        assert_eq!(orders.affliction_smoke(FType::Withering), None);
+        // Slickness stays on the pill it was already cured by.
+        assert_eq!(
+            orders.affliction_pill(FType::Slickness),
+            CureOrders::builtin().affliction_pill(FType::Slickness)
+        );
+        assert_eq!(orders.pill_defence("stimulant"), Some(FType::Instawake));
+    }
+}
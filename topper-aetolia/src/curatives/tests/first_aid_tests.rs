@@ -108,4 +108,48 @@ mod firstaid_tests {
         let stripped = strip_ansi(&ansi_line.to_string());
         assert_eq!(stripped, "1)  pipe:     [aeon]");
     }
+
+    #[test]
+    fn test_priority_commands() {
+        let mut current = HashMap::new();
+        current.insert(FType::Paresis, 1);
+        current.insert(FType::Asthma, 2);
+        let first_aid = FirstAid::from_priorities(current);
+        let mut priorities = HashMap::new();
+        priorities.insert(FType::Paresis, 1);
+        priorities.insert(FType::Asthma, 3);
+        priorities.insert(FType::LeftLegCrippled, 2);
+        assert_eq!(
+            first_aid.get_priority_commands(&priorities),
+            vec![
+                "firstaid priority left_leg_crippled 2".to_string(),
+                "firstaid priority asthma 3".to_string(),
+            ]
+        );
+        assert!(!first_aid.has_priorities(&priorities));
+        let confirmed = FirstAid::from_priorities(priorities.clone());
+        assert!(confirmed.has_priorities(&priorities));
+        assert_eq!(confirmed.get_priority_commands(&priorities).len(), 0);
+    }
+
+    #[test]
+    fn test_priority_commands_reset() {
+        let mut current = HashMap::new();
+        current.insert(FType::Paresis, 1);
+        current.insert(FType::Asthma, 3);
+        current.insert(FType::Dizziness, 5);
+        let first_aid = FirstAid::from_priorities(current);
+        let mut priorities = HashMap::new();
+        priorities.insert(FType::Paresis, 1);
+        assert_eq!(
+            first_aid.get_priority_commands(&priorities),
+            vec![
+                "firstaid priority asthma 2".to_string(),
+                "firstaid priority dizziness 26".to_string(),
+            ]
+        );
+        let swapped = FirstAid::from_priorities(first_aid.get_swapped_priorities(&priorities));
+        assert!(swapped.has_priorities(&priorities));
+        assert_eq!(swapped.get_priority_commands(&priorities).len(), 0);
+    }
 }
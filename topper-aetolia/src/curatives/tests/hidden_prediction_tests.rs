@@ -0,0 +1,123 @@
+mod hidden_prediction_tests {
+    use super::super::*;
+    use topper_core::timeline::BaseAgentState;
+
+    fn guessed(affs: Vec<FType>) -> AgentState {
+        let mut branch = AgentState::get_base_state();
+        for aff in affs {
+            branch.set_flag(aff, true);
+            branch.add_guess(aff);
+        }
+        branch
+    }
+
+    #[test]
+    fn test_single_guess_blind_cure() {
+        let branches = vec![guessed(vec![FType::Anorexia])];
+        let prediction = predict_hidden_affs(
+            &branches,
+            &HiddenAffTable::default(),
+            &HiddenAffEvidence::default(),
+        );
+        assert_eq!(prediction.probability(FType::Anorexia), 1.0);
+        assert_eq!(
+            prediction.choice.map(|(choice, _expected)| choice),
+            Some(HiddenAffChoice::BlindCure(FType::Anorexia))
+        );
+    }
+
+    #[test]
+    fn test_predict_without_blind_cure() {
+        let branches = vec![guessed(vec![FType::Clumsiness])];
+        let prediction = predict_hidden_affs(
+            &branches,
+            &HiddenAffTable::default(),
+            &HiddenAffEvidence::default(),
+        );
+        assert_eq!(
+            prediction.choice.map(|(choice, _expected)| choice),
+            Some(HiddenAffChoice::Predict(FType::Clumsiness))
+        );
+    }
+
+    #[test]
+    fn test_evidence_shifts_odds() {
+        let branches = vec![
+            guessed(vec![FType::Clumsiness]),
+            guessed(vec![FType::Stupidity]),
+        ];
+        let table = HiddenAffTable::default();
+        let even = predict_hidden_affs(&branches, &table, &HiddenAffEvidence::default());
+        assert_eq!(even.probability(FType::Clumsiness), 0.5);
+        let mut evidence = HiddenAffEvidence::default();
+        evidence.observe(
+            &vec![
+                CombatAction::observation("Foe", "Skill", "Bite", "", "Me"),
+                AetObservation::Devenoms("xentio".to_string()),
+            ],
+            &"Me".to_string(),
+            &"Foe".to_string(),
+        );
+        let shifted = predict_hidden_affs(&branches, &table, &evidence);
+        assert!(shifted.probability(FType::Clumsiness) > 0.5);
+        assert_eq!(shifted.probabilities[0].0, FType::Clumsiness);
+    }
+
+    #[test]
+    fn test_unknown_diagnose() {
+        let mut branch = AgentState::get_base_state();
+        branch.hidden_state.add_unknown();
+        branch.hidden_state.add_unknown();
+        let mut table = HiddenAffTable::default();
+        table.diagnose_cost = 1.0;
+        let prediction = predict_hidden_affs(&vec![branch], &table, &HiddenAffEvidence::default());
+        assert_eq!(prediction.probabilities.len(), table.values.len());
+        assert_eq!(
+            prediction.choice.map(|(choice, _expected)| choice),
+            Some(HiddenAffChoice::Diagnose)
+        );
+    }
+
+    #[test]
+    fn test_unknown_weighted_once() {
+        let mut branch = AgentState::get_base_state();
+        branch.hidden_state.add_unknown();
+        let mut table = HiddenAffTable::default();
+        for likelihood in table.likelihoods.values_mut() {
+            *likelihood = 1.0;
+        }
+        table.likelihoods.insert(FType::Clumsiness, 3.0);
+        let candidates = table.values.len() as f32;
+        let prediction = predict_hidden_affs(&vec![branch], &table, &HiddenAffEvidence::default());
+        let expected = 3.0 / (candidates + 2.0);
+        assert!((prediction.probability(FType::Clumsiness) - expected).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_unknown_zero_weights() {
+        let mut branch = AgentState::get_base_state();
+        branch.hidden_state.add_unknown();
+        let mut table = HiddenAffTable::default();
+        for aff in table.values.clone().keys() {
+            table.likelihoods.insert(*aff, 0.0);
+        }
+        let prediction = predict_hidden_affs(&vec![branch], &table, &HiddenAffEvidence::default());
+        assert!(prediction
+            .probabilities
+            .iter()
+            .all(|(_aff, probability)| !probability.is_nan()));
+    }
+
+    #[test]
+    fn test_class_tables() {
+        let mut table = HiddenAffTable::default();
+        table.diagnose_cost = 0.0;
+        set_hidden_aff_table(Class::Bard, table.clone());
+        assert_eq!(get_hidden_aff_table(&Some(Class::Bard)), table);
+        assert_eq!(
+            get_hidden_aff_table(&Some(Class::Zealot)),
+            HiddenAffTable::default()
+        );
+        assert_eq!(get_hidden_aff_table(&None), HiddenAffTable::default());
+    }
+}
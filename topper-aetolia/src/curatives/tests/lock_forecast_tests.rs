@@ -0,0 +1,113 @@
+mod lock_forecast_tests {
+    use super::super::*;
+    use crate::classes::{get_curing_profile, Class};
+    use std::collections::HashMap;
+
+    fn get_stack() -> Vec<VenomPlan> {
+        vec![
+            VenomPlan::Stick(FType::Asthma),
+            VenomPlan::Stick(FType::Anorexia),
+            VenomPlan::Stick(FType::Slickness),
+        ]
+    }
+
+    fn get_config() -> LockForecastConfig {
+        LockForecastConfig {
+            samples: 2,
+            horizon: 10.0,
+            venoms_per_attack: 1,
+            ..Default::default()
+        }
+    }
+
+    fn get_branches() -> Vec<AgentState> {
+        let mut primed = AgentState::default();
+        primed.set_flag(FType::Asthma, true);
+        primed.set_flag(FType::Anorexia, true);
+        vec![primed, AgentState::default()]
+    }
+
+    #[test]
+    fn test_forecast_window() {
+        let etas = forecast_locks(
+            &get_branches(),
+            &get_stack(),
+            &FirstAid::from_priorities(HashMap::new()),
+            &ClassCuringProfile::default(),
+            &get_config(),
+            1.0,
+        );
+        let soft = &etas[0];
+        assert_eq!(soft.lock, LockType::Soft);
+        assert_eq!(soft.probability, 1.0);
+        assert_eq!(soft.earliest, Some(1.0));
+        assert_eq!(soft.latest, Some(6.6));
+        assert_eq!(etas[3].lock, LockType::Hard);
+        assert_eq!(etas[3].probability, 0.0);
+        assert_eq!(etas[3].expected, None);
+    }
+
+    #[test]
+    fn test_forecast_horizon() {
+        let etas = forecast_locks(
+            &get_branches(),
+            &get_stack(),
+            &FirstAid::from_priorities(HashMap::new()),
+            &ClassCuringProfile::default(),
+            &LockForecastConfig {
+                horizon: 5.0,
+                ..get_config()
+            },
+            1.0,
+        );
+        assert_eq!(etas[0].probability, 0.5);
+        assert_eq!(etas[0].earliest, Some(1.0));
+        assert_eq!(etas[0].latest, Some(1.0));
+    }
+
+    #[test]
+    fn test_forecast_from_timeline() {
+        let mut timeline = AetTimeline::new();
+        timeline.state.for_agent(&"Benedicto".to_string(), &|you| {
+            you.set_flag(FType::Asthma, true);
+            you.set_flag(FType::Anorexia, true);
+            you.set_flag(FType::Slickness, true);
+        });
+        let etas = forecast_target_locks(
+            &timeline,
+            &"Benedicto".to_string(),
+            &get_stack(),
+            &get_config(),
+        );
+        assert_eq!(etas[0].probability, 1.0);
+        assert_eq!(etas[0].expected, Some(0.0));
+    }
+
+    #[test]
+    fn test_forecast_class_cures() {
+        let forecast = |class: Class| {
+            forecast_locks(
+                &vec![AgentState::default()],
+                &get_stack(),
+                &FirstAid::from_priorities(HashMap::new()),
+                &get_curing_profile(&class),
+                &get_config(),
+                0.0,
+            )
+        };
+        assert_eq!(forecast(Class::Bard)[0].expected, Some(5.6));
+        // Fitness takes the first asthma, so the lock waits on another attack.
+        assert_eq!(forecast(Class::Monk)[0].expected, Some(8.4));
+    }
+
+    #[test]
+    fn test_learned_cadence() {
+        let mut timeline = AetTimeline::new();
+        assert_eq!(get_attack_balance(&timeline, &get_config()), 2.8);
+        let me = timeline.who_am_i();
+        timeline
+            .state
+            .add_player_hint(&me, ATTACK_BALANCE_HINT, "2.0".to_string());
+        assert_eq!(get_attack_balance(&timeline, &get_config()), 2.0);
+    }
+}
@@ -0,0 +1,67 @@
+mod self_curing_tests {
+    use super::super::*;
+    use topper_core::timeline::BaseAgentState;
+
+    fn afflicted(affs: Vec<FType>) -> AgentState {
+        let mut branch = AgentState::get_base_state();
+        for aff in affs {
+            branch.set_flag(aff, true);
+        }
+        branch
+    }
+
+    fn commands(cures: &Vec<SelfCure>) -> Vec<String> {
+        cures.iter().map(|cure| cure.command.clone()).collect()
+    }
+
+    #[test]
+    fn test_one_cure_per_balance() {
+        let timeline = AetTimeline::new();
+        let branches = vec![afflicted(vec![
+            FType::Anorexia,
+            FType::Asthma,
+            FType::Clumsiness,
+            FType::Aeon,
+        ])];
+        let cures = SelfCuring::default().get_cures(&timeline, &branches, &None);
+        // Asthma blocks smoking for aeon and anorexia blocks eating, so clumsiness has to wait.
+        assert_eq!(cures.len(), 2);
+        assert_eq!(cures[0].command, "touch tree");
+        // Anorexia is on both the torso and skin epidermal orders.
+        assert!(cures[1].command.starts_with("apply epidermal to"));
+        assert_eq!(cures[0].affliction, FType::Aeon);
+        assert_eq!(cures[1].affliction, FType::Anorexia);
+    }
+
+    #[test]
+    fn test_low_share_waits() {
+        let timeline = AetTimeline::new();
+        let branches = vec![
+            afflicted(vec![FType::Clumsiness]),
+            afflicted(vec![]),
+            afflicted(vec![]),
+        ];
+        let cures = SelfCuring::default().get_cures(&timeline, &branches, &None);
+        assert_eq!(cures.len(), 0);
+    }
+
+    #[test]
+    fn test_class_cures() {
+        let timeline = AetTimeline::new();
+        let branches = vec![afflicted(vec![FType::Asthma])];
+        let cures = SelfCuring::default().get_cures(&timeline, &branches, &Some(Class::Monk));
+        assert_eq!(commands(&cures), vec!["fitness".to_string()]);
+        let cures = SelfCuring::default().get_cures(&timeline, &branches, &Some(Class::Bard));
+        assert_eq!(commands(&cures), vec!["eat decongestant".to_string()]);
+    }
+
+    #[test]
+    fn test_unaffected_afflictions_wait() {
+        let curing = SelfCuring::default();
+        let branches = vec![afflicted(vec![FType::Clumsiness, FType::Confusion])];
+        let candidates = curing.get_candidates(&branches, &Some(Class::Ascendril));
+        assert_eq!(candidates[0].0, FType::Confusion);
+        let candidates = curing.get_candidates(&branches, &Some(Class::Bard));
+        assert_eq!(candidates[0].0, FType::Clumsiness);
+    }
+}
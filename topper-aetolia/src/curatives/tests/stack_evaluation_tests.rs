@@ -0,0 +1,73 @@
+mod stack_evaluation_tests {
+    use super::super::*;
+    use std::collections::HashMap;
+
+    // Bards have no class cures, so only first aid cures anything.
+    fn get_evaluation(stack: Vec<VenomPlan>, priorities: Vec<(FType, u32)>) -> StackEvaluation {
+        let mut evaluation = StackEvaluation::new(
+            stack,
+            Class::Bard,
+            FirstAid::from_priorities(priorities.into_iter().collect::<HashMap<_, _>>()),
+        );
+        evaluation.config.trials = 4;
+        evaluation.config.time_limit = 10.0;
+        evaluation
+    }
+
+    #[test]
+    fn test_uncured_lock() {
+        let report = get_evaluation(
+            vec![
+                VenomPlan::Stick(FType::Asthma),
+                VenomPlan::Stick(FType::Anorexia),
+                VenomPlan::Stick(FType::Slickness),
+            ],
+            vec![],
+        )
+        .run();
+        assert_eq!(report.trials, 4);
+        let soft = &report.locks[0];
+        assert_eq!(soft.lock, LockType::Soft);
+        assert_eq!(soft.reached, 4);
+        assert_eq!(soft.average_time, Some(2.8));
+        assert_eq!(report.locks[3].reached, 0);
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(report.entries[0].applied, 4);
+        assert_eq!(report.entries[2].applied, 4);
+        assert!(report.entries.iter().all(|entry| entry.cured_early == 0));
+    }
+
+    #[test]
+    fn test_cured_early() {
+        let mut evaluation = get_evaluation(
+            vec![
+                VenomPlan::Stick(FType::Clumsiness),
+                VenomPlan::Stick(FType::Asthma),
+            ],
+            vec![(FType::Clumsiness, 1)],
+        );
+        evaluation.config.venoms_per_attack = 1;
+        let report = evaluation.run();
+        let clumsiness = &report.entries[0];
+        assert_eq!(clumsiness.affliction, FType::Clumsiness);
+        assert!(clumsiness.applied > 4);
+        assert!(clumsiness.cured_early_rate() > 0.5);
+        assert_eq!(report.entries[1].applied, 0);
+    }
+
+    #[test]
+    fn test_class_cured() {
+        let stack = vec![
+            VenomPlan::Stick(FType::Asthma),
+            VenomPlan::Stick(FType::Anorexia),
+        ];
+        let mut evaluation = get_evaluation(stack, vec![]);
+        evaluation.opponent = Class::Monk;
+        let report = evaluation.run();
+        let asthma = &report.entries[0];
+        assert_eq!(asthma.affliction, FType::Asthma);
+        // Fitness takes the first asthma, and is still recovering when the second lands.
+        assert_eq!(asthma.applied, 8);
+        assert_eq!(asthma.cured_early, 4);
+    }
+}
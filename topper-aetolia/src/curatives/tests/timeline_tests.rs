@@ -25,6 +25,7 @@ mod timeline_tests {
             })]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -59,6 +60,7 @@ mod timeline_tests {
             })]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -99,6 +101,7 @@ mod timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -107,6 +110,7 @@ mod timeline_tests {
             observations: Some(vec![AetObservation::LimbHeal("left leg".into(), 15.00)]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Promptless,
             time: 1000,
             me: "Seurimas".into(),
@@ -158,6 +162,7 @@ mod timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -169,6 +174,7 @@ mod timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Promptless,
             time: 1000,
             me: "Seurimas".into(),
@@ -226,6 +232,7 @@ mod timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -237,6 +244,7 @@ mod timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -248,6 +256,7 @@ mod timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Promptless,
             time: 1000,
             me: "Seurimas".into(),
@@ -311,6 +320,7 @@ mod timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 0,
             me: "Seurimas".into(),
@@ -330,6 +340,7 @@ mod timeline_tests {
             ]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Blackout,
             time: 1000,
             me: "Seurimas".into(),
@@ -338,6 +349,7 @@ mod timeline_tests {
             observations: Some(vec![AetObservation::Cured("heatspear".into())]),
             lines: vec![],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Promptless,
             time: 2000,
             me: "Seurimas".into(),
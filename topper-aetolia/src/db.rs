@@ -6,6 +6,7 @@ use crate::{
     agent::Hypnosis,
     classes::{Class, VenomPlan},
     curatives::first_aid::FirstAidPriorities,
+    timeline::{format_skill_balances_key, SkillBalances},
 };
 
 pub const HINT_TREE: &str = "HINTS";
@@ -35,6 +36,10 @@ pub trait AetDatabaseModule {
     fn insert_hint(&self, key: &String, value: &String);
 
     fn get_hint(&self, key: &String) -> Option<String>;
+
+    fn get_skill_balances(&self, who: &str, skill: &str) -> Option<SkillBalances>;
+
+    fn set_skill_balances(&self, who: &str, skill: &str, balances: SkillBalances);
 }
 
 impl<T: DatabaseModule> AetDatabaseModule for T {
@@ -94,4 +99,16 @@ impl<T: DatabaseModule> AetDatabaseModule for T {
                 .ok()
         })
     }
+
+    fn get_skill_balances(&self, who: &str, skill: &str) -> Option<SkillBalances> {
+        self.get_json::<SkillBalances>("balances", &format_skill_balances_key(who, skill))
+    }
+
+    fn set_skill_balances(&self, who: &str, skill: &str, balances: SkillBalances) {
+        self.insert_json::<SkillBalances>(
+            "balances",
+            &format_skill_balances_key(who, skill),
+            balances,
+        );
+    }
 }
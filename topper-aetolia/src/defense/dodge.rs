@@ -12,8 +12,11 @@ impl DodgeAction {
 }
 
 impl ActiveTransition for DodgeAction {
-    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        Vec::new()
+    fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        match self.act(timeline) {
+            Ok(command) => ProbableEvent::certain(vec![AetObservation::Sent(command)]),
+            Err(_) => vec![],
+        }
     }
     fn act(&self, _timeline: &AetTimeline) -> ActivateResult {
         Ok(match self.dodge_type {
@@ -16,7 +16,7 @@ use crate::{db::AetDatabaseModule, timeline::AetTimeline, types::*};
 
 pub fn should_regenerate(timeline: &AetTimeline, me: &String) -> bool {
     let me = timeline.state.borrow_agent(me);
-    if me.balanced(BType::Regenerate) {
+    if !me.balanced(BType::Regenerate) {
         false
     } else if let Some((_limb, damage, regenerating)) = me.get_restoring() {
         !regenerating && damage > 4000
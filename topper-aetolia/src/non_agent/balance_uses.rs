@@ -0,0 +1,61 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use topper_core::timeline::CType;
+
+use crate::timeline::AetTimelineState;
+use crate::types::BType;
+
+use super::AetNonAgent;
+
+// The skill someone last spent a balance on, and when.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct BalanceUse {
+    pub skill: String,
+    pub time: CType,
+}
+
+pub fn format_balance_uses_id(who: &str) -> String {
+    format!("{}_balance_uses", who)
+}
+
+pub trait AetTimelineBalanceUsesExt {
+    fn get_balance_use(&self, who: &str, balance: BType) -> Option<BalanceUse>;
+
+    fn take_balance_use(&mut self, who: &str, balance: BType) -> Option<BalanceUse>;
+
+    // Returns the use it replaces, if any.
+    fn record_balance_use(&mut self, who: &str, balance: BType, skill: &str) -> Option<BalanceUse>;
+}
+
+impl AetTimelineBalanceUsesExt for AetTimelineState {
+    fn get_balance_use(&self, who: &str, balance: BType) -> Option<BalanceUse> {
+        match self.non_agent_states.get(&format_balance_uses_id(who)) {
+            Some(AetNonAgent::BalanceUses(uses)) => uses.get(&balance).cloned(),
+            Some(_) => panic!("Non-balance uses in balance uses spot!"),
+            None => None,
+        }
+    }
+
+    fn take_balance_use(&mut self, who: &str, balance: BType) -> Option<BalanceUse> {
+        match self.non_agent_states.get_mut(&format_balance_uses_id(who)) {
+            Some(AetNonAgent::BalanceUses(uses)) => uses.remove(&balance),
+            Some(_) => panic!("Non-balance uses in balance uses spot!"),
+            None => None,
+        }
+    }
+
+    fn record_balance_use(&mut self, who: &str, balance: BType, skill: &str) -> Option<BalanceUse> {
+        let balance_use = BalanceUse {
+            skill: skill.to_string(),
+            time: self.time,
+        };
+        match self
+            .non_agent_states
+            .entry(format_balance_uses_id(who))
+            .or_insert_with(|| AetNonAgent::BalanceUses(HashMap::new()))
+        {
+            AetNonAgent::BalanceUses(uses) => uses.insert(balance, balance_use),
+            _ => panic!("Non-balance uses in balance uses spot!"),
+        }
+    }
+}
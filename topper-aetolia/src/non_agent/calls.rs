@@ -0,0 +1,63 @@
+use serde::Deserialize;
+use topper_core::timeline::CType;
+
+use crate::timeline::AetTimelineState;
+
+use super::{AetNonAgent, AetTimelinePlayersExt};
+
+// Only the most recent calls are kept.
+const MAX_CALLS: usize = 20;
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct TargetCall {
+    pub caller: String,
+    pub target: String,
+    pub channel: String,
+    pub time: CType,
+    // Called by someone on our enemies list.
+    pub enemy: bool,
+}
+
+pub fn format_calls_id(me: &str) -> String {
+    format!("{}_calls", me)
+}
+
+pub trait AetTimelineCallsExt {
+    fn add_call(&mut self, caller: &str, target: &str, channel: &str);
+
+    fn get_calls(&self) -> Vec<TargetCall>;
+
+    fn get_calls_since(&self, time: CType) -> Vec<TargetCall> {
+        self.get_calls()
+            .into_iter()
+            .filter(|call| call.time > time)
+            .collect()
+    }
+}
+
+impl AetTimelineCallsExt for AetTimelineState {
+    fn add_call(&mut self, caller: &str, target: &str, channel: &str) {
+        let call = TargetCall {
+            caller: caller.to_string(),
+            target: target.to_string(),
+            channel: channel.to_string(),
+            time: self.time,
+            enemy: self.get_enemies().iter().any(|enemy| enemy.eq(caller)),
+        };
+        let mut calls = self.get_calls();
+        calls.push(call);
+        if calls.len() > MAX_CALLS {
+            calls.remove(0);
+        }
+        self.non_agent_states
+            .insert(format_calls_id(&self.me), AetNonAgent::Calls(calls));
+    }
+
+    fn get_calls(&self) -> Vec<TargetCall> {
+        match self.non_agent_states.get(&format_calls_id(&self.me)) {
+            Some(AetNonAgent::Calls(calls)) => calls.clone(),
+            Some(_) => panic!("Non-call list in calls spot!"),
+            None => vec![],
+        }
+    }
+}
@@ -17,6 +17,26 @@ pub enum EvalStatus {
     AlmostDead,
 }
 
+impl EvalStatus {
+    pub fn from_health_percent(percent: f32) -> Self {
+        if percent >= 100.0 {
+            EvalStatus::Uninjured
+        } else if percent >= 80.0 {
+            EvalStatus::SlightlyBruised
+        } else if percent >= 60.0 {
+            EvalStatus::HeavilyBruised
+        } else if percent >= 45.0 {
+            EvalStatus::SeveralOpenWounds
+        } else if percent >= 30.0 {
+            EvalStatus::CoveredInBlood
+        } else if percent >= 15.0 {
+            EvalStatus::BleedingHeavily
+        } else {
+            EvalStatus::AlmostDead
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct Denizen {
     pub id: String,
@@ -76,6 +96,9 @@ impl AetTimelineDenizenExt for AetTimelineState {
             self.for_room(room_id, &|mut room| {
                 room.denizens.insert(denizen_id);
             });
+            self.for_denizen(denizen_id, &|denizen| {
+                denizen.room_id = room_id;
+            });
         } else {
             self.non_agent_states.insert(
                 key,
@@ -112,7 +135,10 @@ impl AetTimelineDenizenExt for AetTimelineState {
         }
         self.for_room(room_id, &|mut room| {
             room.denizens.insert(denizen_id);
-        })
+        });
+        self.for_denizen(denizen_id, &|denizen| {
+            denizen.room_id = room_id;
+        });
     }
 
     fn for_denizen(&mut self, denizen_id: i64, action: &Fn(&mut Denizen)) {
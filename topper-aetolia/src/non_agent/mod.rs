@@ -1,14 +1,27 @@
+pub mod balance_uses;
+pub mod calls;
 pub mod denizen;
+pub mod players;
 pub mod rooms;
+pub use balance_uses::*;
+pub use calls::*;
 pub use denizen::*;
+pub use players::*;
 pub use rooms::*;
 use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::timeline::LearnedBalances;
+use crate::types::BType;
 
 #[derive(Debug, Deserialize, PartialEq, Clone)]
 pub enum AetNonAgent {
     Room(Room),
     Denizen(Denizen),
     Players(Vec<String>),
+    Calls(Vec<TargetCall>),
+    BalanceUses(HashMap<BType, BalanceUse>),
+    LearnedBalances(LearnedBalances),
 }
 
 impl AetNonAgent {
@@ -0,0 +1,50 @@
+use crate::timeline::AetTimelineState;
+
+use super::AetNonAgent;
+
+pub fn format_allies_id(me: &str) -> String {
+    format!("{}_allies", me)
+}
+
+pub fn format_enemies_id(me: &str) -> String {
+    format!("{}_enemies", me)
+}
+
+pub trait AetTimelinePlayersExt {
+    fn get_allies(&self) -> Vec<String>;
+
+    fn get_enemies(&self) -> Vec<String>;
+
+    fn get_my_target(&self) -> Option<String>;
+
+    fn set_my_target(&mut self, target: &str);
+}
+
+impl AetTimelinePlayersExt for AetTimelineState {
+    fn get_allies(&self) -> Vec<String> {
+        match self.non_agent_states.get(&format_allies_id(&self.me)) {
+            Some(AetNonAgent::Players(allies)) => allies.clone(),
+            Some(_) => panic!("Non-player list in allies spot!"),
+            None => vec![],
+        }
+    }
+
+    fn get_enemies(&self) -> Vec<String> {
+        match self.non_agent_states.get(&format_enemies_id(&self.me)) {
+            Some(AetNonAgent::Players(enemies)) => enemies.clone(),
+            Some(_) => panic!("Non-player list in enemies spot!"),
+            None => vec![],
+        }
+    }
+
+    // The target the game last told us about.
+    fn get_my_target(&self) -> Option<String> {
+        self.get_my_hint(&"target".to_string())
+            .filter(|target| !target.is_empty())
+    }
+
+    fn set_my_target(&mut self, target: &str) {
+        let me = self.me.clone();
+        self.add_player_hint(&me, "target", target.to_string());
+    }
+}
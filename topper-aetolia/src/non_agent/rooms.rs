@@ -91,6 +91,13 @@ pub trait AetTimelineRoomExt {
     fn get_my_room_mut(&mut self) -> Option<&mut Room>;
 
     fn set_player_room(&mut self, room_id: i64, player: &str);
+
+    fn remove_player_from_room(&mut self, room_id: i64, player: &str);
+
+    fn clear_room_players(&mut self, room_id: i64);
+
+    // Everyone else GMCP has seen in our room, sorted by name.
+    fn get_players_in_my_room(&self) -> Vec<String>;
 }
 
 impl AetTimelineRoomExt for AetTimelineState {
@@ -137,4 +144,44 @@ impl AetTimelineRoomExt for AetTimelineState {
             me.room_id = room_id;
         });
     }
+
+    // Players we no longer see are somewhere unknown, rather than left where we saw them.
+    fn remove_player_from_room(&mut self, room_id: i64, player: &str) {
+        let player = player.to_string();
+        self.for_room(room_id, &|room| {
+            room.players.remove(&player);
+        });
+        self.for_agent(&player, &|me| {
+            if me.room_id == room_id {
+                me.room_id = 0;
+            }
+        });
+    }
+
+    fn clear_room_players(&mut self, room_id: i64) {
+        let players: Vec<String> = self
+            .non_agent_states
+            .get(&format_room_id(room_id))
+            .and_then(AetNonAgent::as_room)
+            .map(|room| room.players.iter().cloned().collect())
+            .unwrap_or_default();
+        for player in players {
+            self.remove_player_from_room(room_id, &player);
+        }
+    }
+
+    fn get_players_in_my_room(&self) -> Vec<String> {
+        let mut players: Vec<String> = self
+            .get_my_room()
+            .map(|room| {
+                room.players
+                    .iter()
+                    .filter(|player| !player.eq(&&self.me))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        players.sort();
+        players
+    }
 }
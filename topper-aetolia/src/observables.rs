@@ -1,5 +1,6 @@
+use crate::bt::PlanExplanation;
 use crate::timeline::{simulation_slice, AetObservation, AetTimeSlice, AetTimeline};
-use crate::types::BType;
+use crate::types::*;
 use std::collections::HashMap;
 use topper_core::timeline::CType;
 
@@ -17,13 +18,195 @@ impl ProbableEvent {
     pub fn certain(observations: ActiveEvent) -> Vec<Self> {
         vec![Self::new(observations, 1)]
     }
+    pub fn get_observations(&self) -> &ActiveEvent {
+        &self.0
+    }
+    pub fn get_weight(&self) -> u32 {
+        self.1
+    }
+    // Every pairing of a first and second event, as when two actions are sent together.
+    pub fn combine(first: &Vec<Self>, second: &Vec<Self>) -> Vec<Self> {
+        let mut results = vec![];
+        for ProbableEvent(simulate_first, weight_first) in first.iter() {
+            for ProbableEvent(simulate_second, weight_second) in second.iter() {
+                let mut observations = vec![];
+                observations.append(&mut simulate_first.clone());
+                observations.append(&mut simulate_second.clone());
+                results.push(ProbableEvent(observations, weight_first * weight_second));
+            }
+        }
+        results
+    }
+}
+
+pub const HIT_WEIGHT: u32 = 16;
+pub const MISS_WEIGHT: u32 = 1;
+pub const DODGE_WEIGHT: u32 = 4;
+pub const PARRY_WEIGHT: u32 = 12;
+
+// Relative odds of the ways an attack can play out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttackWeights {
+    pub hit: u32,
+    pub miss: u32,
+    pub parry: u32,
+    pub dodge: u32,
+    pub rebound: u32,
+}
+
+impl AttackWeights {
+    pub fn certain() -> Self {
+        AttackWeights {
+            hit: 1,
+            miss: 0,
+            parry: 0,
+            dodge: 0,
+            rebound: 0,
+        }
+    }
+
+    pub fn against(target: &AgentState, limb: Option<LType>, reboundable: bool) -> Self {
+        if reboundable && target.is(FType::Rebounding) {
+            return AttackWeights {
+                hit: 0,
+                miss: 0,
+                parry: 0,
+                dodge: 0,
+                rebound: 1,
+            };
+        }
+        AttackWeights {
+            hit: HIT_WEIGHT,
+            miss: MISS_WEIGHT,
+            parry: match limb {
+                Some(limb) if target.get_parrying() == Some(limb) && target.can_parry() => {
+                    PARRY_WEIGHT
+                }
+                _ => 0,
+            },
+            dodge: if target.dodge_state.can_dodge() {
+                DODGE_WEIGHT
+            } else {
+                0
+            },
+            rebound: 0,
+        }
+    }
+}
+
+// A single attack, expanded into its hit, miss, parry, dodge and rebound outcomes.
+pub struct AttackSimulation {
+    attack: AetObservation,
+    target: String,
+    limb: Option<LType>,
+    venoms: Vec<String>,
+    reboundable: bool,
+    on_hit: Vec<AetObservation>,
+}
+
+impl AttackSimulation {
+    pub fn new(attack: AetObservation, target: &str) -> Self {
+        AttackSimulation {
+            attack,
+            target: target.to_string(),
+            limb: None,
+            venoms: Vec::new(),
+            reboundable: false,
+            on_hit: Vec::new(),
+        }
+    }
+
+    pub fn limb(mut self, limb: LType) -> Self {
+        if limb != LType::SIZE {
+            self.limb = Some(limb);
+        }
+        self
+    }
+
+    pub fn venom(mut self, venom: &str) -> Self {
+        self.reboundable = true;
+        if venom.len() > 0 {
+            self.venoms.push(venom.to_string());
+        }
+        self
+    }
+
+    pub fn venoms(self, venoms: Vec<&str>) -> Self {
+        venoms
+            .into_iter()
+            .fold(self, |simulation, venom| simulation.venom(venom))
+    }
+
+    pub fn reboundable(mut self) -> Self {
+        self.reboundable = true;
+        self
+    }
+
+    pub fn on_hit(mut self, observation: AetObservation) -> Self {
+        self.on_hit.push(observation);
+        self
+    }
+
+    pub fn events(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        let target = timeline.state.borrow_agent(&self.target);
+        self.weighted_events(AttackWeights::against(&target, self.limb, self.reboundable))
+    }
+
+    pub fn weighted_events(&self, weights: AttackWeights) -> Vec<ProbableEvent> {
+        let mut events = Vec::new();
+        if weights.hit > 0 {
+            let mut observations = vec![self.attack.clone()];
+            observations.append(&mut self.on_hit.clone());
+            for venom in self.venoms.iter() {
+                observations.push(AetObservation::Devenoms(venom.clone()));
+            }
+            events.push(ProbableEvent::new(observations, weights.hit));
+        }
+        if weights.miss > 0 {
+            events.push(ProbableEvent::new(
+                vec![
+                    self.attack.clone(),
+                    AetObservation::Misses(self.target.clone()),
+                ],
+                weights.miss,
+            ));
+        }
+        if let (true, Some(limb)) = (weights.parry > 0, self.limb) {
+            events.push(ProbableEvent::new(
+                vec![
+                    self.attack.clone(),
+                    AetObservation::Parry(self.target.clone(), limb.to_string()),
+                ],
+                weights.parry,
+            ));
+        }
+        if weights.dodge > 0 {
+            events.push(ProbableEvent::new(
+                vec![
+                    self.attack.clone(),
+                    AetObservation::Dodges(self.target.clone()),
+                ],
+                weights.dodge,
+            ));
+        }
+        if weights.rebound > 0 {
+            let mut observations = vec![self.attack.clone()];
+            if self.venoms.is_empty() {
+                observations.push(AetObservation::Rebounds);
+            }
+            for venom in self.venoms.iter() {
+                observations.push(AetObservation::Rebounds);
+                observations.push(AetObservation::Devenoms(venom.clone()));
+            }
+            events.push(ProbableEvent::new(observations, weights.rebound));
+        }
+        events
+    }
 }
 
 pub trait ActiveTransition {
     fn act(&self, timline: &AetTimeline) -> ActivateResult;
-    fn simulate(&self, timline: &AetTimeline) -> Vec<ProbableEvent> {
-        todo!()
-    }
+    fn simulate(&self, timline: &AetTimeline) -> Vec<ProbableEvent>;
 }
 
 #[derive(Default)]
@@ -32,6 +215,7 @@ pub struct ActionPlan {
     qeb: Option<Box<dyn ActiveTransition>>,
     back_qeb: Option<Box<dyn ActiveTransition>>,
     other: HashMap<BType, Box<dyn ActiveTransition>>,
+    explanation: PlanExplanation,
 }
 
 impl core::fmt::Debug for ActionPlan {
@@ -47,9 +231,18 @@ impl ActionPlan {
             qeb: None,
             back_qeb: None,
             other: HashMap::new(),
+            explanation: PlanExplanation::default(),
         }
     }
 
+    pub fn explain(&mut self, explanation: PlanExplanation) {
+        self.explanation = explanation;
+    }
+
+    pub fn get_explanation(&self) -> &PlanExplanation {
+        &self.explanation
+    }
+
     pub fn join(
         old_qeb: Box<dyn ActiveTransition>,
         action: Box<dyn ActiveTransition>,
@@ -144,14 +337,30 @@ impl ActionPlan {
     }
 
     pub fn get_time_slice(&self, timeline: &AetTimeline) -> Option<AetTimeSlice> {
-        if let Some((transition, balance, time)) = self.get_next_balance(timeline) {
-            if let Some(ProbableEvent(observations, _)) = transition.simulate(timeline).first() {
-                Some(simulation_slice(observations.to_vec(), time))
-            } else {
-                None
-            }
+        self.get_time_slices(timeline)
+            .into_iter()
+            .next()
+            .map(|(slice, _weight)| slice)
+    }
+
+    // Every outcome of the next action in the plan, with its relative weight.
+    pub fn get_time_slices(&self, timeline: &AetTimeline) -> Vec<(AetTimeSlice, u32)> {
+        if let Some((transition, _balance, time)) = self.get_next_balance(timeline) {
+            // Actions are simulated from the perspective of whoever takes them.
+            let mut perspective = timeline.branch();
+            perspective.state.me = self.who.clone();
+            transition
+                .simulate(&perspective)
+                .into_iter()
+                .map(|ProbableEvent(observations, weight)| {
+                    let mut slice =
+                        simulation_slice(observations, timeline.state.time + time.max(0));
+                    slice.me = self.who.clone();
+                    (slice, weight)
+                })
+                .collect()
         } else {
-            None
+            Vec::new()
         }
     }
 }
@@ -162,6 +371,9 @@ impl ActiveTransition for Inactivity {
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         Ok(format!(""))
     }
+    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        ProbableEvent::certain(vec![])
+    }
 }
 
 pub struct PlainAction(String);
@@ -176,6 +388,9 @@ impl ActiveTransition for PlainAction {
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         Ok(self.0.clone())
     }
+    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        ProbableEvent::certain(vec![AetObservation::Sent(self.0.clone())])
+    }
 }
 
 pub struct Trace(String);
@@ -190,6 +405,9 @@ impl ActiveTransition for Trace {
     fn act(&self, timline: &AetTimeline) -> ActivateResult {
         Ok(format!("echo {}", self.0))
     }
+    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        ProbableEvent::certain(vec![])
+    }
 }
 
 pub struct SeparatorAction(Box<dyn ActiveTransition>, Box<dyn ActiveTransition>);
@@ -209,15 +427,6 @@ impl ActiveTransition for SeparatorAction {
         ))
     }
     fn simulate(&self, timeline: &AetTimeline) -> Vec<ProbableEvent> {
-        let mut results = vec![];
-        for ProbableEvent(simulate_first, weight_first) in self.0.simulate(&timeline).iter() {
-            for ProbableEvent(simulate_second, weight_second) in self.1.simulate(&timeline).iter() {
-                let mut observations = vec![];
-                observations.append(&mut simulate_first.clone());
-                observations.append(&mut simulate_second.clone());
-                results.push(ProbableEvent(observations, weight_first * weight_second));
-            }
-        }
-        results
+        ProbableEvent::combine(&self.0.simulate(&timeline), &self.1.simulate(&timeline))
     }
 }
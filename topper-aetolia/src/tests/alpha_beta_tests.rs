@@ -0,0 +1,125 @@
+mod alpha_beta_tests {
+    use super::super::*;
+    use crate::alpha_beta::duel_fixtures::*;
+    use std::cell::Cell;
+    use topper_core::timeline::db::DummyDatabaseModule;
+
+    fn get_simulation(
+        landing_weight: u32,
+        opponent: &'static [&'static str],
+    ) -> DuelSimulation<TestPlanner, TestPlanner> {
+        let mut timeline = AetTimeline::new();
+        timeline.state.me = "Seurimas".to_string();
+        DuelSimulation::new(
+            timeline,
+            Duelist::new(
+                "Seurimas",
+                TestPlanner {
+                    strategies: &["reliable", "risky", "idle"],
+                    landing_weight,
+                },
+            ),
+            Duelist::new(
+                "Kaiza",
+                TestPlanner {
+                    strategies: opponent,
+                    landing_weight: 1,
+                },
+            ),
+        )
+    }
+
+    #[test]
+    fn test_recommends_likely_pressure() {
+        let simulation = get_simulation(3, &["idle"]);
+        let scores = simulation.score_strategies::<DummyDatabaseModule>(
+            1,
+            &PressureEvaluator::default(),
+            None,
+        );
+        assert_eq!(scores.len(), 2);
+        let recommendation = simulation
+            .recommend_strategy::<DummyDatabaseModule>(1, &PressureEvaluator::default(), None)
+            .unwrap();
+        assert_eq!(recommendation.strategy, "risky");
+    }
+
+    #[test]
+    fn test_weights_unlikely_pressure() {
+        let simulation = get_simulation(0, &["idle"]);
+        let recommendation = simulation
+            .recommend_strategy::<DummyDatabaseModule>(1, &PressureEvaluator::default(), None)
+            .unwrap();
+        assert_eq!(recommendation.strategy, "reliable");
+    }
+
+    #[test]
+    fn test_opponent_replies() {
+        let quiet = get_simulation(3, &["idle"])
+            .recommend_strategy::<DummyDatabaseModule>(1, &PressureEvaluator::default(), None)
+            .unwrap();
+        let hostile = get_simulation(3, &["reliable", "risky"])
+            .recommend_strategy::<DummyDatabaseModule>(1, &PressureEvaluator::default(), None)
+            .unwrap();
+        assert!(hostile.score < quiet.score);
+    }
+
+    #[test]
+    fn test_custom_evaluator() {
+        let simulation = get_simulation(3, &["idle"]);
+        let anorexia_only = |timeline: &AetTimeline, _me: &String, target: &String| {
+            if timeline.state.borrow_agent(target).is(FType::Anorexia) {
+                1.0
+            } else {
+                0.0
+            }
+        };
+        let recommendation = simulation
+            .recommend_strategy::<DummyDatabaseModule>(1, &anorexia_only, None)
+            .unwrap();
+        assert_eq!(recommendation.strategy, "risky");
+        assert_eq!(recommendation.score, 0.75);
+    }
+
+    // Pressure, optionally without its bounds, counting how many leaves were scored.
+    struct CountingEvaluator {
+        bounded: bool,
+        evaluations: Cell<usize>,
+    }
+
+    impl DuelEvaluator for CountingEvaluator {
+        fn evaluate(&self, timeline: &AetTimeline, me: &String, target: &String) -> f32 {
+            self.evaluations.set(self.evaluations.get() + 1);
+            PressureEvaluator::default().evaluate(timeline, me, target)
+        }
+
+        fn bounds(&self) -> (f32, f32) {
+            if self.bounded {
+                PressureEvaluator::default().bounds()
+            } else {
+                (std::f32::NEG_INFINITY, std::f32::INFINITY)
+            }
+        }
+    }
+
+    #[test]
+    fn test_chance_pruning_keeps_scores() {
+        let simulation = get_simulation(3, &["reliable", "risky", "idle"]);
+        let unbounded = CountingEvaluator {
+            bounded: false,
+            evaluations: Cell::new(0),
+        };
+        let bounded = CountingEvaluator {
+            bounded: true,
+            evaluations: Cell::new(0),
+        };
+        let full = simulation.score_strategies::<DummyDatabaseModule>(2, &unbounded, None);
+        let pruned = simulation.score_strategies::<DummyDatabaseModule>(2, &bounded, None);
+        assert_eq!(full.len(), pruned.len());
+        for (full, pruned) in full.iter().zip(pruned.iter()) {
+            assert_eq!(full.strategy, pruned.strategy);
+            assert!((full.score - pruned.score).abs() < 0.001);
+        }
+        assert!(bounded.evaluations.get() <= unbounded.evaluations.get());
+    }
+}
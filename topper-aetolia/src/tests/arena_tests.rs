@@ -0,0 +1,78 @@
+mod arena_tests {
+    use super::super::*;
+    use crate::alpha_beta::duel_fixtures::*;
+
+    const STRATEGIES: &[&str] = &["lock", "single", "idle"];
+
+    fn get_arena(first: &str, second: &str) -> Arena<TestPlanner, TestPlanner> {
+        Arena::new(
+            ArenaConfig {
+                fights: 3,
+                time_limit: 20.0,
+                ..Default::default()
+            },
+            Combatant::new(
+                "Seurimas",
+                Class::Infiltrator,
+                TestPlanner::new(STRATEGIES),
+                first,
+            ),
+            Combatant::new("Kaiza", Class::Bard, TestPlanner::new(STRATEGIES), second),
+        )
+    }
+
+    #[test]
+    fn test_lock_wins() {
+        let report = get_arena("lock", "single").run();
+        assert_eq!(report.fights, 3);
+        assert_eq!(report.combatants[0].wins, 3);
+        assert_eq!(report.combatants[0].win_rate, 1.0);
+        assert_eq!(report.combatants[0].average_time_to_lock, Some(0.0));
+        assert_eq!(report.combatants[0].average_time_to_kill, Some(10.0));
+        assert_eq!(report.combatants[1].wins, 0);
+        assert!(report.combatants[1].average_affs >= 5.0);
+    }
+
+    #[test]
+    fn test_idle_draws() {
+        let report = get_arena("idle", "idle").run();
+        assert_eq!(report.draws, 3);
+        assert_eq!(report.combatants[0].average_time_to_lock, None);
+        assert_eq!(report.combatants[1].average_affs, 0.0);
+    }
+
+    #[test]
+    fn test_seeded_fights_repeat() {
+        let arena = get_arena("single", "lock");
+        let first = arena.fight(7);
+        let second = arena.fight(7);
+        assert_eq!(first.winner, second.winner);
+        assert_eq!(first.time_to_kill, second.time_to_kill);
+        assert_eq!(first.average_affs, second.average_affs);
+    }
+
+    #[test]
+    fn test_planner_needs_trees() {
+        assert!(BehaviorTreePlanner::new(Class::Zealot, "base", None).is_err());
+        assert!(BehaviorTreePlanner::new(Class::Ravager, "base", None).is_err());
+        let planner = BehaviorTreePlanner::new(Class::Infiltrator, "aggro", None).unwrap();
+        assert_eq!(planner.get_strategies(), &["aggro"]);
+    }
+
+    #[test]
+    fn test_planner_stack_scoped() {
+        let stack: Vec<VenomPlan> = serde_json::from_str("[]").unwrap();
+        let planner =
+            BehaviorTreePlanner::new(Class::Infiltrator, "arena_scoped", Some(stack)).unwrap();
+        let key = "infiltrator_arena_scoped".to_string();
+        let seen = planner.with_stack("arena_scoped", || {
+            LOADED_VENOM_PLANS.read().unwrap().get(&key).cloned()
+        });
+        assert_eq!(
+            seen.map(|stack| stack.map(|stack| stack.len())),
+            Some(Some(0))
+        );
+        // Anyone else planning with the same tree gets their own stack, not ours.
+        assert!(LOADED_VENOM_PLANS.read().unwrap().get(&key).is_none());
+    }
+}
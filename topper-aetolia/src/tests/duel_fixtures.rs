@@ -0,0 +1,84 @@
+// Attacks and planners with scripted outcomes, shared by the duel search and arena tests.
+use crate::alpha_beta::ActionPlanner;
+use crate::db::AetDatabaseModule;
+use crate::observables::*;
+use crate::timeline::*;
+
+// Each outcome lands its affs with its relative weight, then waits out its balance.
+pub struct TestAttack {
+    pub target: String,
+    pub outcomes: Vec<(Vec<&'static str>, u32)>,
+}
+
+impl ActiveTransition for TestAttack {
+    fn act(&self, _timeline: &AetTimeline) -> ActivateResult {
+        Ok("attack".to_string())
+    }
+    fn simulate(&self, _timeline: &AetTimeline) -> Vec<ProbableEvent> {
+        self.outcomes
+            .iter()
+            .map(|(affs, weight)| {
+                let mut observations = vec![AetObservation::Balance("Balance".to_string(), 2.0)];
+                for aff in affs.iter() {
+                    observations.push(AetObservation::OtherAfflicted(
+                        self.target.clone(),
+                        aff.to_string(),
+                    ));
+                }
+                ProbableEvent::new(observations, *weight)
+            })
+            .collect()
+    }
+}
+
+// "lock" and "single" always land, "reliable" and "risky" trade certainty for affs, and
+// anything else does nothing.
+pub struct TestPlanner {
+    pub strategies: &'static [&'static str],
+    pub landing_weight: u32,
+}
+
+impl TestPlanner {
+    pub fn new(strategies: &'static [&'static str]) -> Self {
+        TestPlanner {
+            strategies,
+            landing_weight: 1,
+        }
+    }
+}
+
+impl ActionPlanner for TestPlanner {
+    fn get_strategies(&self) -> &'static [&'static str] {
+        self.strategies
+    }
+    fn get_plan(
+        &self,
+        _timeline: &AetTimeline,
+        actor: &String,
+        target: &String,
+        strategy: &str,
+        _db: Option<&impl AetDatabaseModule>,
+    ) -> ActionPlan {
+        let mut plan = ActionPlan::new(actor);
+        let outcomes = match strategy {
+            "lock" => vec![(
+                vec!["anorexia", "slickness", "asthma", "paresis", "impatience"],
+                1,
+            )],
+            "single" => vec![(vec!["clumsiness"], 1)],
+            "reliable" => vec![(vec!["asthma"], 1)],
+            "risky" => vec![
+                (vec!["asthma", "anorexia", "slickness"], self.landing_weight),
+                (vec![], 1),
+            ],
+            _ => vec![],
+        };
+        if outcomes.len() > 0 {
+            plan.add_to_qeb(Box::new(TestAttack {
+                target: target.clone(),
+                outcomes,
+            }));
+        }
+        plan
+    }
+}
@@ -3,11 +3,10 @@ use crate::classes::{
     VENOM_AFFLICTS,
 };
 use crate::curatives::{
-    handle_simple_cure_action, remove_in_order, top_aff, CALORIC_TORSO_ORDER, PILL_CURE_ORDERS,
-    PILL_DEFENCES, SALVE_CURE_ORDERS, SMOKE_CURE_ORDERS,
+    cure_orders, handle_simple_cure_action, remove_in_order, top_aff, CALORIC_TORSO_ORDER,
 };
 use crate::db::AetDatabaseModule;
-use crate::non_agent::AetNonAgent;
+use crate::non_agent::{format_allies_id, format_enemies_id, AetNonAgent};
 use crate::timeline::*;
 use crate::types::*;
 use log::warn;
@@ -43,7 +42,17 @@ pub fn apply_observation(
                         .initialize_for_normalized_class(class.normal());
                 });
             }
-            handle_combat_action(combat_action, timeline, before, after, db)?;
+            if let Some(learning_db) = db {
+                handle_combat_action_balances(
+                    combat_action,
+                    timeline,
+                    after,
+                    learning_db,
+                    |timeline| handle_combat_action(combat_action, timeline, before, after, db),
+                )?;
+            } else {
+                handle_combat_action(combat_action, timeline, before, after, db)?;
+            }
         }
         AetObservation::Proc(combat_action) => {
             handle_combat_action(combat_action, timeline, before, after, db)?;
@@ -113,6 +122,9 @@ pub fn apply_observation(
             });
         }
         AetObservation::BalanceBack(balance) => {
+            if let Some(db) = db {
+                handle_balance_back(timeline, db, BType::from_name(&balance));
+            }
             let who_am_i = timeline.me.clone();
             for_agent(timeline, &who_am_i, &|me: &mut AgentState| {
                 me.set_balance(BType::from_name(&balance), 0.0);
@@ -293,10 +305,9 @@ pub fn apply_observation(
                         allies.push(strip_ansi(line));
                     }
                 }
-                timeline.non_agent_states.insert(
-                    format!("{}_allies", timeline.me),
-                    AetNonAgent::Players(allies),
-                );
+                timeline
+                    .non_agent_states
+                    .insert(format_allies_id(&timeline.me), AetNonAgent::Players(allies));
             }
             "Enemies" => {
                 let mut tta = -3;
@@ -314,7 +325,7 @@ pub fn apply_observation(
                     }
                 }
                 timeline.non_agent_states.insert(
-                    format!("{}_enemies", timeline.me),
+                    format_enemies_id(&timeline.me),
                     AetNonAgent::Players(enemies),
                 );
             }
@@ -1031,13 +1042,14 @@ pub fn apply_or_infer_cure(
     first_person: bool,
 ) -> Result<Vec<FType>, String> {
     let mut found_cures = Vec::new();
+    let cure_orders = cure_orders();
     if let Some(AetObservation::Cured(aff_name)) = after.get(1) {
         if let Some(aff) = FType::from_name(&aff_name) {
             match cure {
                 SimpleCure::Pill(pill_name) => {
                     who.observe_flag(FType::Anorexia, false);
                     if aff != FType::Void && aff != FType::Weakvoid {
-                        if let Some(order) = PILL_CURE_ORDERS.get(pill_name) {
+                        if let Some(order) = cure_orders.pill_order(pill_name) {
                             for pill_aff in order.iter() {
                                 if *pill_aff == aff {
                                     break;
@@ -1051,9 +1063,7 @@ pub fn apply_or_infer_cure(
                 SimpleCure::Salve(salve_name, salve_loc) => {
                     who.observe_flag(FType::Slickness, false);
                     if aff != FType::Void && aff != FType::Weakvoid {
-                        if let Some(order) =
-                            SALVE_CURE_ORDERS.get(&(salve_name.to_string(), salve_loc.to_string()))
-                        {
+                        if let Some(order) = cure_orders.salve_order(salve_name, salve_loc) {
                             for salve_aff in order.iter() {
                                 if *salve_aff == aff {
                                     break;
@@ -1067,7 +1077,7 @@ pub fn apply_or_infer_cure(
                 SimpleCure::Smoke(herb_name) => {
                     who.observe_flag(FType::Asthma, false);
                     if aff != FType::Void && aff != FType::Weakvoid {
-                        if let Some(order) = SMOKE_CURE_ORDERS.get(herb_name) {
+                        if let Some(order) = cure_orders.smoke_order(herb_name) {
                             for herb_aff in order.iter() {
                                 if *herb_aff == aff {
                                     break;
@@ -1087,7 +1097,7 @@ pub fn apply_or_infer_cure(
             match cure {
                 SimpleCure::Pill(pill_name) => {
                     who.observe_flag(FType::Anorexia, false);
-                    if let Some(order) = PILL_CURE_ORDERS.get(pill_name) {
+                    if let Some(order) = cure_orders.pill_order(pill_name) {
                         for pill_aff in order.iter() {
                             if *pill_aff == def {
                                 break;
@@ -1099,9 +1109,7 @@ pub fn apply_or_infer_cure(
                 }
                 SimpleCure::Salve(salve_name, salve_loc) => {
                     who.observe_flag(FType::Slickness, false);
-                    if let Some(order) =
-                        SALVE_CURE_ORDERS.get(&(salve_name.to_string(), salve_loc.to_string()))
-                    {
+                    if let Some(order) = cure_orders.salve_order(salve_name, salve_loc) {
                         for salve_aff in order.iter() {
                             if *salve_aff == def {
                                 break;
@@ -1113,7 +1121,7 @@ pub fn apply_or_infer_cure(
                 }
                 SimpleCure::Smoke(herb_name) => {
                     who.observe_flag(FType::Asthma, false);
-                    if let Some(order) = SMOKE_CURE_ORDERS.get(herb_name) {
+                    if let Some(order) = cure_orders.smoke_order(herb_name) {
                         for herb_aff in order.iter() {
                             if *herb_aff == def {
                                 break;
@@ -1132,7 +1140,7 @@ pub fn apply_or_infer_cure(
             SimpleCure::Pill(pill_name) => {
                 who.observe_flag(FType::Anorexia, false);
                 if pill_name == "anabiotic" {
-                } else if let Some(order) = PILL_CURE_ORDERS.get(pill_name) {
+                } else if let Some(order) = cure_orders.pill_order(pill_name) {
                     if first_person {
                         for pill_aff in order.iter() {
                             who.observe_flag(*pill_aff, false);
@@ -1144,10 +1152,10 @@ pub fn apply_or_infer_cure(
                             who.clear_relapses();
                         }
                     }
-                } else if let Some(defence) = PILL_DEFENCES.get(pill_name) {
-                    if *defence == FType::Insomnia && who.is(FType::Hypersomnia) {
+                } else if let Some(defence) = cure_orders.pill_defence(pill_name) {
+                    if defence == FType::Insomnia && who.is(FType::Hypersomnia) {
                     } else {
-                        who.set_flag(*defence, true);
+                        who.set_flag(defence, true);
                     }
                 } else {
                     return Err(format!("Could not find pill {}", pill_name));
@@ -1166,9 +1174,7 @@ pub fn apply_or_infer_cure(
                 } else if salve_name == "restoration" {
                     let limb = get_limb_damage(salve_loc)?;
                     who.limb_damage.start_restore(limb, first_person);
-                } else if let Some(order) =
-                    SALVE_CURE_ORDERS.get(&(salve_name.to_string(), salve_loc.to_string()))
-                {
+                } else if let Some(order) = cure_orders.salve_order(salve_name, salve_loc) {
                     if let Ok(limb) = get_limb_damage(salve_loc) {
                         if !who.limb_damage.broken(limb) {
                             if !first_person {
@@ -1192,7 +1198,7 @@ pub fn apply_or_infer_cure(
             }
             SimpleCure::Smoke(herb_name) => {
                 who.observe_flag(FType::Asthma, false);
-                if let Some(order) = SMOKE_CURE_ORDERS.get(herb_name) {
+                if let Some(order) = cure_orders.smoke_order(herb_name) {
                     if first_person {
                         for smoke_aff in order.iter() {
                             who.observe_flag(*smoke_aff, false);
@@ -0,0 +1,198 @@
+use crate::timeline::*;
+use crate::types::*;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use topper_core::timeline::DigestEvent;
+
+// Enough to scroll back through a fight, without cloning a whole session with the timeline.
+pub const DIGEST_LENGTH: usize = 200;
+
+// Everyone an observation acts on, caster first.
+pub fn involved_agents(observation: &AetObservation) -> Vec<String> {
+    let mut involved = Vec::new();
+    match observation {
+        AetObservation::CombatAction(combat_action) => {
+            involved.push(combat_action.caster.clone());
+            if !combat_action.target.is_empty() && combat_action.target != combat_action.caster {
+                involved.push(combat_action.target.clone());
+            }
+        }
+        AetObservation::SimpleCureAction(simple_cure) => {
+            involved.push(simple_cure.caster.clone());
+        }
+        _ => {}
+    }
+    involved
+}
+
+// An agent as an observation found them, to tell afterwards what it changed.
+#[derive(Debug, Clone)]
+pub struct DigestWatch {
+    who: String,
+    affs: Vec<FType>,
+    broken: Vec<LType>,
+}
+
+fn broken_limbs(state: &AgentState) -> Vec<LType> {
+    (0..(LType::SIZE as u8))
+        .filter_map(|idx| LType::try_from(idx).ok())
+        .filter(|limb| state.limb_damage.broken(*limb))
+        .collect()
+}
+
+pub fn watch_observation(
+    state: &AetTimelineState,
+    observation: &AetObservation,
+) -> Vec<DigestWatch> {
+    involved_agents(observation)
+        .into_iter()
+        .map(|who| {
+            let agent = state.borrow_agent(&who);
+            DigestWatch {
+                affs: agent.flags.aff_iter().collect(),
+                broken: broken_limbs(&agent),
+                who,
+            }
+        })
+        .collect()
+}
+
+fn describe_cure(cure: &SimpleCure) -> String {
+    match cure {
+        SimpleCure::Pill(pill) => format!("{} pill", pill),
+        SimpleCure::Salve(salve, location) => format!("{} salve on {}", salve, location),
+        SimpleCure::Smoke(herb) => format!("{} smoke", herb),
+    }
+}
+
+// What an observation adds to the feed. Venoms are taken from the observations that follow the
+// attack, and cures from the afflictions the caster lost.
+pub fn digest_observation(
+    state: &AetTimelineState,
+    observation: &AetObservation,
+    after: &Vec<AetObservation>,
+    watched: Vec<DigestWatch>,
+) -> Vec<String> {
+    let mut digest = Vec::new();
+    match observation {
+        AetObservation::CombatAction(combat_action) => {
+            let venoms: Vec<String> = after
+                .iter()
+                .skip(1)
+                .take_while(|observation| match observation {
+                    AetObservation::CombatAction(_) => false,
+                    _ => true,
+                })
+                .filter_map(|observation| match observation {
+                    AetObservation::Devenoms(venom) => Some(venom.clone()),
+                    _ => None,
+                })
+                .collect();
+            let mut event = if combat_action.target.is_empty()
+                || combat_action.target == combat_action.caster
+            {
+                format!("{} used {}", combat_action.caster, combat_action.skill)
+            } else {
+                format!(
+                    "{} hit {} with {}",
+                    combat_action.caster, combat_action.target, combat_action.skill
+                )
+            };
+            if !venoms.is_empty() {
+                event = format!("{} (venoms {})", event, venoms.join(", "));
+            }
+            digest.push(event);
+        }
+        AetObservation::SimpleCureAction(simple_cure) => {
+            let now = state.borrow_agent(&simple_cure.caster);
+            let cured: Vec<String> = watched
+                .iter()
+                .find(|watch| watch.who == simple_cure.caster)
+                .map(|watch| {
+                    watch
+                        .affs
+                        .iter()
+                        .filter(|aff| !now.is(**aff))
+                        .map(|aff| aff.to_name())
+                        .collect()
+                })
+                .unwrap_or_default();
+            if cured.is_empty() {
+                digest.push(format!(
+                    "{} used {}",
+                    simple_cure.caster,
+                    describe_cure(&simple_cure.cure_type)
+                ));
+            } else {
+                digest.push(format!(
+                    "{} cured {} via {}",
+                    simple_cure.caster,
+                    cured.join(", "),
+                    describe_cure(&simple_cure.cure_type)
+                ));
+            }
+        }
+        _ => {}
+    }
+    for watch in watched.iter() {
+        let now = state.borrow_agent(&watch.who);
+        for limb in broken_limbs(&now) {
+            if !watch.broken.contains(&limb) {
+                digest.push(format!("{}'s {} broken", watch.who, limb.to_string()));
+            }
+        }
+    }
+    digest
+}
+
+pub fn count_branches(state: &AetTimelineState) -> HashMap<String, usize> {
+    state
+        .agent_states
+        .iter()
+        .map(|(who, branches)| (who.clone(), branches.len()))
+        .collect()
+}
+
+// Agents who were left with fewer branches than they had, each with its event.
+pub fn digest_collapses(
+    before: &HashMap<String, usize>,
+    state: &AetTimelineState,
+) -> Vec<(String, String)> {
+    let mut collapsed: Vec<(&String, &usize)> = before.iter().collect();
+    collapsed.sort();
+    collapsed
+        .into_iter()
+        .filter_map(|(who, count)| {
+            let now = state
+                .agent_states
+                .get(who)
+                .map(|branches| branches.len())
+                .unwrap_or_default();
+            if now < *count {
+                Some((
+                    who.clone(),
+                    format!("{}'s branches collapsed ({} -> {})", who, count, now),
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// The newest events first, only those involving the agent if one is given. Every feed is
+// rendered from here, so the Mudlet window and the explainer agree.
+pub fn get_digest_feed(timeline: &AetTimeline, who: Option<&String>, limit: usize) -> Vec<String> {
+    timeline
+        .digest
+        .iter()
+        .rev()
+        .filter(|event| who.map_or(true, |who| event.involves(who)))
+        .take(limit)
+        .map(|event| event.event.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "./tests/digest_tests.rs"]
+mod digest_tests;
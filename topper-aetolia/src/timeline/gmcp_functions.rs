@@ -1,13 +1,28 @@
+use regex::Regex;
+use topper_core::observations::strip_ansi;
 use topper_core::timeline::*;
 
 use crate::{
+    classes::get_vitals_handler,
     db::*,
-    non_agent::{AetTimelineRoomExt, Direction, Room},
+    non_agent::{
+        AetTimelineCallsExt, AetTimelineDenizenExt, AetTimelinePlayersExt, AetTimelineRoomExt,
+        Direction, EvalStatus, Room,
+    },
     types::*,
 };
 
 use super::*;
 
+#[cfg(test)]
+#[path = "./tests/gmcp_tests.rs"]
+mod gmcp_tests;
+
+lazy_static! {
+    // Target calls, as the group module sends them.
+    static ref TARGET_CALL: Regex = Regex::new(r#""X (\w+)\b"#).unwrap();
+}
+
 pub fn apply_gmcp<DB: AetDatabaseModule>(
     timeline: &mut AetTimelineState,
     gmcp: &GMCP,
@@ -20,15 +35,140 @@ pub fn apply_gmcp<DB: AetDatabaseModule>(
         "gmcp.Room.Players" => {
             handle_room_players(&gmcp.1, timeline);
         }
-        "gmcp.Char.Vitals" => handle_char_vitals(&gmcp.1, timeline),
+        "gmcp.Room.AddPlayer" => handle_room_player_change(&gmcp.1, timeline, true),
+        "gmcp.Room.RemovePlayer" => handle_room_player_change(&gmcp.1, timeline, false),
+        "gmcp.Char.Vitals" => handle_char_vitals(&gmcp.1, timeline, db),
+        "gmcp.IRE.Target.Set" => handle_target_set(&gmcp.1, timeline),
+        "gmcp.IRE.Target.Info" => handle_target_info(&gmcp.1, timeline),
+        "gmcp.Char.Items.List" => handle_room_items(&gmcp.1, timeline),
+        "gmcp.Char.Items.Add" => handle_room_item_change(&gmcp.1, timeline, true),
+        "gmcp.Char.Items.Remove" => handle_room_item_change(&gmcp.1, timeline, false),
+        "gmcp.Comm.Channel.Text" => handle_channel_text(&gmcp.1, timeline),
+        "gmcp.Char.Afflictions.List" => handle_own_flag_list(&gmcp.1, timeline, true),
+        "gmcp.Char.Defences.List" => handle_own_flag_list(&gmcp.1, timeline, false),
+        "gmcp.Char.Afflictions.Add" | "gmcp.Char.Defences.Add" => {
+            handle_own_flag_change(&gmcp.1, timeline, true)
+        }
+        "gmcp.Char.Afflictions.Remove" | "gmcp.Char.Defences.Remove" => {
+            handle_own_flag_change(&gmcp.1, timeline, false)
+        }
         _ => {}
     }
     Ok(())
 }
 
-fn handle_char_vitals(
+// Our own afflictions and defences, which should agree with the lines they came with.
+pub fn is_own_flag_gmcp(gmcp: &GMCP) -> bool {
+    gmcp.0.starts_with("gmcp.Char.Afflictions.") || gmcp.0.starts_with("gmcp.Char.Defences.")
+}
+
+// Levelled afflictions are sent as "name (level)".
+fn parse_gmcp_flag(name: &str) -> Option<FType> {
+    let name = name.split(" (").next().unwrap_or_default().trim();
+    FType::from_name(&name.to_string())
+}
+
+// Lists and additions are objects with a name, removals are just names.
+fn get_gmcp_flags(gmcp: &serde_json::Value) -> Vec<FType> {
+    let entries = match gmcp.as_array() {
+        Some(entries) => entries.iter().collect(),
+        None => vec![gmcp],
+    };
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            entry
+                .get("name")
+                .and_then(|name| name.as_str())
+                .or_else(|| entry.as_str())
+        })
+        .filter_map(parse_gmcp_flag)
+        .collect()
+}
+
+// Flags we track ourselves, which GMCP never sends.
+const UNREPORTED_FLAGS: [FType; 22] = [
+    FType::AssumedRebounding,
+    FType::Fallen,
+    FType::Disrupted,
+    FType::Backstabbed,
+    FType::Void,
+    FType::Weakvoid,
+    FType::Itchy,
+    FType::WritheImpaled,
+    FType::WritheArmpitlock,
+    FType::WritheNecklock,
+    FType::WritheThighlock,
+    FType::WritheTransfix,
+    FType::WritheBind,
+    FType::WritheGunk,
+    FType::WritheRopes,
+    FType::WritheVines,
+    FType::WritheWeb,
+    FType::WritheDartpinned,
+    FType::WritheHoist,
+    FType::WritheGrappled,
+    FType::WritheLure,
+    FType::WritheStasis,
+];
+
+// Whether a missing flag in a full GMCP list means we don't have it.
+fn is_reported_flag(flag: FType) -> bool {
+    !UNREPORTED_FLAGS.contains(&flag) && FType::from_name(&flag.to_name()) == Some(flag)
+}
+
+// The full list is ground truth for what it reports, so any branch that disagrees is struck.
+fn handle_own_flag_list(
+    gmcp: &serde_json::Value,
+    timeline: &mut TimelineState<AgentState, crate::non_agent::AetNonAgent>,
+    afflictions: bool,
+) {
+    let flags = get_gmcp_flags(gmcp);
+    for_agent(
+        timeline,
+        &timeline.me.clone(),
+        &move |me: &mut AgentState| {
+            let current: Vec<FType> = if afflictions {
+                me.flags.aff_iter().collect()
+            } else {
+                me.flags.def_iter().collect()
+            };
+            for flag in current {
+                if is_reported_flag(flag) && !flags.contains(&flag) {
+                    me.observe_flag(flag, false);
+                }
+            }
+            for flag in flags.iter() {
+                me.observe_flag(*flag, true);
+            }
+            if afflictions {
+                me.hidden_state.clear();
+            }
+        },
+    );
+}
+
+fn handle_own_flag_change(
+    gmcp: &serde_json::Value,
+    timeline: &mut TimelineState<AgentState, crate::non_agent::AetNonAgent>,
+    value: bool,
+) {
+    let flags = get_gmcp_flags(gmcp);
+    for_agent(
+        timeline,
+        &timeline.me.clone(),
+        &move |me: &mut AgentState| {
+            for flag in flags.iter() {
+                me.observe_flag(*flag, value);
+            }
+        },
+    );
+}
+
+fn handle_char_vitals<DB: AetDatabaseModule>(
     gmcp: &serde_json::Value,
     timeline: &mut TimelineState<crate::types::AgentState, crate::non_agent::AetNonAgent>,
+    db: Option<&DB>,
 ) {
     if let Some(elevation) = gmcp
         .get("elevation")
@@ -72,22 +212,16 @@ fn handle_char_vitals(
             },
         );
     }
-    if let Some(dithering) = gmcp
-        .get("dithering")
-        .and_then(|dithering| dithering.as_str())
-        .and_then(|dithering| dithering.parse::<usize>().ok())
-    {
-        for_agent(
-            timeline,
-            &timeline.me.clone(),
-            &move |me: &mut AgentState| {
-                if let ClassState::Bard(class_state) = &mut me.class_state {
-                    class_state.dithering = dithering;
-                }
-            },
-        );
+    let me = timeline.me.clone();
+    let class = db
+        .and_then(|db| db.get_class(&me))
+        .or_else(|| timeline.borrow_me().class_state.get_normalized_class());
+    if let Some(handler) = class.and_then(|class| get_vitals_handler(&class)) {
+        let vitals = gmcp.clone();
+        for_agent(timeline, &me, &move |me: &mut AgentState| {
+            handler(&vitals, me);
+        });
     }
-    handle_predator_values(gmcp, timeline);
     if let (Some(hp), Some(mp), Some(max_hp), Some(max_mp)) = (
         gmcp.get("hp")
             .and_then(|hp| hp.as_str())
@@ -119,79 +253,17 @@ fn handle_char_vitals(
     }
 }
 
-fn handle_predator_values(
-    gmcp: &serde_json::Value,
-    timeline: &mut TimelineState<AgentState, crate::non_agent::AetNonAgent>,
-) {
-    if let Some(stance) = gmcp
-        .get("knife_stance")
-        .and_then(|stance| stance.as_str())
-        .map(KnifeStance::from_name)
-    {
-        for_agent(
-            timeline,
-            &timeline.me.clone(),
-            &move |me: &mut AgentState| {
-                me.assume_predator(&move |class_state| class_state.stance = stance);
-            },
-        )
-    }
-    if let Some(apex) = gmcp
-        .get("apex")
-        .and_then(|apex| apex.as_str())
-        .and_then(|apex| apex.parse::<u32>().ok())
-    {
-        for_agent(
-            timeline,
-            &timeline.me.clone(),
-            &move |me: &mut AgentState| {
-                if let ClassState::Predator(class_state) = &mut me.class_state {
-                    class_state.apex = apex;
-                }
-            },
-        );
-    }
-}
-
-fn handle_monk_values(
-    gmcp: &serde_json::Value,
-    timeline: &mut TimelineState<AgentState, crate::non_agent::AetNonAgent>,
-) {
-    if let Some(stance) = gmcp
-        .get("stance")
-        .and_then(|stance| stance.as_str())
-        .map(MonkStance::from_name)
-    {
-        for_agent(
-            timeline,
-            &timeline.me.clone(),
-            &move |me: &mut AgentState| {
-                me.assume_monk(&move |class_state| class_state.stance = stance);
-            },
-        )
-    }
-    if let Some(apex) = gmcp
-        .get("kai")
-        .and_then(|apex| apex.as_str())
-        .and_then(|apex| apex.parse::<i32>().ok())
-    {
-        for_agent(
-            timeline,
-            &timeline.me.clone(),
-            &move |me: &mut AgentState| {
-                if let ClassState::Monk(class_state) = &mut me.class_state {
-                    class_state.kai = apex;
-                }
-            },
-        );
-    }
-}
-
 fn handle_room_info(
     gmcp: &serde_json::Value,
     timeline: &mut TimelineState<crate::types::AgentState, crate::non_agent::AetNonAgent>,
 ) {
     if let Some(room_id) = gmcp.get("num").and_then(|num| num.as_i64()) {
+        let old_room_id = timeline.borrow_me().room_id;
+        if old_room_id != room_id {
+            // Room.Players follows with whoever is here.
+            timeline.clear_room_players(old_room_id);
+            timeline.clear_room_players(room_id);
+        }
         timeline.for_agent(&timeline.me.clone(), &|me| {
             me.room_id = room_id;
         });
@@ -221,9 +293,179 @@ fn handle_room_players(
     timeline: &mut TimelineState<crate::types::AgentState, crate::non_agent::AetNonAgent>,
 ) {
     if let Some(players) = player_list.as_array() {
-        if let Some(player) = player_list.get("name").and_then(|player| player.as_str()) {
-            let my_room = timeline.borrow_me().room_id;
-            timeline.set_player_room(my_room, player);
+        let my_room = timeline.borrow_me().room_id;
+        let players: Vec<String> = players.iter().filter_map(get_player_name).collect();
+        let gone: Vec<String> = timeline
+            .get_my_room()
+            .map(|room| {
+                room.players
+                    .iter()
+                    .filter(|player| !players.contains(player))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        for player in gone {
+            timeline.remove_player_from_room(my_room, &player);
+        }
+        for player in players {
+            if !player.eq(&timeline.me) {
+                timeline.set_player_room(my_room, &player);
+            }
+        }
+    }
+}
+
+// Players are sent as objects with a name, except in removals, which may be just the name.
+fn get_player_name(player: &serde_json::Value) -> Option<String> {
+    player
+        .get("name")
+        .and_then(|name| name.as_str())
+        .or_else(|| player.as_str())
+        .map(|name| name.to_string())
+}
+
+fn handle_room_player_change(
+    player: &serde_json::Value,
+    timeline: &mut TimelineState<crate::types::AgentState, crate::non_agent::AetNonAgent>,
+    added: bool,
+) {
+    if let Some(player) = get_player_name(player) {
+        if player.eq(&timeline.me) {
+            return;
+        }
+        let my_room = timeline.borrow_me().room_id;
+        if added {
+            timeline.set_player_room(my_room, &player);
+        } else {
+            timeline.remove_player_from_room(my_room, &player);
+        }
+    }
+}
+
+fn handle_target_set(
+    gmcp: &serde_json::Value,
+    timeline: &mut TimelineState<AgentState, crate::non_agent::AetNonAgent>,
+) {
+    if let Some(target) = gmcp.as_str() {
+        timeline.set_my_target(target);
+    }
+}
+
+// Denizen targets are tracked by their status, players by their health.
+fn handle_target_info(
+    gmcp: &serde_json::Value,
+    timeline: &mut TimelineState<AgentState, crate::non_agent::AetNonAgent>,
+) {
+    let percent = gmcp
+        .get("hpperc")
+        .and_then(|percent| percent.as_str())
+        .and_then(|percent| percent.trim_end_matches('%').parse::<f32>().ok());
+    let id = gmcp.get("id").and_then(|id| id.as_str());
+    if let (Some(percent), Some(id)) = (percent, id) {
+        if let Ok(denizen_id) = id.parse::<i64>() {
+            let status = EvalStatus::from_health_percent(percent);
+            timeline.for_denizen(denizen_id, &move |denizen| {
+                denizen.status = status;
+            });
+        } else if let Some(target) = timeline.get_my_target() {
+            timeline.for_agent(&target, &move |you| {
+                let health = you.get_max_stat(SType::Health) as f32 * percent / 100.0;
+                you.set_stat(SType::Health, health as CType);
+            });
+        }
+    }
+}
+
+// Room items marked as monsters, and not dead, are denizens.
+fn get_item_denizen(item: &serde_json::Value) -> Option<(i64, String)> {
+    let attributes = item
+        .get("attrib")
+        .and_then(|attrib| attrib.as_str())
+        .unwrap_or_default();
+    if !attributes.contains('m') || attributes.contains('d') {
+        return None;
+    }
+    let id = item
+        .get("id")
+        .and_then(|id| id.as_str())
+        .and_then(|id| id.parse::<i64>().ok())?;
+    let name = item.get("name").and_then(|name| name.as_str())?;
+    Some((id, name.to_string()))
+}
+
+fn is_room_location(gmcp: &serde_json::Value) -> bool {
+    gmcp.get("location").and_then(|location| location.as_str()) == Some("room")
+}
+
+// The full list replaces whatever denizens we thought were in the room.
+fn handle_room_items(
+    gmcp: &serde_json::Value,
+    timeline: &mut TimelineState<AgentState, crate::non_agent::AetNonAgent>,
+) {
+    if !is_room_location(gmcp) {
+        return;
+    }
+    if let Some(items) = gmcp.get("items").and_then(|items| items.as_array()) {
+        let room_id = timeline.borrow_me().room_id;
+        let denizens: Vec<(i64, String)> = items.iter().filter_map(get_item_denizen).collect();
+        let gone: Vec<i64> = timeline
+            .get_my_room()
+            .map(|room| {
+                room.denizens
+                    .iter()
+                    .filter(|denizen_id| !denizens.iter().any(|(id, _name)| id == *denizen_id))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        for denizen_id in gone {
+            timeline.observe_denizen_in_room(denizen_id, 0);
+        }
+        for (denizen_id, name) in denizens {
+            timeline.add_denizen(denizen_id, denizen_id.to_string(), room_id, name, None);
+        }
+    }
+}
+
+fn handle_room_item_change(
+    gmcp: &serde_json::Value,
+    timeline: &mut TimelineState<AgentState, crate::non_agent::AetNonAgent>,
+    added: bool,
+) {
+    if !is_room_location(gmcp) {
+        return;
+    }
+    if let Some(item) = gmcp.get("item") {
+        if added {
+            if let Some((denizen_id, name)) = get_item_denizen(item) {
+                let room_id = timeline.borrow_me().room_id;
+                timeline.add_denizen(denizen_id, denizen_id.to_string(), room_id, name, None);
+            }
+        } else if let Some(denizen_id) = item
+            .get("id")
+            .and_then(|id| id.as_str())
+            .or_else(|| item.as_str())
+            .and_then(|id| id.parse::<i64>().ok())
+        {
+            // Leaving the room isn't dying, so keep what we know and put it in the null room.
+            timeline.observe_denizen_in_room(denizen_id, 0);
+        }
+    }
+}
+
+fn handle_channel_text(
+    gmcp: &serde_json::Value,
+    timeline: &mut TimelineState<AgentState, crate::non_agent::AetNonAgent>,
+) {
+    if let (Some(channel), Some(talker), Some(text)) = (
+        gmcp.get("channel").and_then(|channel| channel.as_str()),
+        gmcp.get("talker").and_then(|talker| talker.as_str()),
+        gmcp.get("text").and_then(|text| text.as_str()),
+    ) {
+        if let Some(captures) = TARGET_CALL.captures(&strip_ansi(&text.to_string())) {
+            let target = captures.get(1).unwrap().as_str().to_string();
+            timeline.add_call(talker, &target, channel);
         }
     }
 }
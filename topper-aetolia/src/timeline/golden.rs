@@ -0,0 +1,182 @@
+use super::*;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use topper_core::observations::ObservationParser;
+use topper_core::timeline::db::DummyDatabaseModule;
+
+// What a recorded fight should have left an agent with. Only limbs with damage and balances
+// still recovering are listed, so an expectation reads like the state it describes.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldenAgent {
+    #[serde(default)]
+    pub affs: Vec<FType>,
+    #[serde(default)]
+    pub limbs: BTreeMap<String, CType>,
+    #[serde(default)]
+    pub balances: BTreeMap<String, f32>,
+}
+
+// Balances are compared to the hundredth of a second, as the game reports them.
+const BALANCE_TOLERANCE: f32 = 0.01;
+
+impl GoldenAgent {
+    pub fn from_state(state: &AgentState) -> Self {
+        let mut golden = GoldenAgent::default();
+        golden.affs = state.flags.aff_iter().collect();
+        for idx in 0..(LType::SIZE as usize) {
+            if let Ok(limb) = LType::try_from(idx as u8) {
+                let damage = state.limb_damage.get_damage(limb);
+                if damage > 0 {
+                    golden.limbs.insert(format!("{:?}", limb), damage);
+                }
+            }
+        }
+        for idx in 0..(BType::SIZE as usize) {
+            if let Ok(balance) = BType::try_from(idx) {
+                if !state.balanced(balance) {
+                    let left = (state.get_balance(balance) * 100.0).round() / 100.0;
+                    golden.balances.insert(format!("{:?}", balance), left);
+                }
+            }
+        }
+        golden
+    }
+
+    pub fn diff(&self, actual: &GoldenAgent) -> Vec<String> {
+        let mut diffs = Vec::new();
+        let missing: Vec<&FType> = self
+            .affs
+            .iter()
+            .filter(|aff| !actual.affs.contains(aff))
+            .collect();
+        if !missing.is_empty() {
+            diffs.push(format!("missing affs {:?}", missing));
+        }
+        let unexpected: Vec<&FType> = actual
+            .affs
+            .iter()
+            .filter(|aff| !self.affs.contains(aff))
+            .collect();
+        if !unexpected.is_empty() {
+            diffs.push(format!("unexpected affs {:?}", unexpected));
+        }
+        for limb in union_keys(&self.limbs, &actual.limbs) {
+            let expected = self.limbs.get(limb).cloned().unwrap_or_default();
+            let found = actual.limbs.get(limb).cloned().unwrap_or_default();
+            if expected != found {
+                diffs.push(format!("{} expected {}, found {}", limb, expected, found));
+            }
+        }
+        for balance in union_keys(&self.balances, &actual.balances) {
+            let expected = self.balances.get(balance).cloned().unwrap_or_default();
+            let found = actual.balances.get(balance).cloned().unwrap_or_default();
+            if (expected - found).abs() > BALANCE_TOLERANCE {
+                diffs.push(format!(
+                    "{} expected {:.2}s, found {:.2}s",
+                    balance, expected, found
+                ));
+            }
+        }
+        diffs
+    }
+}
+
+fn union_keys<'a, V>(a: &'a BTreeMap<String, V>, b: &'a BTreeMap<String, V>) -> Vec<&'a String> {
+    let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+// The named agents as they stood once every slice up to the time given was pushed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldenCheckpoint {
+    pub time: CType,
+    pub agents: BTreeMap<String, GoldenAgent>,
+}
+
+// Time slices from a recorded session, one message per line. Requests are skipped, as they
+// only steer the topper and never touch the timeline.
+pub fn read_recorded_slices(session: &str) -> Result<Vec<AetTimeSlice>, String> {
+    let mut slices = Vec::new();
+    for (line_num, line) in session.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message: serde_json::Value =
+            serde_json::from_str(line).map_err(|err| format!("Line {}: {}", line_num + 1, err))?;
+        if let Some(slice) = message.get("TimeSlice") {
+            slices.push(
+                serde_json::from_value(slice.clone())
+                    .map_err(|err| format!("Line {}: {}", line_num + 1, err))?,
+            );
+        }
+    }
+    Ok(slices)
+}
+
+// Replays the slices as the topper would, observing their lines first, and checkpoints the
+// same agents at the same times as the expectations, with balances recovered up to then.
+// Agents in several branches are taken from their first.
+pub fn replay_golden(
+    slices: Vec<AetTimeSlice>,
+    observer: &ObservationParser<AetObservation>,
+    expected: &Vec<GoldenCheckpoint>,
+) -> Result<Vec<GoldenCheckpoint>, String> {
+    let mut timeline = AetTimeline::new();
+    let mut checkpoints = expected.iter().peekable();
+    let mut actual = Vec::new();
+    let checkpoint = |timeline: &AetTimeline, expected: &GoldenCheckpoint| GoldenCheckpoint {
+        time: expected.time,
+        agents: expected
+            .agents
+            .keys()
+            .map(|name| {
+                (
+                    name.clone(),
+                    GoldenAgent::from_state(&timeline.state.borrow_agent(name)),
+                )
+            })
+            .collect(),
+    };
+    for mut slice in slices.into_iter() {
+        while let Some(expected) = checkpoints.peek() {
+            if expected.time >= slice.time {
+                break;
+            }
+            timeline.update_time(expected.time)?;
+            actual.push(checkpoint(&timeline, expected));
+            checkpoints.next();
+        }
+        observer.observe_into(&mut slice, None);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>)?;
+    }
+    for expected in checkpoints {
+        timeline.update_time(expected.time)?;
+        actual.push(checkpoint(&timeline, expected));
+    }
+    Ok(actual)
+}
+
+// One line per disagreement, naming the time and agent, or nothing if the replay matched.
+pub fn diff_golden(
+    expected: &Vec<GoldenCheckpoint>,
+    actual: &Vec<GoldenCheckpoint>,
+) -> Vec<String> {
+    let mut diffs = Vec::new();
+    for (expected, actual) in expected.iter().zip(actual.iter()) {
+        for (name, expected_agent) in expected.agents.iter() {
+            let actual_agent = actual.agents.get(name).cloned().unwrap_or_default();
+            for diff in expected_agent.diff(&actual_agent) {
+                diffs.push(format!("@{} {}: {}", expected.time, name, diff));
+            }
+        }
+    }
+    diffs
+}
+
+#[cfg(test)]
+#[path = "./tests/golden_tests.rs"]
+mod golden_tests;
@@ -0,0 +1,241 @@
+use crate::db::AetDatabaseModule;
+use crate::non_agent::{AetNonAgent, AetTimelineBalanceUsesExt};
+use crate::timeline::*;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use topper_core::timeline::BALANCE_SCALE;
+
+// Only the most recent samples are kept, so a changed build is picked up quickly.
+const MAX_SAMPLES: usize = 10;
+// Gaps longer than this are a lull in the fight, not a balance.
+const MAX_SPACING: f32 = 10.0;
+// Samples this close to the fastest are taken to agree with it.
+const AGREEMENT: f32 = 0.3;
+const CONFIDENT_SAMPLES: usize = 3;
+// Player hint holding how long our own last attack kept us off balance, in seconds.
+pub const ATTACK_BALANCE_HINT: &str = "ATTACK_BALANCE";
+// How often learned balances are written back to the database, in centiseconds.
+const FLUSH_INTERVAL: CType = 3000;
+const LEARNED_BALANCES_ID: &str = "learned_balances";
+
+// Observed gaps between a skill and the next use of the same balance. People act on balance
+// when they can, so the fastest gap is the best guess; the rest is lag and hesitation.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceEstimate {
+    pub samples: Vec<f32>,
+}
+
+impl BalanceEstimate {
+    pub fn add_sample(&mut self, seconds: f32) {
+        self.samples.push(seconds);
+        if self.samples.len() > MAX_SAMPLES {
+            self.samples.remove(0);
+        }
+    }
+
+    pub fn estimate(&self) -> Option<f32> {
+        self.samples
+            .iter()
+            .cloned()
+            .fold(None, |fastest: Option<f32>, sample| {
+                Some(fastest.map_or(sample, |fastest| fastest.min(sample)))
+            })
+    }
+
+    pub fn is_confident(&self) -> bool {
+        if let Some(fastest) = self.estimate() {
+            self.samples
+                .iter()
+                .filter(|sample| **sample - fastest <= AGREEMENT)
+                .count()
+                >= CONFIDENT_SAMPLES
+        } else {
+            false
+        }
+    }
+
+    pub fn confident_estimate(&self) -> Option<f32> {
+        if self.is_confident() {
+            self.estimate()
+        } else {
+            None
+        }
+    }
+}
+
+pub type SkillBalances = HashMap<BType, BalanceEstimate>;
+
+pub fn format_skill_balances_key(who: &str, skill: &str) -> String {
+    format!("{}_{}", who, skill)
+}
+
+fn get_raw_balances(who: &AgentState) -> Vec<CType> {
+    (0..(BType::SIZE as usize))
+        .map(|idx| {
+            BType::try_from(idx)
+                .map(|balance| who.get_raw_balance(balance))
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+// Balances learned this session, keyed by who and skill. Each is read from the database the
+// first time it's needed, and only written back when flushed.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct LearnedBalances {
+    pub skills: HashMap<(String, String), SkillBalances>,
+    pub dirty: HashSet<(String, String)>,
+    pub flushed_at: CType,
+}
+
+fn learned_balances(timeline: &mut AetTimelineState) -> &mut LearnedBalances {
+    match timeline
+        .non_agent_states
+        .entry(LEARNED_BALANCES_ID.to_string())
+        .or_insert_with(|| AetNonAgent::LearnedBalances(LearnedBalances::default()))
+    {
+        AetNonAgent::LearnedBalances(learned) => learned,
+        _ => panic!("Non-learned balances in learned balances spot!"),
+    }
+}
+
+fn get_skill_balances<'a>(
+    timeline: &'a mut AetTimelineState,
+    db: &impl AetDatabaseModule,
+    who: &str,
+    skill: &str,
+) -> &'a mut SkillBalances {
+    learned_balances(timeline)
+        .skills
+        .entry((who.to_string(), skill.to_string()))
+        .or_insert_with(|| db.get_skill_balances(who, skill).unwrap_or_default())
+}
+
+pub fn learn_sample(
+    timeline: &mut AetTimelineState,
+    db: &impl AetDatabaseModule,
+    who: &str,
+    skill: &str,
+    balance: BType,
+    gap: f32,
+) {
+    if gap <= 0.0 || gap > MAX_SPACING {
+        return;
+    }
+    get_skill_balances(timeline, db, who, skill)
+        .entry(balance)
+        .or_default()
+        .add_sample(gap);
+    learned_balances(timeline)
+        .dirty
+        .insert((who.to_string(), skill.to_string()));
+}
+
+// Writes back everything learned since the last flush.
+pub fn flush_learned_balances(timeline: &mut AetTimelineState, db: &impl AetDatabaseModule) {
+    let now = timeline.time;
+    let learned = learned_balances(timeline);
+    for (who, skill) in learned.dirty.drain() {
+        if let Some(balances) = learned.skills.get(&(who.clone(), skill.clone())) {
+            db.set_skill_balances(&who, &skill, balances.clone());
+        }
+    }
+    learned.flushed_at = now;
+}
+
+// Flushes once enough time has passed, so a long fight doesn't lose everything it learned.
+pub fn flush_learned_balances_periodically(
+    timeline: &mut AetTimelineState,
+    db: &impl AetDatabaseModule,
+) {
+    let now = timeline.time;
+    let learned = learned_balances(timeline);
+    if !learned.dirty.is_empty() && now - learned.flushed_at >= FLUSH_INTERVAL {
+        flush_learned_balances(timeline, db);
+    }
+}
+
+// Marks the balance as spent on the skill, learning from the gap since it was last spent.
+fn spend_balance(
+    timeline: &mut AetTimelineState,
+    db: &impl AetDatabaseModule,
+    who: &str,
+    skill: &str,
+    balance: BType,
+) {
+    if let Some(last_use) = timeline.record_balance_use(who, balance, skill) {
+        let gap = (timeline.time - last_use.time) as f32 / BALANCE_SCALE;
+        learn_sample(timeline, db, who, &last_use.skill, balance, gap);
+    }
+}
+
+// Runs a combat action through its handler, then learns from the balances it spent. For
+// anyone else, a confident estimate replaces the class handler's constant.
+pub fn handle_combat_action_balances(
+    combat_action: &CombatAction,
+    timeline: &mut AetTimelineState,
+    after: &Vec<AetObservation>,
+    db: &impl AetDatabaseModule,
+    handle: impl FnOnce(&mut AetTimelineState) -> Result<(), String>,
+) -> Result<(), String> {
+    let caster = combat_action.caster.clone();
+    let skill = combat_action.skill.clone();
+    if caster.eq(&timeline.me) {
+        handle(timeline)?;
+        // We only learn our own balances when they come back. The action itself leads after.
+        for observation in after.iter().skip(1) {
+            match observation {
+                AetObservation::Balance(balance, _duration) => {
+                    timeline.record_balance_use(&caster, BType::from_name(balance), &skill);
+                }
+                AetObservation::CombatAction(_) => break,
+                _ => {}
+            }
+        }
+        return Ok(());
+    }
+    let before = get_raw_balances(&timeline.borrow_agent(&caster));
+    handle(timeline)?;
+    let after = get_raw_balances(&timeline.borrow_agent(&caster));
+    let spent: Vec<BType> = (0..(BType::SIZE as usize))
+        .filter(|idx| after[*idx] > before[*idx])
+        .filter_map(|idx| BType::try_from(idx).ok())
+        .collect();
+    if spent.is_empty() {
+        return Ok(());
+    }
+    let learned = get_skill_balances(timeline, db, &caster, &skill).clone();
+    for balance in spent {
+        spend_balance(timeline, db, &caster, &skill, balance);
+        if let Some(duration) = learned
+            .get(&balance)
+            .and_then(BalanceEstimate::confident_estimate)
+        {
+            for_agent(timeline, &caster, &|me: &mut AgentState| {
+                me.set_balance(balance, duration);
+            });
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_balance_back(
+    timeline: &mut AetTimelineState,
+    db: &impl AetDatabaseModule,
+    balance: BType,
+) {
+    let me = timeline.me.clone();
+    if let Some(last_use) = timeline.take_balance_use(&me, balance) {
+        let gap = (timeline.time - last_use.time) as f32 / BALANCE_SCALE;
+        learn_sample(timeline, db, &me, &last_use.skill, balance, gap);
+        if balance == BType::Balance && gap > 0.0 && gap <= MAX_SPACING {
+            timeline.add_player_hint(&me, ATTACK_BALANCE_HINT, gap.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/learned_balances_tests.rs"]
+mod learned_balances_tests;
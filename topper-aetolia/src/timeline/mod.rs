@@ -1,9 +1,17 @@
 pub mod apply_functions;
+pub mod digest;
 pub mod gmcp_functions;
+pub mod golden;
+pub mod learned_balances;
 pub mod observations;
+pub mod plausibility;
 pub mod types;
 pub use apply_functions::*;
+pub use digest::*;
 pub use gmcp_functions::*;
+pub use golden::*;
+pub use learned_balances::*;
 pub use observations::*;
+pub use plausibility::*;
 pub use topper_core::timeline::{BaseTimeline, TestableTimeline};
 pub use types::*;
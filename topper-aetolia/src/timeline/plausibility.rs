@@ -0,0 +1,223 @@
+use crate::timeline::*;
+use crate::types::*;
+use log::{debug, warn};
+use std::convert::TryFrom;
+
+// Our timers on other people are estimates, so they must be this far from done to count.
+const BALANCE_SLACK: CType = 50;
+// Past this many branches, an implausible action is taken at its word rather than doubled again.
+const MAX_QUARANTINE_BRANCHES: usize = 8;
+// Melee attacks, which can only land from the same room. Anything else may be ranged, and room
+// ids lag behind GMCP besides, so only these are doubted for coming from elsewhere.
+const SAME_ROOM_SKILLS: [&str; 31] = [
+    "Slash",
+    "Stab",
+    "Slice",
+    "Thrust",
+    "Ambush",
+    "Flourish",
+    "Pierce",
+    "Sever",
+    "Dualraze",
+    "Reave",
+    "Twirl",
+    "Crosscut",
+    "Trip",
+    "Slam",
+    "Gouge",
+    "Heartbreaker",
+    "Slit",
+    "Doublestab",
+    "Bite",
+    "Garrote",
+    "Vorpal",
+    "Heelrush",
+    "Direblow",
+    "Risekick",
+    "Pummel",
+    "Wanekick",
+    "Clawtwist",
+    "Sunkick",
+    "Palmforce",
+    "Twinpress",
+    "Jawcrack",
+];
+
+// Something an observation claims that what we track says could not have happened. These
+// catch the spoofs and illusions the triggers do not flag themselves.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Implausibility {
+    // Acted with neither balance nor equilibrium on any branch.
+    OffBalance(String),
+    // Used a cure that every branch has blocked.
+    CureBlocked(String, FType),
+    // Acted on us from a room other than ours.
+    NotInRoom(String),
+}
+
+impl Implausibility {
+    // The cure handlers take a cure as proof its blocker was gone, so the branch where it never
+    // happened starts a strike behind. Balances and rooms are only estimates, with no such proof,
+    // so both readings start even and later observations strike whichever was wrong.
+    pub fn strikes_unapplied(&self) -> bool {
+        match self {
+            Implausibility::CureBlocked(_, _) => true,
+            _ => false,
+        }
+    }
+}
+
+fn all_branches(
+    state: &AetTimelineState,
+    who: &String,
+    predicate: impl Fn(&AgentState) -> bool,
+) -> bool {
+    match state.agent_states.get(who) {
+        Some(branches) if !branches.is_empty() => branches.iter().all(predicate),
+        _ => false,
+    }
+}
+
+fn is_off(who: &AgentState, balance: BType) -> bool {
+    who.get_raw_balance(balance) > BALANCE_SLACK
+}
+
+// Our own actions are never second-guessed.
+pub fn check_plausibility(
+    state: &AetTimelineState,
+    observation: &AetObservation,
+) -> Option<Implausibility> {
+    match observation {
+        AetObservation::CombatAction(combat_action) if !combat_action.caster.eq(&state.me) => {
+            let caster = &combat_action.caster;
+            let my_room = state.borrow_me().room_id;
+            let caster_room = state.borrow_agent(caster).room_id;
+            if all_branches(state, caster, |branch| {
+                is_off(branch, BType::Balance) && is_off(branch, BType::Equil)
+            }) {
+                Some(Implausibility::OffBalance(caster.clone()))
+            } else if combat_action.target.eq(&state.me)
+                && SAME_ROOM_SKILLS.contains(&combat_action.skill.as_str())
+                && my_room != 0
+                && caster_room != 0
+                && caster_room != my_room
+            {
+                Some(Implausibility::NotInRoom(caster.clone()))
+            } else {
+                None
+            }
+        }
+        AetObservation::SimpleCureAction(simple_cure) if !simple_cure.caster.eq(&state.me) => {
+            let blocker = match simple_cure.cure_type {
+                SimpleCure::Pill(_) => FType::Anorexia,
+                SimpleCure::Salve(_, _) => FType::Slickness,
+                SimpleCure::Smoke(_) => FType::Asthma,
+            };
+            if all_branches(state, &simple_cure.caster, |branch| branch.is(blocker)) {
+                Some(Implausibility::CureBlocked(
+                    simple_cure.caster.clone(),
+                    blocker,
+                ))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+// Everyone the observation touches, as they were, to be kept alongside the branches where
+// it held. Other effects, such as hints, are applied outright.
+pub fn quarantine_snapshot(
+    state: &AetTimelineState,
+    observation: &AetObservation,
+) -> Vec<(String, Vec<AgentState>)> {
+    involved_agents(observation)
+        .into_iter()
+        .map(|who| {
+            let branches = state
+                .agent_states
+                .get(&who)
+                .cloned()
+                .unwrap_or_else(|| vec![AgentState::default()]);
+            (who, branches)
+        })
+        .collect()
+}
+
+// The balances the observation changed for the caster.
+fn spent_balances(applied: &AgentState, unapplied: &AgentState) -> Vec<BType> {
+    (0..(BType::SIZE as usize))
+        .filter_map(|idx| BType::try_from(idx).ok())
+        .filter(|balance| applied.get_raw_balance(*balance) != unapplied.get_raw_balance(*balance))
+        .collect()
+}
+
+// Acting off balance only counts if the action spent a balance, and every one it spent was off.
+fn is_confirmed(
+    state: &AetTimelineState,
+    implausibility: &Implausibility,
+    unapplied: &Vec<(String, Vec<AgentState>)>,
+) -> bool {
+    match implausibility {
+        Implausibility::OffBalance(who) => {
+            let unapplied = unapplied
+                .iter()
+                .find(|(agent, _branches)| agent == who)
+                .map(|(_agent, branches)| branches.clone())
+                .unwrap_or_default();
+            let applied = state.borrow_agent(who);
+            let spent = unapplied
+                .first()
+                .map(|unapplied| spent_balances(&applied, unapplied))
+                .unwrap_or_default();
+            !spent.is_empty()
+                && unapplied
+                    .iter()
+                    .all(|branch| spent.iter().all(|balance| is_off(branch, *balance)))
+        }
+        _ => true,
+    }
+}
+
+// Neither reading is trusted outright. Later observations strike whichever was wrong.
+// The applied branches stay first, so they still lead until something strikes them.
+pub fn quarantine(
+    state: &mut AetTimelineState,
+    implausibility: &Implausibility,
+    unapplied: Vec<(String, Vec<AgentState>)>,
+) {
+    if !is_confirmed(state, implausibility, &unapplied) {
+        return;
+    }
+    debug!("Quarantining {:?}", implausibility);
+    if unapplied.iter().any(|(who, branches)| {
+        let applied = state
+            .agent_states
+            .get(who)
+            .map_or(0, |applied| applied.len());
+        applied + branches.len() > MAX_QUARANTINE_BRANCHES
+    }) {
+        warn!("Too many branches to quarantine {:?}", implausibility);
+        return;
+    }
+    let time = state.time;
+    for (who, unapplied) in unapplied {
+        let mut branches = state.agent_states.remove(&who).unwrap_or_default();
+        branches
+            .iter_mut()
+            .for_each(|branch| branch.branch_state.branch(time));
+        for mut branch in unapplied {
+            branch.branch_state.branch(time);
+            if implausibility.strikes_unapplied() {
+                branch.branch_state.strike();
+            }
+            branches.push(branch);
+        }
+        state.agent_states.insert(who, branches);
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/plausibility_tests.rs"]
+mod plausibility_tests;
@@ -19,6 +19,7 @@ mod apply_tests {
             observations: None,
             lines: vec![("Saidenn uses Geometrics Shape on you.".to_string(), 0)],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Promptless,
             time: 0,
             me: "Seurimas".into(),
@@ -31,6 +32,7 @@ mod apply_tests {
                 ("afflicted with laxity.".to_string(), 1),
             ],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Promptless,
             time: 0,
             me: "Seurimas".into(),
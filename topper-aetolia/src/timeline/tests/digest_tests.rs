@@ -0,0 +1,164 @@
+mod digest_tests {
+    use topper_core::timeline::db::DummyDatabaseModule;
+    use topper_core::timeline::{BattleEvent, DigestEvent};
+
+    use super::super::*;
+
+    // Lines only matter for linking, so each observation gets a placeholder of its own.
+    fn push_observed(timeline: &mut AetTimeline, observations: Vec<AetObservation>, first: u32) {
+        let lines = (0..observations.len() as u32)
+            .map(|idx| ("...".to_string(), first + idx))
+            .collect();
+        let observation_lines = (0..observations.len() as u32)
+            .map(|idx| Some(first + idx))
+            .collect();
+        let slice = AetTimeSlice {
+            observations: Some(observations),
+            gmcp: Vec::new(),
+            lines,
+            observation_lines,
+            prompt: AetPrompt::Promptless,
+            time: 0,
+            me: "Seurimas".to_string(),
+        };
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+    }
+
+    fn events(timeline: &AetTimeline) -> Vec<BattleEvent> {
+        timeline
+            .digest
+            .iter()
+            .map(|event| event.event.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_digest_attack() {
+        let mut timeline = AetTimeline::new();
+        push_observed(
+            &mut timeline,
+            vec![
+                CombatAction::observation(
+                    "Seurimas",
+                    "Assassination",
+                    "Doublestab",
+                    "",
+                    "Benedicto",
+                ),
+                AetObservation::Devenoms("slike".into()),
+                AetObservation::Devenoms("kalmia".into()),
+            ],
+            10,
+        );
+        assert_eq!(
+            events(&timeline),
+            vec![BattleEvent::Linked(
+                "Seurimas hit Benedicto with Doublestab (venoms slike, kalmia)".to_string(),
+                10
+            )]
+        );
+        assert_eq!(
+            get_digest_feed(&timeline, Some(&"benedicto".to_string()), 5),
+            vec!["[10] Seurimas hit Benedicto with Doublestab (venoms slike, kalmia)".to_string()]
+        );
+        assert!(get_digest_feed(&timeline, Some(&"Kaiza".to_string()), 5).is_empty());
+        // Only the agents involved count, not names that happen to be in the text.
+        assert!(get_digest_feed(&timeline, Some(&"Ben".to_string()), 5).is_empty());
+        assert!(get_digest_feed(&timeline, Some(&"Slike".to_string()), 5).is_empty());
+        assert_eq!(
+            get_digest_feed(&timeline, Some(&"Seurimas".to_string()), 5).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_digest_cure() {
+        let mut timeline = AetTimeline::new();
+        timeline.state.for_agent(&"Benedicto".to_string(), &|me| {
+            me.set_flag(FType::Paresis, true);
+        });
+        push_observed(
+            &mut timeline,
+            vec![AetObservation::SimpleCureAction(SimpleCureAction::pill(
+                "Benedicto",
+                "opiate",
+            ))],
+            3,
+        );
+        push_observed(
+            &mut timeline,
+            vec![AetObservation::SimpleCureAction(SimpleCureAction::pill(
+                "Benedicto",
+                "opiate",
+            ))],
+            5,
+        );
+        assert_eq!(
+            events(&timeline),
+            vec![
+                BattleEvent::Linked("Benedicto cured paresis via opiate pill".to_string(), 3),
+                BattleEvent::Linked("Benedicto used opiate pill".to_string(), 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_digest_unlinked() {
+        let mut timeline = AetTimeline::new();
+        let mut slice = AetTimeSlice::new(
+            "Seurimas".to_string(),
+            0,
+            vec![CombatAction::observation(
+                "Seurimas",
+                "Assassination",
+                "Bite",
+                "",
+                "Benedicto",
+            )],
+        );
+        timeline.push_time_slice(slice.clone(), None as Option<&DummyDatabaseModule>);
+        // Simulated slices are not digested at all.
+        assert!(timeline.digest.is_empty());
+        slice
+            .lines
+            .push(("Seurimas bites Benedicto.".to_string(), 7));
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        assert_eq!(
+            events(&timeline),
+            vec![BattleEvent::Plain(
+                "Seurimas hit Benedicto with Bite".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_digest_collapse() {
+        let mut timeline = AetTimeline::new();
+        let mut likely = AgentState::default();
+        likely.set_flag(FType::Paresis, true);
+        likely.branch_state.branch(0);
+        let mut unlikely = likely.clone();
+        unlikely.set_flag(FType::Asthma, true);
+        unlikely.branch_state.strike();
+        timeline
+            .state
+            .agent_states
+            .insert("Benedicto".to_string(), vec![likely, unlikely]);
+        push_observed(
+            &mut timeline,
+            vec![
+                AetObservation::SimpleCureAction(SimpleCureAction::pill("Benedicto", "opiate")),
+                AetObservation::Devenoms("kalmia".into()),
+            ],
+            20,
+        );
+        assert_eq!(
+            timeline.digest.last(),
+            Some(&DigestEvent::new(
+                "Benedicto's branches collapsed (2 -> 1)".to_string(),
+                Some(21),
+                vec!["Benedicto".to_string()],
+            ))
+        );
+    }
+}
@@ -0,0 +1,270 @@
+mod gmcp_tests {
+    use serde_json::json;
+    use topper_core::timeline::db::DummyDatabaseModule;
+
+    use super::super::*;
+    use crate::non_agent::*;
+
+    fn me() -> String {
+        "Seurimas".to_string()
+    }
+
+    fn gmcp_slice(gmcp: Vec<GMCP>) -> AetTimeSlice {
+        AetTimeSlice {
+            observations: None,
+            lines: Vec::new(),
+            gmcp,
+            observation_lines: Vec::new(),
+            prompt: AetPrompt::Promptless,
+            time: 0,
+            me: me(),
+        }
+    }
+
+    fn get_timeline(branches: Vec<Vec<FType>>) -> AetTimeline {
+        let mut timeline = AetTimeline::new();
+        timeline.state.me = me();
+        let branches = branches
+            .into_iter()
+            .map(|affs| {
+                let mut branch = AgentState::default();
+                branch.branch_state.branch(0);
+                for aff in affs {
+                    branch.set_flag(aff, true);
+                }
+                branch
+            })
+            .collect();
+        timeline.state.agent_states.insert(me(), branches);
+        timeline
+    }
+
+    #[test]
+    fn test_affliction_list_collapses_branches() {
+        let mut timeline = get_timeline(vec![
+            vec![FType::Asthma, FType::Clumsiness],
+            vec![FType::Asthma, FType::Paresis],
+            vec![FType::Clumsiness],
+        ]);
+        let slice = gmcp_slice(vec![(
+            "gmcp.Char.Afflictions.List".to_string(),
+            json!([
+                {"name": "asthma", "cure": "eat kawhe", "desc": ""},
+                {"name": "clumsiness", "cure": "eat euphoriant", "desc": ""},
+            ]),
+        )]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        let branches = timeline.state.get_agent(&me()).unwrap();
+        assert_eq!(branches.len(), 1);
+        assert!(branches[0].is(FType::Asthma));
+        assert!(branches[0].is(FType::Clumsiness));
+        assert!(!branches[0].is(FType::Paresis));
+    }
+
+    #[test]
+    fn test_affliction_list_keeps_unreported() {
+        let mut timeline = get_timeline(vec![vec![
+            FType::Asthma,
+            FType::Fallen,
+            FType::Void,
+            FType::WritheWeb,
+        ]]);
+        let slice = gmcp_slice(vec![(
+            "gmcp.Char.Afflictions.List".to_string(),
+            json!([{"name": "clumsiness", "cure": "eat euphoriant", "desc": ""}]),
+        )]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        let me = timeline.state.borrow_me();
+        assert!(me.is(FType::Clumsiness));
+        assert!(!me.is(FType::Asthma));
+        assert!(me.is(FType::Fallen));
+        assert!(me.is(FType::Void));
+        assert!(me.is(FType::WritheWeb));
+    }
+
+    #[test]
+    fn test_affliction_add_remove() {
+        let mut timeline = get_timeline(vec![vec![FType::Asthma], vec![FType::Anorexia]]);
+        let slice = gmcp_slice(vec![
+            (
+                "gmcp.Char.Afflictions.Add".to_string(),
+                json!({"name": "asthma", "cure": "eat kawhe", "desc": ""}),
+            ),
+            (
+                "gmcp.Char.Afflictions.Remove".to_string(),
+                json!(["anorexia"]),
+            ),
+        ]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        let branches = timeline.state.get_agent(&me()).unwrap();
+        assert_eq!(branches.len(), 1);
+        assert!(branches[0].is(FType::Asthma));
+        assert!(!branches[0].is(FType::Anorexia));
+    }
+
+    #[test]
+    fn test_defence_list() {
+        let mut timeline = get_timeline(vec![vec![FType::Shielded, FType::AssumedRebounding]]);
+        let slice = gmcp_slice(vec![(
+            "gmcp.Char.Defences.List".to_string(),
+            json!([{"name": "deafness", "desc": ""}, {"name": "blindness", "desc": ""}]),
+        )]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        let me = timeline.state.borrow_me();
+        assert!(me.is(FType::Deafness));
+        assert!(me.is(FType::Blindness));
+        assert!(!me.is(FType::Shielded));
+        assert!(me.is(FType::AssumedRebounding));
+    }
+
+    #[test]
+    fn test_target_set_info() {
+        let mut timeline = get_timeline(vec![vec![]]);
+        let slice = gmcp_slice(vec![
+            ("gmcp.IRE.Target.Set".to_string(), json!("Kaiza")),
+            (
+                "gmcp.IRE.Target.Info".to_string(),
+                json!({"id": "Kaiza", "short_desc": "Kaiza", "hpperc": "75%"}),
+            ),
+        ]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        assert_eq!(timeline.state.get_my_target(), Some("Kaiza".to_string()));
+        let kaiza = timeline.state.borrow_agent(&"Kaiza".to_string());
+        assert_eq!(kaiza.get_health_percent(), 0.75);
+        let slice = gmcp_slice(vec![("gmcp.IRE.Target.Set".to_string(), json!(""))]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        assert_eq!(timeline.state.get_my_target(), None);
+    }
+
+    #[test]
+    fn test_room_items() {
+        let mut timeline = get_timeline(vec![vec![]]);
+        timeline.state.for_agent(&me(), &|me| {
+            me.room_id = 1234;
+        });
+        let slice = gmcp_slice(vec![(
+            "gmcp.Char.Items.List".to_string(),
+            json!({"location": "room", "items": [
+                {"id": "101", "name": "a rat", "attrib": "m"},
+                {"id": "102", "name": "the corpse of a rat", "attrib": "t"},
+                {"id": "103", "name": "a guard", "attrib": "mx"},
+            ]}),
+        )]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        let mut denizens: Vec<i64> = timeline
+            .state
+            .get_my_room()
+            .unwrap()
+            .denizens
+            .iter()
+            .cloned()
+            .collect();
+        denizens.sort();
+        assert_eq!(denizens, vec![101, 103]);
+        assert_eq!(
+            timeline
+                .state
+                .check_denizen(101, &|denizen| denizen.full_name.clone()),
+            Some("a rat".to_string())
+        );
+        let slice = gmcp_slice(vec![
+            (
+                "gmcp.Char.Items.Remove".to_string(),
+                json!({"location": "room", "item": {"id": "101", "name": "a rat"}}),
+            ),
+            (
+                "gmcp.Char.Items.Add".to_string(),
+                json!({"location": "room", "item": {"id": "104", "name": "a wolf", "attrib": "m"}}),
+            ),
+        ]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        let room = timeline.state.get_my_room().unwrap();
+        assert!(!room.denizens.contains(&101));
+        assert!(room.denizens.contains(&104));
+        assert_eq!(
+            timeline
+                .state
+                .check_denizen(101, &|denizen| denizen.room_id),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_channel_calls() {
+        let mut timeline = get_timeline(vec![vec![]]);
+        timeline.state.non_agent_states.insert(
+            format_enemies_id(&me()),
+            AetNonAgent::Players(vec!["Bob".to_string()]),
+        );
+        let mut slice = gmcp_slice(vec![
+            (
+                "gmcp.Comm.Channel.Text".to_string(),
+                json!({"channel": "party", "talker": "Benedicto", "text": "(Party): Benedicto says, \"X Kaiza.\""}),
+            ),
+            (
+                "gmcp.Comm.Channel.Text".to_string(),
+                json!({"channel": "party", "talker": "Benedicto", "text": "(Party): Benedicto says, \"Hello.\""}),
+            ),
+        ]);
+        slice.time = 100;
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        let mut slice = gmcp_slice(vec![(
+            "gmcp.Comm.Channel.Text".to_string(),
+            json!({"channel": "say", "talker": "Bob", "text": "Bob says, \"X Seurimas!\""}),
+        )]);
+        slice.time = 200;
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        let calls = timeline.state.get_calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].caller, "Benedicto");
+        assert_eq!(calls[0].target, "Kaiza");
+        assert!(!calls[0].enemy);
+        assert_eq!(calls[1].target, "Seurimas");
+        assert!(calls[1].enemy);
+        assert_eq!(timeline.state.get_calls_since(100), vec![calls[1].clone()]);
+    }
+
+    #[test]
+    fn test_room_players() {
+        let mut timeline = get_timeline(vec![vec![]]);
+        let slice = gmcp_slice(vec![
+            ("gmcp.Room.Info".to_string(), json!({"num": 1234})),
+            (
+                "gmcp.Room.Players".to_string(),
+                json!([
+                    {"name": me(), "fullname": me()},
+                    {"name": "Kaiza", "fullname": "Kaiza the Reaper"},
+                    {"name": "Benedicto", "fullname": "Benedicto"},
+                ]),
+            ),
+        ]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        assert_eq!(
+            timeline.state.get_players_in_my_room(),
+            vec!["Benedicto".to_string(), "Kaiza".to_string()]
+        );
+        assert_eq!(
+            timeline.state.borrow_agent(&"Kaiza".to_string()).room_id,
+            1234
+        );
+        let slice = gmcp_slice(vec![
+            (
+                "gmcp.Room.AddPlayer".to_string(),
+                json!({"name": "Tsuyu", "fullname": "Tsuyu"}),
+            ),
+            ("gmcp.Room.RemovePlayer".to_string(), json!("Kaiza")),
+        ]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        assert_eq!(
+            timeline.state.get_players_in_my_room(),
+            vec!["Benedicto".to_string(), "Tsuyu".to_string()]
+        );
+        assert_eq!(timeline.state.borrow_agent(&"Kaiza".to_string()).room_id, 0);
+        // Moving on leaves everyone behind until the next list.
+        let slice = gmcp_slice(vec![("gmcp.Room.Info".to_string(), json!({"num": 1235}))]);
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+        assert!(timeline.state.get_players_in_my_room().is_empty());
+        assert_eq!(timeline.state.borrow_agent(&"Tsuyu".to_string()).room_id, 0);
+        assert_eq!(timeline.state.borrow_me().room_id, 1235);
+    }
+}
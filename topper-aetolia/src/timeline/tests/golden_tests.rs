@@ -0,0 +1,78 @@
+mod golden_tests {
+    use std::fs;
+    use std::path::Path;
+    use topper_core::observations::ObservationParser;
+
+    use super::super::*;
+
+    lazy_static! {
+        static ref observer: ObservationParser<AetObservation> =
+            ObservationParser::<AetObservation>::new_from_directory(
+                "../triggers".to_string(),
+                aet_observation_creator
+            )
+            .unwrap();
+    }
+
+    // Each golden/<fight>.jsonl is a recorded session, checked against golden/<fight>.expected.json.
+    // With UPDATE_GOLDEN set, the expectations are rewritten from the replay instead.
+    #[test]
+    fn test_golden_fights() {
+        let update = std::env::var("UPDATE_GOLDEN").is_ok();
+        let mut failures = Vec::new();
+        let mut fights: Vec<_> = fs::read_dir("golden")
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().map(|ext| ext == "jsonl").unwrap_or(false))
+            .collect();
+        fights.sort();
+        for fight in fights.iter() {
+            let expected_path = fight.with_extension("expected.json");
+            let slices = read_recorded_slices(&fs::read_to_string(fight).unwrap()).unwrap();
+            let expected: Vec<GoldenCheckpoint> =
+                serde_json::from_str(&fs::read_to_string(&expected_path).unwrap()).unwrap();
+            let actual = replay_golden(slices, &observer, &expected).unwrap();
+            if update {
+                fs::write(
+                    &expected_path,
+                    serde_json::to_string_pretty(&actual).unwrap() + "\n",
+                )
+                .unwrap();
+            } else {
+                for diff in diff_golden(&expected, &actual) {
+                    failures.push(format!("{}: {}", display_name(fight), diff));
+                }
+            }
+        }
+        assert!(
+            failures.is_empty(),
+            "Golden fights diverged (UPDATE_GOLDEN=1 to accept):\n{}",
+            failures.join("\n")
+        );
+    }
+
+    fn display_name(path: &Path) -> String {
+        path.file_name().unwrap().to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_golden_diff() {
+        let mut expected = GoldenAgent::default();
+        expected.affs = vec![FType::Paresis, FType::Asthma];
+        expected.limbs.insert("LeftLegDamage".to_string(), 3333);
+        expected.balances.insert("Balance".to_string(), 2.5);
+        let mut actual = GoldenAgent::default();
+        actual.affs = vec![FType::Asthma, FType::Clumsiness];
+        actual.limbs.insert("LeftLegDamage".to_string(), 3333);
+        actual.balances.insert("Balance".to_string(), 2.505);
+        actual.balances.insert("Salve".to_string(), 1.0);
+        assert_eq!(
+            expected.diff(&actual),
+            vec![
+                "missing affs [Paresis]".to_string(),
+                "unexpected affs [Clumsiness]".to_string(),
+                "Salve expected 0.00s, found 1.00s".to_string(),
+            ]
+        );
+    }
+}
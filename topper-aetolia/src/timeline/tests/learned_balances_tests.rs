@@ -0,0 +1,122 @@
+mod learned_balances_tests {
+    use topper_core::timeline::db::MemoryDatabaseModule;
+
+    use super::super::*;
+
+    fn action_slice(time: CType, observations: Vec<AetObservation>) -> AetTimeSlice {
+        AetTimeSlice {
+            observations: Some(observations),
+            lines: Vec::new(),
+            gmcp: Vec::new(),
+            observation_lines: Vec::new(),
+            prompt: AetPrompt::Promptless,
+            time,
+            me: "Seurimas".to_string(),
+        }
+    }
+
+    fn vorpal(time: CType) -> AetTimeSlice {
+        action_slice(
+            time,
+            vec![CombatAction::observation(
+                "Kaiza",
+                "Battlefury",
+                "Vorpal",
+                "",
+                "Seurimas",
+            )],
+        )
+    }
+
+    #[test]
+    fn test_balance_estimate() {
+        let mut estimate = BalanceEstimate::default();
+        for sample in [3.1, 2.5, 5.0, 2.7].iter() {
+            estimate.add_sample(*sample);
+        }
+        assert_eq!(estimate.estimate(), Some(2.5));
+        assert_eq!(estimate.confident_estimate(), None);
+        estimate.add_sample(2.6);
+        assert_eq!(estimate.confident_estimate(), Some(2.5));
+    }
+
+    #[test]
+    fn test_learned_opponent_balance() {
+        let db = MemoryDatabaseModule::new();
+        let mut timeline = AetTimeline::new();
+        for time in [0, 250, 500].iter() {
+            timeline.push_time_slice(vorpal(*time), Some(&db)).unwrap();
+        }
+        // Two gaps are not enough to go on.
+        assert_eq!(
+            timeline
+                .state
+                .borrow_agent(&"Kaiza".to_string())
+                .get_balance(BType::Balance),
+            2.65
+        );
+        // A lull between fights teaches nothing.
+        timeline.push_time_slice(vorpal(750), Some(&db)).unwrap();
+        timeline.push_time_slice(vorpal(3000), Some(&db)).unwrap();
+        flush_learned_balances(&mut timeline.state, &db);
+        let learned = db.get_skill_balances("Kaiza", "Vorpal").unwrap();
+        assert_eq!(learned.get(&BType::Balance).unwrap().samples, vec![2.5; 3]);
+        assert_eq!(
+            timeline
+                .state
+                .borrow_agent(&"Kaiza".to_string())
+                .get_balance(BType::Balance),
+            2.5
+        );
+    }
+
+    #[test]
+    fn test_learned_own_balance() {
+        let db = MemoryDatabaseModule::new();
+        let mut timeline = AetTimeline::new();
+        timeline.state.me = "Seurimas".to_string();
+        let slice = action_slice(
+            0,
+            vec![
+                CombatAction::observation("Seurimas", "Battlefury", "Vorpal", "", "Kaiza"),
+                AetObservation::Balance("Balance".to_string(), 2.65),
+            ],
+        );
+        timeline.push_time_slice(slice, Some(&db)).unwrap();
+        let slice = action_slice(
+            280,
+            vec![AetObservation::BalanceBack("Balance".to_string())],
+        );
+        timeline.push_time_slice(slice, Some(&db)).unwrap();
+        flush_learned_balances(&mut timeline.state, &db);
+        let learned = db.get_skill_balances("Seurimas", "Vorpal").unwrap();
+        assert_eq!(learned.get(&BType::Balance).unwrap().samples, vec![2.8]);
+        assert_eq!(
+            timeline
+                .state
+                .get_player_hint(&"Seurimas".to_string(), &ATTACK_BALANCE_HINT.to_string()),
+            Some("2.8".to_string())
+        );
+    }
+
+    #[test]
+    fn test_learned_balances_flushed() {
+        let db = MemoryDatabaseModule::new();
+        let mut timeline = AetTimeline::new();
+        for time in [0, 250, 500, 750].iter() {
+            timeline.push_time_slice(vorpal(*time), Some(&db)).unwrap();
+        }
+        // Learned in memory, but not yet written back.
+        assert_eq!(db.get_skill_balances("Kaiza", "Vorpal"), None);
+        timeline.push_time_slice(vorpal(3250), Some(&db)).unwrap();
+        assert_eq!(
+            timeline
+                .state
+                .borrow_agent(&"Kaiza".to_string())
+                .get_balance(BType::Balance),
+            2.5
+        );
+        let learned = db.get_skill_balances("Kaiza", "Vorpal").unwrap();
+        assert_eq!(learned.get(&BType::Balance).unwrap().samples, vec![2.5; 3]);
+    }
+}
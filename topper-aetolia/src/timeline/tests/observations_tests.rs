@@ -19,6 +19,7 @@ mod observer_tests {
                 0,
             )],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Promptless,
             time: 0,
             me: "Seurimas".into(),
@@ -44,6 +45,7 @@ mod observer_tests {
                 0,
             )],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Promptless,
             time: 0,
             me: "Seurimas".into(),
@@ -66,6 +68,7 @@ mod observer_tests {
             observations: None,
             lines: vec![("You use Assassination Warding.".to_string(), 0)],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Promptless,
             time: 0,
             me: "Seurimas".into(),
@@ -91,6 +94,7 @@ mod observer_tests {
                 ("Benedicto touches a tree of life tattoo.".to_string(), 1),
             ],
             gmcp: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: AetPrompt::Promptless,
             time: 0,
             me: "Seurimas".into(),
@@ -0,0 +1,214 @@
+mod plausibility_tests {
+    use topper_core::timeline::db::DummyDatabaseModule;
+
+    use super::super::*;
+
+    fn get_timeline() -> AetTimeline {
+        let mut timeline = AetTimeline::new();
+        timeline.state.me = "Seurimas".to_string();
+        for who in ["Seurimas", "Kaiza"].iter() {
+            timeline.state.for_agent(&who.to_string(), &|me| {
+                me.room_id = 1234;
+            });
+        }
+        timeline
+    }
+
+    fn push(timeline: &mut AetTimeline, observation: AetObservation) {
+        push_all(timeline, vec![observation]);
+    }
+
+    fn push_all(timeline: &mut AetTimeline, observations: Vec<AetObservation>) {
+        let slice = AetTimeSlice {
+            observations: Some(observations),
+            lines: Vec::new(),
+            gmcp: Vec::new(),
+            observation_lines: Vec::new(),
+            prompt: AetPrompt::Promptless,
+            time: 0,
+            me: "Seurimas".to_string(),
+        };
+        timeline.push_time_slice(slice, None as Option<&DummyDatabaseModule>);
+    }
+
+    fn vorpal() -> AetObservation {
+        CombatAction::observation("Kaiza", "Battlefury", "Vorpal", "", "Seurimas")
+    }
+
+    fn kaiza_branches(timeline: &AetTimeline) -> usize {
+        timeline
+            .state
+            .get_agent(&"Kaiza".to_string())
+            .map(|branches| branches.len())
+            .unwrap_or_default()
+    }
+
+    fn my_branches(timeline: &AetTimeline) -> usize {
+        timeline
+            .state
+            .get_agent(&"Seurimas".to_string())
+            .map(|branches| branches.len())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_plausible_attack() {
+        let mut timeline = get_timeline();
+        assert_eq!(check_plausibility(&timeline.state, &vorpal()), None);
+        push(&mut timeline, vorpal());
+        assert_eq!(kaiza_branches(&timeline), 1);
+    }
+
+    #[test]
+    fn test_attack_off_balance() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Kaiza".to_string(), &|me| {
+            me.set_balance(BType::Balance, 3.0);
+            me.set_balance(BType::Equil, 3.0);
+        });
+        assert_eq!(
+            check_plausibility(&timeline.state, &vorpal()),
+            Some(Implausibility::OffBalance("Kaiza".to_string()))
+        );
+        push(&mut timeline, vorpal());
+        // Both readings are kept, for Kaiza and for us, until something strikes one.
+        let branches = timeline.state.get_agent(&"Kaiza".to_string()).unwrap();
+        assert_eq!(branches.len(), 2);
+        assert!(branches
+            .iter()
+            .all(|branch| branch.branch_state.strikes() == 0));
+        assert!(branches
+            .iter()
+            .any(|branch| branch.get_balance(BType::Balance) == 3.0));
+        assert_eq!(my_branches(&timeline), 2);
+    }
+
+    #[test]
+    fn test_free_action_off_balance() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Kaiza".to_string(), &|me| {
+            me.set_balance(BType::Balance, 3.0);
+            me.set_balance(BType::Equil, 3.0);
+        });
+        let free = CombatAction::observation("Kaiza", "Battlefury", "Nothing", "", "Seurimas");
+        assert_eq!(
+            check_plausibility(&timeline.state, &free),
+            Some(Implausibility::OffBalance("Kaiza".to_string()))
+        );
+        push(&mut timeline, free);
+        // Nothing was spent, so there is nothing to doubt.
+        let branches = timeline.state.get_agent(&"Kaiza".to_string()).unwrap();
+        assert_eq!(branches.len(), 1);
+        assert_eq!(branches[0].branch_state.strikes(), 0);
+    }
+
+    #[test]
+    fn test_quarantine_cap() {
+        let mut timeline = get_timeline();
+        let implausibility = Implausibility::NotInRoom("Kaiza".to_string());
+        for _ in 0..3 {
+            let snapshot = quarantine_snapshot(&timeline.state, &vorpal());
+            quarantine(&mut timeline.state, &implausibility, snapshot);
+        }
+        assert_eq!(kaiza_branches(&timeline), 8);
+        assert_eq!(my_branches(&timeline), 8);
+        let snapshot = quarantine_snapshot(&timeline.state, &vorpal());
+        quarantine(&mut timeline.state, &implausibility, snapshot);
+        assert_eq!(kaiza_branches(&timeline), 8);
+        assert_eq!(my_branches(&timeline), 8);
+    }
+
+    #[test]
+    fn test_attack_from_elsewhere() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Kaiza".to_string(), &|me| {
+            me.room_id = 4321;
+        });
+        assert_eq!(
+            check_plausibility(&timeline.state, &vorpal()),
+            Some(Implausibility::NotInRoom("Kaiza".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_ranged_attack_from_elsewhere() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Kaiza".to_string(), &|me| {
+            me.room_id = 4321;
+        });
+        let throw = CombatAction::observation("Kaiza", "Beastmastery", "Throw", "", "Seurimas");
+        assert_eq!(check_plausibility(&timeline.state, &throw), None);
+    }
+
+    #[test]
+    fn test_attack_from_elsewhere_confirmed() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Kaiza".to_string(), &|me| {
+            me.room_id = 4321;
+        });
+        push(
+            &mut timeline,
+            CombatAction::observation("Kaiza", "Dhuriv", "Gouge", "", "Seurimas"),
+        );
+        assert_eq!(my_branches(&timeline), 2);
+        // Curing the impatience strikes the reading where the gouge never landed.
+        push_all(
+            &mut timeline,
+            vec![
+                AetObservation::SimpleCureAction(SimpleCureAction::pill("Seurimas", "goldenseal")),
+                AetObservation::Cured("impatience".to_string()),
+            ],
+        );
+        let branches = timeline.state.get_agent(&"Seurimas".to_string()).unwrap();
+        assert_eq!(branches.len(), 1);
+        assert!(!branches[0].is(FType::Impatience));
+        assert!(!branches[0].balanced(BType::Pill));
+    }
+
+    #[test]
+    fn test_blocked_cure() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Kaiza".to_string(), &|me| {
+            me.set_flag(FType::Anorexia, true);
+        });
+        let eat =
+            AetObservation::SimpleCureAction(SimpleCureAction::pill("Kaiza", "antipsychotic"));
+        assert_eq!(
+            check_plausibility(&timeline.state, &eat),
+            Some(Implausibility::CureBlocked(
+                "Kaiza".to_string(),
+                FType::Anorexia
+            ))
+        );
+        push(&mut timeline, eat);
+        // Eating is taken as proof the anorexia was gone, until something says otherwise.
+        assert_eq!(kaiza_branches(&timeline), 1);
+        let kaiza = timeline.state.borrow_agent(&"Kaiza".to_string());
+        assert!(!kaiza.balanced(BType::Pill));
+        assert!(!kaiza.is(FType::Anorexia));
+    }
+
+    #[test]
+    fn test_blocked_cure_doubted() {
+        let mut timeline = get_timeline();
+        timeline.state.for_agent(&"Kaiza".to_string(), &|me| {
+            me.set_flag(FType::Anorexia, true);
+        });
+        push_all(
+            &mut timeline,
+            vec![
+                AetObservation::SimpleCureAction(SimpleCureAction::pill("Kaiza", "antipsychotic")),
+                // Only cures anything if the anorexia was real.
+                AetObservation::SimpleCureAction(SimpleCureAction::salve(
+                    "Kaiza",
+                    "epidermal",
+                    "torso",
+                )),
+            ],
+        );
+        let branches = timeline.state.get_agent(&"Kaiza".to_string()).unwrap();
+        assert_eq!(branches.len(), 2);
+        assert!(branches.iter().any(|branch| !branch.balanced(BType::Pill)));
+        assert!(branches.iter().any(|branch| branch.balanced(BType::Pill)));
+    }
+}
@@ -0,0 +1,28 @@
+mod types_tests {
+    use super::super::*;
+
+    #[test]
+    fn test_strikeout_keeps_order() {
+        let mut timeline = AetTimeline::new();
+        let balances = [5.0, 1.0, 4.0, 2.0, 3.0, 6.0];
+        let mut branches = Vec::new();
+        for balance in balances.iter().chain(balances.iter()) {
+            let mut branch = AgentState::default();
+            branch.set_balance(BType::Balance, *balance);
+            branches.push(branch);
+        }
+        timeline
+            .state
+            .agent_states
+            .insert("Kaiza".to_string(), branches);
+        timeline.state.strikeout();
+        let kept: Vec<f32> = timeline
+            .state
+            .get_agent(&"Kaiza".to_string())
+            .unwrap()
+            .iter()
+            .map(|branch| branch.get_balance(BType::Balance))
+            .collect();
+        assert_eq!(kept, balances.to_vec());
+    }
+}
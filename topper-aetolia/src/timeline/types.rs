@@ -1,5 +1,8 @@
 use super::apply_functions::*;
+use super::digest::*;
 use super::gmcp_functions::*;
+use super::learned_balances::flush_learned_balances_periodically;
+use super::plausibility::*;
 use crate::classes::{get_skill_class, handle_combat_action, handle_sent, Class, VENOM_AFFLICTS};
 use crate::curatives::{
     handle_simple_cure_action, top_aff, CALORIC_TORSO_ORDER, PILL_CURE_ORDERS, PILL_DEFENCES,
@@ -46,6 +49,7 @@ pub fn simulation_slice(observations: Vec<AetObservation>, time: CType) -> AetTi
         observations: Some(observations),
         gmcp: Vec::new(),
         lines: Vec::new(),
+        observation_lines: Vec::new(),
         prompt: AetPrompt::Simulation,
         time,
         me: "".to_string(),
@@ -255,6 +259,7 @@ pub trait AetTimelineStateTrait {
         &mut self,
         slice: &TimeSlice<AetObservation, AetPrompt>,
         db: Option<&DB>,
+        digest: Option<&mut Vec<DigestEvent>>,
     ) -> Result<(), String>;
 
     fn strikeout(&mut self);
@@ -354,10 +359,11 @@ impl AetTimelineStateTrait for AetTimelineState {
         &mut self,
         slice: &TimeSlice<AetObservation, AetPrompt>,
         db: Option<&DB>,
+        mut digest: Option<&mut Vec<DigestEvent>>,
     ) -> Result<(), String> {
         self.me = slice.me.clone();
         self.update_time(slice.time);
-        for gmcp in slice.gmcp.iter() {
+        for gmcp in slice.gmcp.iter().filter(|gmcp| !is_own_flag_gmcp(gmcp)) {
             self.apply_gmcp(gmcp, db);
         }
         let mut before = Vec::new();
@@ -370,15 +376,32 @@ impl AetTimelineStateTrait for AetTimelineState {
         let lines = &slice.lines;
         let mut after = observations.clone();
         let mut is_illusion = false;
-        for observation in observations.iter() {
+        for (idx, observation) in observations.iter().enumerate() {
             if *observation == AetObservation::Illusion {
                 is_illusion = !is_illusion;
             }
             if !is_illusion {
+                let watched = digest
+                    .as_ref()
+                    .map(|_| watch_observation(self, observation));
+                let unapplied = check_plausibility(self, observation).map(|implausibility| {
+                    let snapshot = quarantine_snapshot(self, observation);
+                    (implausibility, snapshot)
+                });
                 let obs_results = self.apply_observation(observation, lines, &before, &after, db);
                 if let Err(error) = obs_results {
                     println!("Bad observation: {:?} ({})", observation, error);
                 }
+                if let Some((implausibility, unapplied)) = unapplied {
+                    quarantine(self, &implausibility, unapplied);
+                }
+                if let (Some(digest), Some(watched)) = (digest.as_mut(), watched) {
+                    let line = slice.observation_lines.get(idx).cloned().flatten();
+                    let agents = involved_agents(observation);
+                    for event in digest_observation(self, observation, &after, watched) {
+                        digest.push(DigestEvent::new(event, line, agents.clone()));
+                    }
+                }
             }
             if after.len() > 0 {
                 let next = after.remove(0);
@@ -391,7 +414,19 @@ impl AetTimelineStateTrait for AetTimelineState {
                 you.set_stat(SType::SP, sp);
             });
         }
+        // Our own afflictions and defences go last, so they settle what the lines left uncertain.
+        for gmcp in slice.gmcp.iter().filter(|gmcp| is_own_flag_gmcp(gmcp)) {
+            self.apply_gmcp(gmcp, db);
+        }
+        let branches = digest.as_ref().map(|_| count_branches(self));
         self.strikeout();
+        if let (Some(digest), Some(branches)) = (digest.as_mut(), branches) {
+            // Collapses follow from the whole slice, so they are put on its last line.
+            let line = slice.observation_lines.iter().rev().find_map(|line| *line);
+            for (who, event) in digest_collapses(&branches, self) {
+                digest.push(DigestEvent::new(event, line, vec![who]));
+            }
+        }
         Ok(())
     }
 
@@ -407,11 +442,9 @@ impl AetTimelineStateTrait for AetTimelineState {
             values.retain(|branch| branch.branch_state.strikes() == lowest_strikes);
             let mid = values.len();
             if mid > 1 {
+                // Duplicates go, but the order stays, so the leading branch is still the likeliest.
                 let mut set = HashSet::new();
-                for branch in values.iter() {
-                    set.insert(branch.clone());
-                }
-                values.splice(.., set);
+                values.retain(|branch| set.insert(branch.clone()));
             }
             let after = values.len();
             if before != after {
@@ -429,6 +462,7 @@ impl AetTimelineTrait for AetTimeline {
     fn reset(&mut self, full: bool) {
         if full {
             self.state.agent_states = HashMap::new();
+            self.digest.clear();
         } else {
             for (key, val) in self.state.agent_states.iter_mut() {
                 val.truncate(1);
@@ -467,7 +501,20 @@ impl<DB: AetDatabaseModule + DatabaseModule> BaseTimeline<AetObservation, AetPro
         slice: TimeSlice<AetObservation, AetPrompt>,
         db: Option<&DB>,
     ) -> Result<(), String> {
-        let result = self.state.apply_time_slice::<DB>(&slice, db);
+        // Simulated slices have no lines, and nothing in them to link the digest to.
+        let digest = if slice.lines.is_empty() {
+            None
+        } else {
+            Some(&mut self.digest)
+        };
+        let result = self.state.apply_time_slice::<DB>(&slice, db, digest);
+        if let Some(db) = db {
+            flush_learned_balances_periodically(&mut self.state, db);
+        }
+        if self.digest.len() > DIGEST_LENGTH {
+            let excess = self.digest.len() - DIGEST_LENGTH;
+            self.digest.drain(..excess);
+        }
         self.slices.push(slice);
         result
     }
@@ -479,6 +526,10 @@ impl TestableTimeline<AetObservation, AetPrompt> for AetTimeline {
         slice: TimeSlice<AetObservation, AetPrompt>,
     ) -> Result<(), String> {
         self.state
-            .apply_time_slice::<DummyDatabaseModule>(&slice, None)
+            .apply_time_slice::<DummyDatabaseModule>(&slice, None, None)
     }
 }
+
+#[cfg(test)]
+#[path = "./tests/types_tests.rs"]
+mod types_tests;
@@ -17,3 +17,13 @@ pub trait UnpoweredFunction {
     ) -> UnpoweredFunctionState;
     fn reset(self: &mut Self, model: &Self::Model);
 }
+
+// A controller which notes why a tree went the way it did. Notes taken under a branch that
+// fails are rolled back, so only the path actually taken is described. A checkpoint is just
+// the notes as they stood, so one never needs to be released. Controllers without notes can
+// use `()` as their checkpoint.
+pub trait BranchNotes {
+    type Checkpoint: Send + Sync;
+    fn checkpoint(&self) -> Self::Checkpoint;
+    fn rollback(&mut self, checkpoint: Self::Checkpoint);
+}
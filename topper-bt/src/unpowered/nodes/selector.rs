@@ -1,19 +1,25 @@
 use crate::unpowered::*;
 
-pub struct Selector<M, C> {
+pub struct Selector<M, C: BranchNotes> {
     nodes: Vec<Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>>,
     index: Option<usize>,
+    // Taken when the running node started, so it survives the node waiting.
+    checkpoint: Option<C::Checkpoint>,
 }
 
-impl<M, C> Selector<M, C> {
+impl<M, C: BranchNotes> Selector<M, C> {
     pub fn new(
         nodes: Vec<Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>>,
     ) -> Self {
-        Selector { nodes, index: None }
+        Selector {
+            nodes,
+            index: None,
+            checkpoint: None,
+        }
     }
 }
 
-impl<M: 'static, C: 'static> UnpoweredFunction for Selector<M, C> {
+impl<M: 'static, C: BranchNotes + 'static> UnpoweredFunction for Selector<M, C> {
     type Model = M;
     type Controller = C;
     fn resume_with(
@@ -24,9 +30,15 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Selector<M, C> {
         let mut running_index = self.index.unwrap_or(0);
         loop {
             if let Some(node) = self.nodes.get_mut(running_index) {
+                let checkpoint = self
+                    .checkpoint
+                    .take()
+                    .unwrap_or_else(|| controller.checkpoint());
                 let result = node.resume_with(model, controller);
                 match result {
                     UnpoweredFunctionState::Failed => {
+                        // Whatever the failed node noted no longer explains anything.
+                        controller.rollback(checkpoint);
                         // Move on to the next node.
                         running_index += 1;
                     }
@@ -37,6 +49,7 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Selector<M, C> {
                     _ => {
                         // Waiting, NeedsGas
                         self.index = Some(running_index);
+                        self.checkpoint = Some(checkpoint);
                         return result;
                     }
                 }
@@ -49,5 +62,6 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Selector<M, C> {
 
     fn reset(self: &mut Self, _parameter: &Self::Model) {
         self.index = None;
+        self.checkpoint = None;
     }
 }
@@ -1,19 +1,25 @@
 use crate::unpowered::*;
 
-pub struct Sequence<M, C> {
+pub struct Sequence<M, C: BranchNotes> {
     nodes: Vec<Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>>,
     index: Option<usize>,
+    // Taken when this run started, so it survives a node waiting.
+    checkpoint: Option<C::Checkpoint>,
 }
 
-impl<M, C> Sequence<M, C> {
+impl<M, C: BranchNotes> Sequence<M, C> {
     pub fn new(
         nodes: Vec<Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>>,
     ) -> Self {
-        Sequence { nodes, index: None }
+        Sequence {
+            nodes,
+            index: None,
+            checkpoint: None,
+        }
     }
 }
 
-impl<M: 'static, C: 'static> UnpoweredFunction for Sequence<M, C> {
+impl<M: 'static, C: BranchNotes + 'static> UnpoweredFunction for Sequence<M, C> {
     type Model = M;
     type Controller = C;
     fn resume_with(
@@ -21,6 +27,9 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Sequence<M, C> {
         model: &Self::Model,
         controller: &mut Self::Controller,
     ) -> UnpoweredFunctionState {
+        if self.index.is_none() {
+            self.checkpoint = Some(controller.checkpoint());
+        }
         let mut running_index = self.index.unwrap_or(0);
         loop {
             if let Some(node) = self.nodes.get_mut(running_index) {
@@ -32,6 +41,9 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Sequence<M, C> {
                     }
                     UnpoweredFunctionState::Failed => {
                         self.index = None;
+                        if let Some(checkpoint) = self.checkpoint.take() {
+                            controller.rollback(checkpoint);
+                        }
                         return result;
                     }
                     _ => {
@@ -42,6 +54,7 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Sequence<M, C> {
                 }
             } else {
                 self.index = None;
+                self.checkpoint = None;
                 return UnpoweredFunctionState::Complete;
             }
         }
@@ -49,5 +62,6 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Sequence<M, C> {
 
     fn reset(self: &mut Self, model: &Self::Model) {
         self.index = None;
+        self.checkpoint = None;
     }
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{nodes::*, UnpoweredFunction};
+use super::{nodes::*, BranchNotes, UnpoweredFunction};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub enum UnpoweredTreeDef<U: UserNodeDefinition> {
@@ -39,7 +39,10 @@ where
     }
 }
 
-impl<U: UserNodeDefinition> UnpoweredTreeDef<U> {
+impl<U: UserNodeDefinition> UnpoweredTreeDef<U>
+where
+    U::Controller: BranchNotes,
+{
     pub fn create_tree(
         &self,
     ) -> Box<dyn UnpoweredFunction<Model = U::Model, Controller = U::Controller> + Send + Sync>
@@ -1,7 +1,9 @@
+pub use crate::topper::recorder::SessionRecorder;
 pub use crate::topper::telnet::TelnetModule;
 pub use crate::topper::timeline::TimelineModule;
 use topper_core::timeline::db::DatabaseModule;
 use topper_core::timeline::{BaseAgentState, CType, Timeline};
+pub mod recorder;
 pub mod telnet;
 pub mod timeline;
 use log::info;
@@ -227,8 +229,15 @@ pub trait TopperHandler<BS> {
     ) -> Result<TopperResponse<BS>, String>;
     fn from_str(&self, line: &String) -> Result<Self::Message, String>;
 
+    fn get_recorder(&mut self) -> Option<&mut SessionRecorder> {
+        None
+    }
+
     fn parse_request_or_event(&mut self, line: &String) -> Result<TopperResponse<BS>, String> {
         let start = Instant::now();
+        if let Some(recorder) = self.get_recorder() {
+            recorder.record(line);
+        }
         let parsed = self.from_str(line);
         let result = match parsed {
             Ok(mut topper_msg) => self.handle_request_or_event(&mut topper_msg),
@@ -0,0 +1,147 @@
+use crate::topper::{TopperHandler, TopperResponse};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+
+// Every message as it arrived, one JSON line each, so a session can be fed back through a topper.
+// Each line is written out as it is recorded, so a crash loses nothing before it.
+pub struct SessionRecorder {
+    file: LineWriter<File>,
+}
+
+impl SessionRecorder {
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(SessionRecorder {
+            file: LineWriter::new(file),
+        })
+    }
+
+    pub fn record(&mut self, line: &str) {
+        if let Err(err) = writeln!(self.file, "{}", line) {
+            println!("Could not record line: {:?}", err);
+        }
+    }
+}
+
+pub fn read_session(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut lines = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            lines.push(line);
+        }
+    }
+    Ok(lines)
+}
+
+// Responses come back in the order the lines were handled, stopping where the session was killed.
+pub fn replay_session<BS, H: TopperHandler<BS>>(
+    handler: &mut H,
+    lines: &Vec<String>,
+) -> Vec<TopperResponse<BS>> {
+    let mut responses = Vec::new();
+    for line in lines.iter() {
+        let response = handler
+            .parse_request_or_event(line)
+            .unwrap_or_else(|err| TopperResponse::error(err.to_string()));
+        let die = response.die;
+        responses.push(response);
+        if die {
+            break;
+        }
+    }
+    responses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counts what it has seen, so a replay only matches if every line came back in order.
+    #[derive(Default)]
+    struct CountingHandler {
+        seen: usize,
+        recorder: Option<SessionRecorder>,
+    }
+
+    impl TopperHandler<()> for CountingHandler {
+        type Message = String;
+
+        fn handle_request_or_event(
+            &mut self,
+            line: &mut String,
+        ) -> Result<TopperResponse<()>, String> {
+            self.seen += 1;
+            if line == "kill" {
+                Ok(TopperResponse::die())
+            } else {
+                Ok(TopperResponse::passive(line.clone(), self.seen.to_string()))
+            }
+        }
+
+        fn from_str(&self, line: &String) -> Result<String, String> {
+            if line.starts_with("bad") {
+                Err(format!("Bad line: {}", line))
+            } else {
+                Ok(line.clone())
+            }
+        }
+
+        fn get_recorder(&mut self) -> Option<&mut SessionRecorder> {
+            self.recorder.as_mut()
+        }
+    }
+
+    fn sent(responses: &Vec<TopperResponse<()>>) -> Vec<String> {
+        responses
+            .iter()
+            .map(|response| serde_json::to_string(response).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_replay_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("topper_session_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let session: Vec<String> = vec!["first", "bad line", "second", "kill", "after"]
+            .into_iter()
+            .map(|line| line.to_string())
+            .collect();
+        let mut live = CountingHandler {
+            recorder: Some(SessionRecorder::new(&path).unwrap()),
+            ..Default::default()
+        };
+        let mut live_responses = Vec::new();
+        for line in session.iter().take(4) {
+            live_responses.push(
+                live.parse_request_or_event(line)
+                    .unwrap_or_else(|err| TopperResponse::error(err.to_string())),
+            );
+        }
+        let recorded = read_session(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(recorded, session[..4].to_vec());
+        let replayed = replay_session(&mut CountingHandler::default(), &recorded);
+        assert_eq!(sent(&replayed), sent(&live_responses));
+        // Nothing after the kill is handled, as in the live session.
+        let replayed = replay_session(&mut CountingHandler::default(), &session);
+        assert_eq!(sent(&replayed), sent(&live_responses));
+    }
+
+    #[test]
+    fn test_record_written_immediately() {
+        let path =
+            std::env::temp_dir().join(format!("topper_session_live_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut recorder = SessionRecorder::new(&path).unwrap();
+        recorder.record("first");
+        // Still held open, as if the topper were about to crash.
+        let recorded = read_session(&path).unwrap();
+        drop(recorder);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(recorded, vec!["first".to_string()]);
+    }
+}
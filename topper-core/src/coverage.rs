@@ -0,0 +1,144 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref NUMBER: Regex = Regex::new(r"\d+").unwrap();
+    static ref CAPITALIZED: Regex = Regex::new(r"\b[A-Z][a-z']+\b").unwrap();
+    static ref WHITESPACE: Regex = Regex::new(r"\s+").unwrap();
+}
+
+// Capitalized words that start sentences far more often than they name someone.
+const SENTENCE_WORDS: [&str; 24] = [
+    "A", "An", "The", "You", "Your", "You're", "Yourself", "He", "She", "It", "They", "His", "Her",
+    "Its", "Their", "This", "That", "With", "As", "From", "Suddenly", "There", "What", "Without",
+];
+
+// Names and numbers are replaced, so every line a missing trigger should catch shares a shape.
+pub fn normalize_line(line: &str) -> String {
+    let line = NUMBER.replace_all(line.trim(), "<n>");
+    let line = CAPITALIZED.replace_all(&line, |captures: &regex::Captures| {
+        let word = captures.get(0).unwrap().as_str();
+        if SENTENCE_WORDS.contains(&word) {
+            word.to_string()
+        } else {
+            "<name>".to_string()
+        }
+    });
+    WHITESPACE.replace_all(&line, " ").to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnmatchedShape {
+    pub shape: String,
+    pub count: usize,
+    // The first line seen with this shape.
+    pub example: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct LineCoverage {
+    shapes: HashMap<String, UnmatchedShape>,
+}
+
+impl LineCoverage {
+    pub fn record_unmatched(&mut self, line: &str) {
+        let shape = normalize_line(line);
+        if shape.is_empty() {
+            return;
+        }
+        self.shapes
+            .entry(shape.clone())
+            .or_insert_with(|| UnmatchedShape {
+                shape,
+                count: 0,
+                example: line.trim().to_string(),
+            })
+            .count += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+    }
+
+    // Most common shapes first, ties broken by shape so reports are stable.
+    pub fn report(&self) -> Vec<&UnmatchedShape> {
+        let mut shapes: Vec<&UnmatchedShape> = self.shapes.values().collect();
+        shapes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.shape.cmp(&b.shape)));
+        shapes
+    }
+
+    pub fn format_report(&self, limit: usize) -> String {
+        self.report()
+            .iter()
+            .take(limit)
+            .map(|shape| {
+                format!(
+                    "{:>5} {}\n      e.g. {}",
+                    shape.count, shape.shape, shape.example
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod coverage_test {
+    use super::*;
+
+    #[test]
+    fn test_normalize_line() {
+        assert_eq!(
+            normalize_line("Kaiza slashes you with a scythe for 1234 damage."),
+            "<name> slashes you with a scythe for <n> damage."
+        );
+        assert_eq!(
+            normalize_line("  You see   Benedicto's wounds close. "),
+            "You see <name> wounds close."
+        );
+    }
+
+    #[test]
+    fn test_report_clusters() {
+        let mut coverage = LineCoverage::default();
+        coverage.record_unmatched("Kaiza bleeds for 12 health.");
+        coverage.record_unmatched("Tsuyu bleeds for 40 health.");
+        coverage.record_unmatched("The wind howls.");
+        coverage.record_unmatched("");
+        let report = coverage.report();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].shape, "<name> bleeds for <n> health.");
+        assert_eq!(report[0].count, 2);
+        assert_eq!(report[0].example, "Kaiza bleeds for 12 health.");
+        assert_eq!(report[1].count, 1);
+    }
+
+    #[test]
+    fn test_observe_with_coverage() {
+        use crate::observations::ObservationParser;
+        use crate::timeline::TimeSlice;
+        let parser = ObservationParser::new_from_string(
+            r#"[{"regex": "^You are hit\\.$", "args": [], "observation_name": "Hit"}]"#.to_string(),
+            |name, _args| name.clone(),
+        )
+        .unwrap();
+        let slice: TimeSlice<String, ()> = TimeSlice {
+            observations: None,
+            gmcp: vec![],
+            lines: vec![
+                ("You are hit.".to_string(), 0),
+                ("Kaiza dodges.".to_string(), 1),
+            ],
+            observation_lines: Vec::new(),
+            prompt: (),
+            time: 0,
+            me: "Seurimas".to_string(),
+        };
+        let mut coverage = LineCoverage::default();
+        let observations = parser.observe_with_coverage(&slice, &mut coverage);
+        assert_eq!(observations, vec!["Hit".to_string()]);
+        assert_eq!(coverage.report().len(), 1);
+        assert_eq!(coverage.report()[0].shape, "<name> dodges.");
+    }
+}
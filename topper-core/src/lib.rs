@@ -13,5 +13,6 @@ extern crate chrono;
 extern crate simplelog;
 pub mod colored_lines;
 pub mod combinatorics;
+pub mod coverage;
 pub mod observations;
 pub mod timeline;
@@ -1,3 +1,4 @@
+use crate::coverage::LineCoverage;
 use crate::timeline::TimeSlice;
 use regex::{Captures, Match, Regex, RegexSet, RegexSetBuilder};
 use serde::{Deserialize, Serialize};
@@ -237,6 +238,45 @@ where
     }
 
     pub fn observe<P>(&self, slice: &TimeSlice<O, P>) -> Vec<O> {
+        self.observe_lines(slice, None)
+            .into_iter()
+            .map(|(observation, _line)| observation)
+            .collect()
+    }
+
+    // As observe, but lines which produced no observation are recorded in the coverage.
+    pub fn observe_with_coverage<P>(
+        &self,
+        slice: &TimeSlice<O, P>,
+        coverage: &mut LineCoverage,
+    ) -> Vec<O> {
+        self.observe_lines(slice, Some(coverage))
+            .into_iter()
+            .map(|(observation, _line)| observation)
+            .collect()
+    }
+
+    // Appends what the lines show to the slice's observations, noting the line behind each.
+    // Observations already in the slice came from elsewhere, so they have no line.
+    pub fn observe_into<P>(
+        &self,
+        slice: &mut TimeSlice<O, P>,
+        coverage: Option<&mut LineCoverage>,
+    ) {
+        let observed = self.observe_lines(slice, coverage);
+        let observations = slice.observations.get_or_insert(Vec::new());
+        slice.observation_lines.resize(observations.len(), None);
+        for (observation, line) in observed {
+            observations.push(observation);
+            slice.observation_lines.push(Some(line));
+        }
+    }
+
+    fn observe_lines<P>(
+        &self,
+        slice: &TimeSlice<O, P>,
+        mut coverage: Option<&mut LineCoverage>,
+    ) -> Vec<(O, u32)> {
         let mut observations = Vec::new();
         {
             let mut benchmarks = BENCHMARKS.lock().unwrap();
@@ -247,6 +287,7 @@ where
         }
         for (line, idx) in slice.lines.iter() {
             let stripped = strip_ansi(line);
+            let observed = observations.len();
             // for match_num in self.regex_set.matches(&stripped) {
             //     let mapping = self.mappings.get(match_num).unwrap();
             //     let regex = self.regexes.get(match_num).unwrap();
@@ -264,15 +305,20 @@ where
                     continue;
                 }
                 if let Some(arguments) = mapping.try_get_arguments(&slice, &regex, &stripped) {
-                    observations.push((self.observation_creator)(
-                        &mapping.observation_name,
-                        arguments,
+                    observations.push((
+                        (self.observation_creator)(&mapping.observation_name, arguments),
+                        *idx,
                     ));
                     log::info!("{:?}", observations.get(observations.len() - 1));
                 }
                 // *BENCHMARKS.lock().unwrap().get_mut(match_num).unwrap() +=
                 //     Instant::now().duration_since(now).as_nanos();
             }
+            if observations.len() == observed {
+                if let Some(coverage) = coverage.as_mut() {
+                    coverage.record_unmatched(&stripped);
+                }
+            }
         }
         observations
     }
@@ -1,4 +1,5 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -29,3 +30,42 @@ impl DatabaseModule for DummyDatabaseModule {
         panic!("Dummy called");
     }
 }
+
+// Keeps everything in memory, for simulations and tests that need a working database.
+#[derive(Default)]
+pub struct MemoryDatabaseModule {
+    trees: RwLock<HashMap<(String, String), Arc<[u8]>>>,
+}
+
+impl MemoryDatabaseModule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DatabaseModule for MemoryDatabaseModule {
+    fn insert_json<T: Serialize>(&self, tree: &str, key: &String, value: T) {
+        if let Ok(bytes) = serde_json::to_vec(&value) {
+            self.insert(tree, key, &bytes);
+        }
+    }
+    fn get_json<T: DeserializeOwned>(&self, tree: &str, key: &String) -> Option<T> {
+        self.get(tree, key)
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    }
+
+    fn insert(&self, tree: &str, key: &String, value: &[u8]) {
+        self.trees
+            .write()
+            .unwrap()
+            .insert((tree.to_string(), key.clone()), value.into());
+    }
+
+    fn get(&self, tree: &str, key: &String) -> Option<Arc<[u8]>> {
+        self.trees
+            .read()
+            .unwrap()
+            .get(&(tree.to_string(), key.clone()))
+            .cloned()
+    }
+}
@@ -15,6 +15,9 @@ pub struct TimeSlice<O, P> {
     pub observations: Option<Vec<O>>,
     pub gmcp: Vec<GMCP>,
     pub lines: Vec<(String, u32)>,
+    // The line each observation came from, where the observer matched one.
+    #[serde(default)]
+    pub observation_lines: Vec<Option<u32>>,
     pub prompt: P,
     pub time: CType,
     pub me: String,
@@ -26,6 +29,7 @@ impl<O, P: Default> TimeSlice<O, P> {
             observations: Some(observations),
             gmcp: Vec::new(),
             lines: Vec::new(),
+            observation_lines: Vec::new(),
             prompt: P::default(),
             time: 0,
             me,
@@ -193,15 +197,70 @@ impl<A: BaseAgentState + Clone, N: Clone> TimelineState<A, N> {
     }
 }
 
-#[derive(Clone, Debug, Display, PartialEq, Deserialize)]
+// One entry in the combat feed. Linked events name the line they came from.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 pub enum BattleEvent {
     Plain(String),
     Linked(String, u32),
 }
 
+impl BattleEvent {
+    pub fn new(text: String, line: Option<u32>) -> Self {
+        match line {
+            Some(line) => BattleEvent::Linked(text, line),
+            None => BattleEvent::Plain(text),
+        }
+    }
+
+    pub fn text(&self) -> &String {
+        match self {
+            BattleEvent::Plain(text) | BattleEvent::Linked(text, _) => text,
+        }
+    }
+
+    pub fn line(&self) -> Option<u32> {
+        match self {
+            BattleEvent::Plain(_) => None,
+            BattleEvent::Linked(_, line) => Some(*line),
+        }
+    }
+}
+
+// Every feed renders events the same way, so a line number found in one is found in all.
+impl std::fmt::Display for BattleEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BattleEvent::Plain(text) => write!(f, "{}", text),
+            BattleEvent::Linked(text, line) => write!(f, "[{}] {}", line, text),
+        }
+    }
+}
+
+// A feed event, with everyone it is about, so a feed can be narrowed to one of them.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct DigestEvent {
+    pub event: BattleEvent,
+    pub agents: Vec<String>,
+}
+
+impl DigestEvent {
+    pub fn new(text: String, line: Option<u32>, agents: Vec<String>) -> Self {
+        DigestEvent {
+            event: BattleEvent::new(text, line),
+            agents,
+        }
+    }
+
+    pub fn involves(&self, who: &str) -> bool {
+        self.agents
+            .iter()
+            .any(|agent| agent.eq_ignore_ascii_case(who))
+    }
+}
+
 pub struct Timeline<O, P, A, N> {
     pub slices: Vec<TimeSlice<O, P>>,
-    pub digest: Vec<BattleEvent>,
+    pub digest: Vec<DigestEvent>,
     pub state: TimelineState<A, N>,
     pub default_agent: A,
 }
@@ -1,11 +1,18 @@
 use serde::{Deserialize, Serialize};
-use topper_aetolia::{curatives::MENTAL_AFFLICTIONS, timeline::AetTimeline, types::*};
+use topper_aetolia::{
+    curatives::MENTAL_AFFLICTIONS,
+    timeline::{get_digest_feed, AetTimeline},
+    types::*,
+};
 use yew::prelude::*;
 
 use crate::bindings::trace;
 
 use super::page::ExplainerPageMessage;
 
+// The same feed the Mudlet window shows, newest first.
+const FEED_LENGTH: usize = 8;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Mutation {
     AddAffliction(String, FType),
@@ -38,9 +45,15 @@ impl Component for StateBlock {
         let props = ctx.props();
         let me = props.timeline.state.borrow_agent(&props.me);
         let you = props.timeline.state.borrow_agent(&props.you);
+        // Only what involved the target, as in the Mudlet feed.
+        let target = Some(&props.you).filter(|you| !you.is_empty());
+        let feed = get_digest_feed(&props.timeline, target, FEED_LENGTH);
         html!(<div class="page__state">
             <PlayerState state={me} />
             <PlayerState state={you} />
+            <div class="page__state__feed">
+              {for feed.iter().map(|event| html!(<div>{event}</div>))}
+            </div>
         </div>)
     }
 }
@@ -26,11 +26,12 @@ pub fn build_time_slices(page: &ExplainerPage) -> Vec<AetTimeSlice> {
                 observations: None,
                 gmcp: Vec::new(),
                 lines: slice_lines,
+                observation_lines: Vec::new(),
                 prompt: AetPrompt::Promptless,
                 time,
                 me: me.clone(),
             };
-            slice.observations = Some(OBSERVER.observe(&slice));
+            OBSERVER.observe_into(&mut slice, None);
             slices.push(slice);
             slice_lines = Vec::new();
         } else {